@@ -0,0 +1,155 @@
+use chronorust::stats::split_stats;
+use crossterm::event;
+use std::{
+    io, thread,
+    time::{Duration, Instant, SystemTime},
+};
+
+// Frame rates `bench` can suggest, fastest first
+const FPS_STEPS: [u32; 6] = [60, 30, 20, 10, 5, 1];
+
+// `chronorust bench`: how precise the clock and the event loop really are
+// on this machine. The loop waits on crossterm's poll the way the TUI does
+// between frames, so its overshoot is the jitter the screen sees.
+pub fn run(duration: Duration, fps: u32) -> io::Result<()> {
+    let started = (Instant::now(), SystemTime::now());
+    let frame = Duration::from_secs(1) / fps;
+    println!(
+        "Measuring for {} at {} fps (a frame every {})...",
+        seconds(duration),
+        fps,
+        millis(frame)
+    );
+
+    // The smallest step the monotonic clock takes
+    let mut resolution = Duration::MAX;
+    let mut last = Instant::now();
+    for _ in 0..100_000 {
+        let now = Instant::now();
+        let step = now - last;
+        if !step.is_zero() {
+            resolution = resolution.min(step);
+        }
+        last = now;
+    }
+
+    // A poll with nothing to read, as every frame does once. Without a
+    // terminal there is nothing to poll, and frames are timed with a plain
+    // sleep instead.
+    let terminal = event::poll(Duration::ZERO).is_ok();
+    let mut polls = Vec::with_capacity(1_000);
+    for _ in 0..if terminal { 1_000 } else { 0 } {
+        let before = Instant::now();
+        poll(Duration::ZERO)?;
+        polls.push(before.elapsed());
+    }
+
+    // Waiting out whole frames; a key press ends a wait early, so that
+    // frame doesn't count
+    let mut late = Vec::new();
+    let mut keys = 0;
+    while started.0.elapsed() < duration {
+        let before = Instant::now();
+        if terminal && poll(frame)? {
+            keys += 1;
+            continue;
+        }
+        if !terminal {
+            thread::sleep(frame);
+        }
+        late.push(before.elapsed().saturating_sub(frame));
+    }
+
+    let monotonic = started.0.elapsed();
+    let wall = SystemTime::now()
+        .duration_since(started.1)
+        .unwrap_or_default();
+
+    println!();
+    println!("Timing report");
+    println!("  Clock resolution: {}", nanos(resolution));
+    match split_stats(&polls) {
+        Some(stats) => println!(
+            "  Poll latency:     mean {}, max {} (over {} polls)",
+            nanos(stats.mean),
+            nanos(stats.max),
+            stats.count
+        ),
+        None => println!("  Poll latency:     no terminal to poll; frames timed with sleep"),
+    }
+    match split_stats(&late) {
+        Some(stats) => println!(
+            "  Frame jitter:     mean {}, p99 {}, max {}, stddev {} (over {} frames)",
+            millis(stats.mean),
+            millis(percentile(&late, 99)),
+            millis(stats.max),
+            millis(stats.stddev),
+            stats.count
+        ),
+        None => println!("  Frame jitter:     no frames measured"),
+    }
+    if keys > 0 {
+        println!(
+            "                    ({} frames cut short by input left out)",
+            keys
+        );
+    }
+    let (drift, sign) = if wall >= monotonic {
+        (wall - monotonic, '+')
+    } else {
+        (monotonic - wall, '-')
+    };
+    let ppm = drift.as_secs_f64() / monotonic.as_secs_f64() * 1e6;
+    println!(
+        "  Clock drift:      system clock {}{} against Instant over {} ({}{:.1} ppm)",
+        sign,
+        millis(drift),
+        seconds(monotonic),
+        sign,
+        ppm
+    );
+
+    // The fastest rate whose frames are late by under a quarter of a frame
+    // almost every time
+    if !late.is_empty() {
+        let p99 = percentile(&late, 99);
+        let suggested = FPS_STEPS
+            .iter()
+            .copied()
+            .find(|&fps| p99 * 4 < Duration::from_secs(1) / fps)
+            .unwrap_or(1);
+        println!();
+        println!("Suggested --fps: {}", suggested);
+    }
+    Ok(())
+}
+
+// Whether input arrived, which is read and dropped so the next poll waits
+fn poll(timeout: Duration) -> io::Result<bool> {
+    if event::poll(timeout)? {
+        event::read()?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+fn percentile(samples: &[Duration], percent: usize) -> Duration {
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+    sorted[(sorted.len() - 1) * percent / 100]
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.3} s", duration.as_secs_f64())
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3} ms", duration.as_secs_f64() * 1e3)
+}
+
+fn nanos(duration: Duration) -> String {
+    match duration.as_nanos() {
+        nanos if nanos < 10_000 => format!("{} ns", nanos),
+        nanos => format!("{:.1} µs", nanos as f64 / 1e3),
+    }
+}
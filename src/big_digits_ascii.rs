@@ -0,0 +1,38 @@
+use crate::big_digits_unicode::{join_glyphs, BIG_HEIGHT};
+
+// The box-drawing font redrawn in plain ASCII for --ascii terminals, in
+// seven-segment style. Same rows and widths, so the layout doesn't change.
+fn glyph(c: char) -> Option<[&'static str; BIG_HEIGHT]> {
+    Some(match c {
+        '0' => [" _ ", "| |", "|_|"],
+        '1' => ["   ", "  |", "  |"],
+        '2' => [" _ ", " _|", "|_ "],
+        '3' => [" _ ", " _|", " _|"],
+        '4' => ["   ", "|_|", "  |"],
+        '5' => [" _ ", "|_ ", " _|"],
+        '6' => [" _ ", "|_ ", "|_|"],
+        '7' => [" _ ", "  |", "  |"],
+        '8' => [" _ ", "|_|", "|_|"],
+        '9' => [" _ ", "|_|", " _|"],
+        ':' => [" ", ".", "."],
+        '.' => [" ", " ", "."],
+        '-' => ["  ", "--", "  "],
+        ' ' => [" ", " ", " "],
+        '_' => [" ", " ", " "],
+        'W' => ["   ", "| |", "\\^/"],
+        'O' => [" _ ", "| |", "|_|"],
+        'R' => [" _ ", "|_)", "| \\"],
+        'K' => ["   ", "|/ ", "|\\ "],
+        'E' => [" _ ", "|_ ", "|_ "],
+        'S' => [" _ ", "|_ ", " _|"],
+        'T' => ["___", " | ", " | "],
+        'D' => [" _ ", "| \\", "|_/"],
+        'N' => ["   ", "|\\|", "| |"],
+        _ => return None,
+    })
+}
+
+// The ASCII counterpart of format_big_time_unicode
+pub fn format_big_time_ascii(text: &str) -> Option<Vec<String>> {
+    join_glyphs(text, glyph)
+}
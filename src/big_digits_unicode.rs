@@ -0,0 +1,61 @@
+// Three-row box-drawing font for the time display and the interval phase
+// names. Digits and letters are three cells wide, separators one; glyphs
+// are joined with a single column of space.
+pub const BIG_HEIGHT: usize = 3;
+
+fn glyph(c: char) -> Option<[&'static str; BIG_HEIGHT]> {
+    Some(match c {
+        '0' => ["┏━┓", "┃ ┃", "┗━┛"],
+        '1' => ["  ╻", "  ┃", "  ╹"],
+        '2' => ["╺━┓", "┏━┛", "┗━╸"],
+        '3' => ["╺━┓", " ━┫", "╺━┛"],
+        '4' => ["╻ ╻", "┗━┫", "  ╹"],
+        '5' => ["┏━╸", "┗━┓", "╺━┛"],
+        '6' => ["┏━╸", "┣━┓", "┗━┛"],
+        '7' => ["╺━┓", "  ┃", "  ╹"],
+        '8' => ["┏━┓", "┣━┫", "┗━┛"],
+        '9' => ["┏━┓", "┗━┫", "╺━┛"],
+        ':' => ["╻", " ", "╹"],
+        '.' => [" ", " ", "╻"],
+        '-' => ["  ", "╺╸", "  "],
+        ' ' => [" ", " ", " "],
+        // Groups microsecond digits as "mmm_uuu"
+        '_' => [" ", " ", " "],
+        // Letters of the interval phase names
+        'W' => ["╻ ╻", "┃╻┃", "┗┻┛"],
+        'O' => ["┏━┓", "┃ ┃", "┗━┛"],
+        'R' => ["┏━┓", "┣┳┛", "╹┗╸"],
+        'K' => ["╻┏╸", "┣┫ ", "╹┗╸"],
+        'E' => ["┏━╸", "┣━ ", "┗━╸"],
+        'S' => ["┏━╸", "┗━┓", "╺━┛"],
+        'T' => ["╺┳╸", " ┃ ", " ╹ "],
+        'D' => ["┳━┓", "┃ ┃", "┻━┛"],
+        'N' => ["┳┓╻", "┃┃┃", "╹┗┻"],
+        _ => return None,
+    })
+}
+
+// Renders a formatted time such as "01:02:03.456" as BIG_HEIGHT rows of
+// box-drawing digits. Returns None if the text has anything the font can't
+// draw (day counts, "TIME UP"), so the caller can fall back to plain text.
+pub fn format_big_time_unicode(text: &str) -> Option<Vec<String>> {
+    join_glyphs(text, glyph)
+}
+
+// Lays a font's glyphs side by side, or None at the first it lacks
+pub fn join_glyphs(
+    text: &str,
+    glyph: fn(char) -> Option<[&'static str; BIG_HEIGHT]>,
+) -> Option<Vec<String>> {
+    let mut rows = vec![String::new(); BIG_HEIGHT];
+    for (index, c) in text.chars().enumerate() {
+        let glyph = glyph(c)?;
+        for (row, part) in rows.iter_mut().zip(glyph) {
+            if index > 0 {
+                row.push(' ');
+            }
+            row.push_str(part);
+        }
+    }
+    Some(rows)
+}
@@ -0,0 +1,533 @@
+use crate::i18n::Lang;
+use crate::theme::{unknown_theme, Theme};
+use crate::{LapColors, SummaryFormat};
+use chronorust::distance::parse_distance;
+use chronorust::duration::parse_duration;
+use chronorust::export::ExportFormat;
+use chronorust::logging::LogFormat;
+use chronorust::project::parse_project;
+use chronorust::timefmt::TimeFormat;
+use chronorust::{validate_datetime_format, Precision, RateUnit, RoundingMode};
+use clap::{Args, Parser, Subcommand};
+use std::{path::PathBuf, str::FromStr, time::Duration};
+
+const AFTER_HELP: &str = "\
+CONTROLS:
+    ?               Show every key, including remapped ones
+    L               Record lap time
+    X / Delete      Delete the selected (or most recent) lap
+    Up / Down       Select a lap (PageUp/PageDown/Home jump; End follows new laps)
+    N               Add or edit a note on the selected lap
+    /               Filter laps by split (e.g. >01:30), Esc clears
+    R               Reset chronometer and restart
+    B               Restart the current lap from the last lap time
+    O               Reset and restart at a typed offset (e.g. 45:00)
+    U               Undo the last lap deletion or reset
+    A               Add a named countdown timer (e.g. pasta 11m)
+    Shift+P         Start a timer from the [presets] in the config file
+    [ / ]           Select previous/next timer
+    Z               Remove the selected timer
+    P               Cycle precision (seconds, milli, micro)
+    V               Toggle the laps-per-minute chart
+    T               Toggle the lap statistics panel
+    H               Toggle the lap heatmap colors
+    D               Show split times first in the lap list
+    C               Toggle the world clock (needs 'zones')
+    I               Browse past sessions (Enter shows a session's laps)
+    M               Toggle large digits for the time
+    W               Write a plain-text snapshot of the screen
+    E               Export laps as CSV, JSON or LiveSplit (see --export-format)
+    Y / Shift+Y     Copy the time / every lap to the clipboard
+    J               Start the next segment of a --session plan
+    K               Lock the controls; K then Enter unlocks
+    S               Pause/Resume chronometer
+    F               Freeze the shown time while the clock runs on
+    G               Switch the --tag project new laps and time go under
+    +               Start another timer in a new tab
+    Tab / Shift+Tab Switch to the next/previous timer tab
+    Ctrl+Z          Suspend to the shell (fg resumes; the clock keeps running)
+    Ctrl+D          Detach from the daemon, which keeps timing (attach)
+    Q               Quit application
+
+    Keys can be remapped under [keys] in ~/.config/chronorust/config.toml
+
+EXAMPLES:
+    chronorust                      # Start chronometer
+    chronorust -C                   # Start with logging enabled
+    chronorust --prestart 3         # Count -3, -2, -1 then start
+    chronorust countdown 25m        # Count down from 25 minutes
+    chronorust countdown tea        # Count down from the 'tea' preset
+    chronorust countdown 10m --overtime  # Keep counting past zero
+    chronorust alarm 07:30          # Ring at the next 07:30
+    chronorust pomodoro --work 50m  # 50 minute work phases
+    chronorust interval --work 40s --rest 20s --rounds 8
+    chronorust seq 5m 30s 10m       # Countdowns back to back
+    chronorust --session agenda.toml  # Named segments, e.g. a meeting
+    chronorust --tag client-a       # Tag laps and time, G switches
+    chronorust daemon &             # Time in the background, then:
+    chronorust ctl start            #   start, lap, status, stop or quit
+    chronorust attach               #   or watch it in the TUI; Ctrl+D detaches
+    chronorust run -- make          # Time a command, exit with its status
+    chronorust history 1            # Laps of the most recent session
+    chronorust bench --fps 60       # How steady 60 frames a second would be
+
+For more information, visit: https://github.com/stulluk/chronorust";
+
+// The about text is the Cargo.toml description
+#[derive(Parser)]
+#[command(name = "chronorust", version, about, after_help = AFTER_HELP)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub mode: Option<Mode>,
+
+    #[command(flatten)]
+    pub options: Options,
+}
+
+// What the clock does; without a subcommand it is a stopwatch
+#[derive(Subcommand)]
+pub enum Mode {
+    /// Count up from zero (the default)
+    Stopwatch,
+    /// Count down from a duration instead of up
+    Countdown {
+        /// Time to count down from (e.g. 25m or 01:30:00), or a preset name
+        #[arg(value_name = "DURATION")]
+        duration: String,
+        /// Keep counting past zero, shown as negative time
+        #[arg(long)]
+        overtime: bool,
+    },
+    /// Count down to a time of day, then ring
+    Alarm {
+        /// Time of day (07:30 or 07:30:15), today or else tomorrow
+        #[arg(
+            value_name = "TIME",
+            required_unless_present = "at",
+            conflicts_with = "at"
+        )]
+        time: Option<String>,
+        /// Date and time instead (2024-06-01 14:00)
+        #[arg(long, value_name = "DATETIME")]
+        at: Option<String>,
+    },
+    /// Alternate work and break phases with a bell
+    Pomodoro {
+        /// Work phase length
+        #[arg(long, value_name = "D", default_value = "25m", value_parser = positive_duration)]
+        work: Duration,
+        /// Break phase length
+        #[arg(long = "break", value_name = "D", default_value = "5m", value_parser = positive_duration)]
+        rest: Duration,
+    },
+    /// Tabata-style work/rest rounds with cues before each change
+    Interval {
+        /// Work phase length
+        #[arg(long, value_name = "D", default_value = "20s", value_parser = positive_duration)]
+        work: Duration,
+        /// Rest between rounds
+        #[arg(long, value_name = "D", default_value = "10s", value_parser = parse_duration)]
+        rest: Duration,
+        /// Number of work phases
+        #[arg(long, value_name = "N", default_value = "8", value_parser = positive::<u32>)]
+        rounds: u32,
+    },
+    /// Run countdowns back to back, with a bell as each one ends
+    Seq {
+        /// Segment lengths (e.g. 5m 30s 10m) or preset names, in order
+        #[arg(value_name = "DURATION", required = true)]
+        durations: Vec<String>,
+    },
+    /// List past sessions, or the laps of one of them
+    History {
+        /// Session to show, 1 being the most recent
+        #[arg(value_name = "SESSION")]
+        number: Option<usize>,
+    },
+    /// Measure clock resolution, poll latency, frame jitter and clock drift
+    Bench {
+        /// How long to measure for
+        #[arg(long, value_name = "D", default_value = "5s", value_parser = positive_duration)]
+        duration: Duration,
+    },
+    /// Run the stopwatch in the background, controlled through a Unix socket
+    Daemon,
+    /// Send a command to a running daemon and print its answer
+    Ctl {
+        /// start, stop (pauses), pause, resume, lap, reset, status, state or quit
+        #[arg(
+            value_name = "COMMAND",
+            value_parser = ["start", "stop", "pause", "resume", "lap", "reset", "status", "state", "quit"]
+        )]
+        command: String,
+    },
+    /// Show a running daemon's session in the TUI; Ctrl+D detaches
+    Attach,
+    /// Time a command, like time(1), and exit with its status
+    Run {
+        /// Command and its arguments; put -- before it if it starts with a dash
+        #[arg(
+            value_name = "COMMAND",
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        command: Vec<String>,
+    },
+}
+
+// Options shared by every mode; global so they also go after the subcommand
+#[derive(Args)]
+pub struct Options {
+    /// Enable session logging
+    #[arg(short = 'C', long = "logging", global = true)]
+    pub logging: bool,
+
+    /// Log to PATH instead of a generated file name (implies -C)
+    #[arg(long, value_name = "PATH", global = true)]
+    pub log_file: Option<PathBuf>,
+
+    /// Log as readable text or as JSON lines (text, jsonl)
+    #[arg(long, value_name = "F", default_value = "text", value_parser = log_format, global = true)]
+    pub log_format: LogFormat,
+
+    /// Write the log and snapshots to DIR (created if needed)
+    #[arg(long, value_name = "DIR", global = true)]
+    pub output_dir: Option<PathBuf>,
+
+    /// Count down N seconds before starting (up to a day)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(0..=86_400), global = true)]
+    pub prestart: Option<u64>,
+
+    /// Count up from a past local time (2024-01-01 09:00:00)
+    #[arg(long, value_name = "DATETIME", global = true)]
+    pub since: Option<String>,
+
+    /// Resume from and save the session to a binary file
+    #[arg(long, value_name = "PATH", global = true)]
+    pub binary_state: Option<PathBuf>,
+
+    /// Run through the named segments of a plan file
+    #[arg(long, value_name = "PATH", global = true)]
+    pub session: Option<PathBuf>,
+
+    /// Offer to resume the last session, saved on quit
+    #[arg(long, global = true)]
+    pub resume: bool,
+
+    /// Don't add this session to the history or the daily totals
+    #[arg(long, global = true)]
+    pub no_history: bool,
+
+    /// Show lap rate per 'min' or 'hour'
+    #[arg(long, value_name = "U", default_value = "min", value_parser = rate_unit, global = true)]
+    pub rate_unit: RateUnit,
+
+    /// Print a single self-updating line (e.g. for tmux)
+    #[arg(long, conflicts_with = "no_tui", global = true)]
+    pub line: bool,
+
+    /// Redraw at most N times a second (1-60)
+    #[arg(long, value_name = "N", default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..=60), global = true)]
+    pub fps: u32,
+
+    /// Run headless: SIGUSR1 laps, Ctrl+C prints the total
+    #[arg(long, global = true)]
+    pub no_tui: bool,
+
+    /// Print the status this often with --no-tui
+    #[arg(long, value_name = "D", requires = "no_tui", value_parser = positive_duration, global = true)]
+    pub interval: Option<Duration>,
+
+    /// Flash the title border on every beat at N BPM
+    #[arg(long, value_name = "N", value_parser = positive::<u32>, global = true)]
+    pub bpm: Option<u32>,
+
+    /// Beep on metronome beats and prestart seconds
+    #[arg(long, global = true)]
+    pub beep: bool,
+
+    /// Desktop notification when a countdown, timer or pomodoro phase ends
+    #[arg(long, global = true)]
+    pub notify: bool,
+
+    /// Run event hooks from DIR instead of ~/.config/chronorust/hooks
+    #[arg(long, value_name = "DIR", conflicts_with = "no_hooks", global = true)]
+    pub hooks_dir: Option<PathBuf>,
+
+    /// Don't run event hooks
+    #[arg(long, global = true)]
+    pub no_hooks: bool,
+
+    /// Play a WAV/OGG file instead of the bell for alarms, and on every lap
+    #[arg(long, value_name = "FILE", value_parser = existing_file, global = true)]
+    pub sound: Option<PathBuf>,
+
+    /// Auto-pause after D without key presses (e.g. 5m)
+    #[arg(long, value_name = "D", value_parser = parse_duration, global = true)]
+    pub idle_pause: Option<Duration>,
+
+    /// Compare every lap against a target pace (e.g. 01:00)
+    #[arg(long, value_name = "D", value_parser = parse_duration, global = true)]
+    pub pace: Option<Duration>,
+
+    /// Record a lap every D of elapsed time (e.g. 1m)
+    #[arg(long, value_name = "D", value_parser = positive_duration, global = true)]
+    pub auto_lap: Option<Duration>,
+
+    /// Limit the total pause time (e.g. 05:00)
+    #[arg(long, value_name = "D", value_parser = parse_duration, global = true)]
+    pub pause_budget: Option<Duration>,
+
+    /// A goal time for the session; shows how far ahead or behind you are
+    #[arg(long, value_name = "D", value_parser = positive_duration, global = true)]
+    pub target: Option<Duration>,
+
+    /// Project laps left before a target total time
+    #[arg(long, value_name = "D", alias = "projection", value_parser = projection, global = true)]
+    pub project: Option<Duration>,
+
+    /// With --project, show the pace needed for N laps
+    #[arg(long, value_name = "N", requires = "project", value_parser = positive::<usize>, global = true)]
+    pub project_laps: Option<usize>,
+
+    /// Tag laps and time with a project, switched with G
+    #[arg(long, value_name = "NAME", value_parser = parse_project, global = true)]
+    pub tag: Option<String>,
+
+    /// Review the laps of an earlier session's log or lap CSV, read-only
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["binary_state", "resume", "since", "prestart", "session", "no_tui", "line"],
+        global = true
+    )]
+    pub import: Option<PathBuf>,
+
+    /// Compare laps against an earlier JSON or CSV lap export (ghost columns)
+    #[arg(long, alias = "reference", value_name = "FILE", global = true)]
+    pub compare: Option<PathBuf>,
+
+    /// Exclude the first N warm-up laps from stats
+    #[arg(long, value_name = "N", default_value_t = 0, global = true)]
+    pub skip_laps: usize,
+
+    /// Timestamps in the log, history and world clock (iso, locale, 12h)
+    #[arg(long, value_name = "F", default_value = "iso", value_parser = time_format, global = true)]
+    pub time_format: TimeFormat,
+
+    /// strftime format for log and history timestamps (e.g. %FT%T%:z)
+    #[arg(long, value_name = "F", value_parser = datetime_format, global = true)]
+    pub datetime_format: Option<String>,
+
+    /// Show distance, speed and pace/km for D per lap (400m, 1mi)
+    #[arg(long, value_name = "D", value_parser = parse_distance, global = true)]
+    pub lap_distance: Option<f64>,
+
+    /// Show 'sec', 'milli' or 'micro' seconds (also s, ms, us)
+    #[arg(long, value_name = "P", default_value = "milli", value_parser = precision, global = true)]
+    pub precision: Precision,
+
+    /// 'truncate' or round to 'nearest' digit
+    #[arg(long, value_name = "M", default_value = "truncate", value_parser = rounding, global = true)]
+    pub rounding: RoundingMode,
+
+    /// Print a 'json' or 'kv' summary line on exit
+    #[arg(long, value_name = "F", value_parser = summary_format, global = true)]
+    pub summary_format: Option<SummaryFormat>,
+
+    /// Write 'csv', 'json' or LiveSplit 'lss' exports with E
+    #[arg(long, value_name = "F", default_value = "csv", value_parser = export_format, global = true)]
+    pub export_format: ExportFormat,
+
+    /// Same as --export-format lss
+    #[arg(long, global = true)]
+    pub export_lss: bool,
+
+    /// Name the laps after the segments of a LiveSplit .lss file
+    #[arg(long, value_name = "LSS", global = true)]
+    pub segments: Option<PathBuf>,
+
+    /// Serve the status API on a port or address (needs the 'http' feature)
+    #[arg(long, visible_alias = "serve", value_name = "ADDR", global = true)]
+    pub http: Option<String>,
+
+    /// Keep PATH holding the time as shown, e.g. for an OBS text source
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["no_tui", "line"], global = true)]
+    pub write_current: Option<PathBuf>,
+
+    /// Rewrite --write-current at most every N milliseconds
+    #[arg(long, value_name = "MS", default_value_t = 250, requires = "write_current", value_parser = clap::value_parser!(u64).range(10..=60_000), global = true)]
+    pub write_interval: u64,
+
+    /// Socket for daemon, ctl and attach [default: $XDG_RUNTIME_DIR/chronorust.sock]
+    #[arg(long, value_name = "PATH", global = true)]
+    pub socket: Option<PathBuf>,
+
+    /// Broadcast the timer as JSON over a WebSocket on localhost:PORT
+    #[arg(long, value_name = "PORT", conflicts_with_all = ["no_tui", "line"], global = true)]
+    pub ws_port: Option<u16>,
+
+    /// Show the time in comma-separated zones (needs 'zones')
+    #[arg(long, value_name = "LIST", global = true)]
+    pub zones: Option<String>,
+
+    /// Window size for the laps chart
+    #[arg(long, value_name = "D", default_value = "1m", value_parser = positive_duration, global = true)]
+    pub bucket: Duration,
+
+    /// Use plain ASCII instead of emoji and box drawing
+    #[arg(long, conflicts_with = "unicode", global = true)]
+    pub ascii: bool,
+
+    /// Use Unicode glyphs even without a UTF-8 locale
+    #[arg(long, global = true)]
+    pub unicode: bool,
+
+    /// Language of the screen and exit report: en or tr [default: from LC_ALL, LC_MESSAGES or LANG]
+    #[arg(long, value_name = "LANG", value_parser = lang, global = true)]
+    pub lang: Option<Lang>,
+
+    /// Color theme: dark (default), light, solarized, high-contrast or monochrome
+    #[arg(long, value_name = "NAME", value_parser = theme, global = true)]
+    pub theme: Option<Theme>,
+
+    /// Disable colors (also honors NO_COLOR)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Start with the lap heatmap colors off (H toggles)
+    #[arg(long, global = true)]
+    pub no_heatmap: bool,
+
+    /// Lap colors H toggles: a 'heat' gradient or 'pace' against the average
+    #[arg(long, value_name = "M", default_value = "heat", value_parser = lap_colors, global = true)]
+    pub lap_colors: LapColors,
+
+    /// Splits within PCT% of the average count as even with --lap-colors pace
+    #[arg(long, value_name = "PCT", default_value_t = 5.0, value_parser = percent, global = true)]
+    pub lap_tolerance: f64,
+
+    /// Leave the mouse to the terminal, e.g. for selecting text
+    #[arg(long, global = true)]
+    pub no_mouse: bool,
+
+    /// Start with the large box-drawing digits (M toggles)
+    #[arg(long, global = true)]
+    pub big: bool,
+
+    /// Reset and quit without asking, even with laps recorded
+    #[arg(long, global = true)]
+    pub no_confirm: bool,
+
+    /// Start with the controls locked; K then Enter unlocks
+    #[arg(long, global = true)]
+    pub locked: bool,
+
+    /// Start with split times first in the lap list (D toggles)
+    #[arg(long, global = true)]
+    pub splits: bool,
+}
+
+pub fn positive_duration(value: &str) -> Result<Duration, String> {
+    match parse_duration(value)? {
+        duration if duration.is_zero() => Err("must be greater than zero".to_string()),
+        duration => Ok(duration),
+    }
+}
+
+fn positive<T: FromStr + Default + PartialEq>(value: &str) -> Result<T, String> {
+    match value.parse() {
+        Ok(number) if number != T::default() => Ok(number),
+        _ => Err("expected a whole number greater than zero".to_string()),
+    }
+}
+
+fn existing_file(value: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(value);
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err("no such file".to_string())
+    }
+}
+
+fn theme(value: &str) -> Result<Theme, String> {
+    Theme::named(value).ok_or_else(|| unknown_theme(value))
+}
+
+fn lang(value: &str) -> Result<Lang, String> {
+    Lang::parse(value).ok_or_else(|| "expected 'en' or 'tr'".to_string())
+}
+
+fn rate_unit(value: &str) -> Result<RateUnit, String> {
+    RateUnit::parse(value).ok_or_else(|| "expected 'min' or 'hour'".to_string())
+}
+
+// --project takes a target time; a name is most likely meant as a tag
+fn projection(value: &str) -> Result<Duration, String> {
+    parse_duration(value).map_err(|err| {
+        if value.chars().any(|c| c.is_ascii_alphabetic()) && parse_project(value).is_ok() {
+            format!("{}; project tags are set with --tag {}", err, value)
+        } else {
+            err
+        }
+    })
+}
+
+fn time_format(value: &str) -> Result<TimeFormat, String> {
+    TimeFormat::parse(value).ok_or_else(|| "expected 'iso', 'locale' or '12h'".to_string())
+}
+
+fn datetime_format(value: &str) -> Result<String, String> {
+    validate_datetime_format(value)?;
+    Ok(value.to_string())
+}
+
+fn precision(value: &str) -> Result<Precision, String> {
+    match value {
+        "sec" | "s" => Ok(Precision::Seconds),
+        "milli" | "ms" => Ok(Precision::Millis),
+        "micro" | "us" => Ok(Precision::Micro),
+        _ => Err("expected 'sec', 'milli' or 'micro'".to_string()),
+    }
+}
+
+fn rounding(value: &str) -> Result<RoundingMode, String> {
+    match value {
+        "truncate" => Ok(RoundingMode::Truncate),
+        "nearest" => Ok(RoundingMode::Nearest),
+        _ => Err("expected 'truncate' or 'nearest'".to_string()),
+    }
+}
+
+fn summary_format(value: &str) -> Result<SummaryFormat, String> {
+    match value {
+        "json" => Ok(SummaryFormat::Json),
+        "kv" => Ok(SummaryFormat::Kv),
+        _ => Err("expected 'json' or 'kv'".to_string()),
+    }
+}
+
+fn lap_colors(value: &str) -> Result<LapColors, String> {
+    match value {
+        "heat" => Ok(LapColors::Heat),
+        "pace" => Ok(LapColors::Pace),
+        _ => Err("expected 'heat' or 'pace'".to_string()),
+    }
+}
+
+fn percent(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(percent) if (0.0..=100.0).contains(&percent) => Ok(percent),
+        _ => Err("expected a percentage from 0 to 100".to_string()),
+    }
+}
+
+fn export_format(value: &str) -> Result<ExportFormat, String> {
+    ExportFormat::parse(value).ok_or_else(|| "expected 'csv', 'json' or 'lss'".to_string())
+}
+
+fn log_format(value: &str) -> Result<LogFormat, String> {
+    LogFormat::parse(value).ok_or_else(|| "expected 'text' or 'jsonl'".to_string())
+}
@@ -0,0 +1,102 @@
+use std::{
+    env,
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+// Where copied text ended up, for the status line
+pub enum Copied {
+    // A clipboard tool took it
+    Tool,
+    // Handed to the terminal as an OSC 52 escape; whether it honours it
+    // can't be told from here
+    Terminal,
+}
+
+// Copies through the platform's clipboard tool, so no clipboard library is
+// linked in. Over SSH that would fill the remote machine's clipboard, so
+// the terminal is asked instead, as it is when no tool works.
+pub fn copy(text: &str) -> io::Result<Copied> {
+    let remote = env::var_os("SSH_TTY").is_some() || env::var_os("SSH_CONNECTION").is_some();
+    if !remote {
+        for &(program, args) in platform_tools() {
+            if run_tool(program, args, text) {
+                return Ok(Copied::Tool);
+            }
+        }
+    }
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    stdout.flush()?;
+    Ok(Copied::Terminal)
+}
+
+fn run_tool(program: &str, args: &[&str], text: &str) -> bool {
+    let child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(mut child) = child else {
+        return false;
+    };
+    // Dropping stdin closes it, so the tool sees the end of the text
+    let written = child
+        .stdin
+        .take()
+        .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    child.wait().is_ok_and(|status| status.success()) && written
+}
+
+// Wayland first, since X tools only reach XWayland's clipboard there
+#[cfg(all(unix, not(target_os = "macos")))]
+fn platform_tools() -> &'static [(&'static str, &'static [&'static str])] {
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        &[
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    } else {
+        &[
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn platform_tools() -> &'static [(&'static str, &'static [&'static str])] {
+    &[("pbcopy", &[])]
+}
+
+#[cfg(windows)]
+fn platform_tools() -> &'static [(&'static str, &'static [&'static str])] {
+    &[("clip", &[])]
+}
+
+#[cfg(not(any(unix, windows)))]
+fn platform_tools() -> &'static [(&'static str, &'static [&'static str])] {
+    &[]
+}
+
+// Standard padded base64, which OSC 52 carries the text in
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | u32::from(byte) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                encoded.push(ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
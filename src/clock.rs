@@ -0,0 +1,42 @@
+// Where the library reads the time. Under test it's a clock each test
+// thread moves by hand with advance(), so timing checks come out exact.
+#[cfg(not(test))]
+use std::time::{Instant, SystemTime};
+
+#[cfg(not(test))]
+pub fn now() -> Instant {
+    Instant::now()
+}
+
+#[cfg(not(test))]
+pub fn system_now() -> SystemTime {
+    SystemTime::now()
+}
+
+#[cfg(test)]
+pub use mock::{advance, now, system_now};
+
+#[cfg(test)]
+mod mock {
+    use std::{
+        cell::Cell,
+        time::{Duration, Instant, SystemTime},
+    };
+
+    thread_local! {
+        static ORIGIN: (Instant, SystemTime) = (Instant::now(), SystemTime::now());
+        static OFFSET: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+    }
+
+    pub fn now() -> Instant {
+        ORIGIN.with(|origin| origin.0) + OFFSET.with(Cell::get)
+    }
+
+    pub fn system_now() -> SystemTime {
+        ORIGIN.with(|origin| origin.1) + OFFSET.with(Cell::get)
+    }
+
+    pub fn advance(by: Duration) {
+        OFFSET.with(|offset| offset.set(offset.get() + by));
+    }
+}
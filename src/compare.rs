@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use crate::duration::parse_duration;
+use crate::export::parse_lap_csv;
+
+// How one lap went against the same lap of a reference session. Deltas are
+// true when ahead, like the pace and target deltas.
+#[derive(Clone, Copy)]
+pub struct LapComparison {
+    // The reference lap's cumulative time
+    pub reference: Duration,
+    pub delta: (Duration, bool),
+    // The lap's own split against the reference lap's split
+    pub split_delta: (Duration, bool),
+}
+
+impl LapComparison {
+    // A lap faster than the reference's own lap. A single recorded attempt's
+    // splits are also its best segments, so this is a gold split the way
+    // LiveSplit colors one, whether the run is ahead overall or not.
+    pub fn is_gold(&self) -> bool {
+        self.split_delta.1 && !self.split_delta.0.is_zero()
+    }
+}
+
+// Lap `index` of the cumulative `laps` against the reference's
+pub fn compare_lap(
+    reference: &[Duration],
+    laps: &[Duration],
+    index: usize,
+) -> Option<LapComparison> {
+    let cumulative = *reference.get(index)?;
+    let actual = *laps.get(index)?;
+    let previous = |laps: &[Duration]| index.checked_sub(1).map_or(Duration::ZERO, |i| laps[i]);
+    Some(LapComparison {
+        reference: cumulative,
+        delta: difference(actual, cumulative),
+        split_delta: difference(
+            actual.saturating_sub(previous(laps)),
+            cumulative.saturating_sub(previous(reference)),
+        ),
+    })
+}
+
+// The cumulative lap times of a JSON or CSV lap export, told apart by the
+// JSON export's opening brace
+pub fn parse_reference(text: &str) -> Result<Vec<Duration>, String> {
+    if text.trim_start().starts_with('{') {
+        parse_lap_json(text)
+    } else {
+        parse_lap_csv(text)
+    }
+}
+
+// The "laps" objects of --export-format json. The formatted `cumulative`
+// is finer than `cumulative_ms` under --precision micro and coarser under
+// --precision sec, so it's used when the two agree to the millisecond.
+fn parse_lap_json(text: &str) -> Result<Vec<Duration>, String> {
+    let (_, laps) = text
+        .split_once("\"laps\"")
+        .ok_or_else(|| "no \"laps\" array".to_string())?;
+    let mut cumulative = Vec::new();
+    for (index, object) in laps.split('{').skip(1).enumerate() {
+        let lap = index + 1;
+        let object = object.split('}').next().unwrap_or(object);
+        let millis = field(object, "cumulative_ms")
+            .map(|millis| {
+                millis.parse().map_err(|_| {
+                    format!("lap {}: '{}' is not a number of milliseconds", lap, millis)
+                })
+            })
+            .transpose()?;
+        let formatted = field(object, "cumulative")
+            .map(|formatted| {
+                parse_duration(formatted.trim_matches('"'))
+                    .map_err(|err| format!("lap {}: {}", lap, err))
+            })
+            .transpose()?;
+        let time = match (formatted, millis) {
+            (Some(formatted), Some(millis)) if formatted.as_millis() != u128::from(millis) => {
+                Duration::from_millis(millis)
+            }
+            (Some(formatted), _) => formatted,
+            (None, Some(millis)) => Duration::from_millis(millis),
+            (None, None) => return Err(format!("lap {}: no cumulative time", lap)),
+        };
+        if cumulative.last().is_some_and(|&previous| time < previous) {
+            return Err(format!("lap {}: cumulative time goes backwards", lap));
+        }
+        cumulative.push(time);
+    }
+    Ok(cumulative)
+}
+
+// The raw value of `"key":` in a flat JSON object, up to the next comma
+fn field<'a>(object: &'a str, key: &str) -> Option<&'a str> {
+    let (_, rest) = object.split_once(&format!("\"{}\"", key))?;
+    let rest = rest.trim_start().strip_prefix(':')?;
+    Some(rest.split(',').next().unwrap_or(rest).trim())
+}
+
+fn difference(actual: Duration, reference: Duration) -> (Duration, bool) {
+    if actual <= reference {
+        (reference - actual, true)
+    } else {
+        (actual - reference, false)
+    }
+}
@@ -0,0 +1,210 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+// The subset of TOML the config file uses: [tables] holding `name = "value"`
+// string entries or one-line `name = ["a", "b"]` arrays, with # comments.
+// Entries before the first table header are top-level, kept in a table
+// named "". Entries keep their file order.
+#[derive(Default)]
+pub struct Config {
+    tables: Vec<(String, Vec<(String, Value)>)>,
+}
+
+enum Value {
+    String(String),
+    Array(Vec<String>),
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut config = Config::default();
+        for (index, raw) in text.lines().enumerate() {
+            let line = index + 1;
+            let row = strip_comment(raw).trim();
+            if row.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = row.strip_prefix('[') {
+                let name = name
+                    .strip_suffix(']')
+                    .ok_or_else(|| format!("line {}: unclosed table header", line))?
+                    .trim();
+                if name.is_empty() {
+                    return Err(format!("line {}: empty table name", line));
+                }
+                config.tables.push((name.to_string(), Vec::new()));
+                continue;
+            }
+
+            let (name, value) = row
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected name = \"value\"", line))?;
+            let name = name.trim().trim_matches('"');
+            let value = value.trim();
+            let value = match value.strip_prefix('[') {
+                Some(items) => items
+                    .strip_suffix(']')
+                    .and_then(parse_array)
+                    .map(Value::Array),
+                None => value
+                    .strip_prefix('"')
+                    .and_then(|value| value.strip_suffix('"'))
+                    .map(|value| Value::String(value.to_string())),
+            }
+            .ok_or_else(|| {
+                format!(
+                    "line {}: value for '{}' must be a quoted string or an array of them",
+                    line, name
+                )
+            })?;
+            if config.tables.is_empty() {
+                config.tables.push((String::new(), Vec::new()));
+            }
+            let (_, entries) = config.tables.last_mut().unwrap();
+            entries.push((name.to_string(), value));
+        }
+        Ok(config)
+    }
+
+    // A missing file is the same as an empty one
+    pub fn load(path: &Path) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::parse(&text),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+
+    // For files named on the command line, which have to be there
+    pub fn load_existing(path: &Path) -> Result<Self, String> {
+        Self::parse(&fs::read_to_string(path).map_err(|err| err.to_string())?)
+    }
+
+    // String entries of every [name] table, in file order
+    pub fn table(&self, name: &str) -> Result<Vec<(&str, &str)>, String> {
+        self.entries(name)
+            .into_iter()
+            .map(|(key, value)| match value {
+                Value::String(value) => Ok((key, value.as_str())),
+                Value::Array(_) => Err(format!("'{}' must be a quoted string", key)),
+            })
+            .collect()
+    }
+
+    // The last `key` array in [table], or at the top level for ""
+    pub fn array(&self, table: &str, key: &str) -> Result<Option<Vec<&str>>, String> {
+        match self
+            .entries(table)
+            .into_iter()
+            .rev()
+            .find(|&(name, _)| name == key)
+        {
+            Some((_, Value::Array(items))) => Ok(Some(items.iter().map(String::as_str).collect())),
+            Some((_, Value::String(_))) => {
+                Err(format!("'{}' must be an array of quoted strings", key))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // The last `key` string in [table], for tables that also hold arrays
+    pub fn string(&self, table: &str, key: &str) -> Result<Option<&str>, String> {
+        match self
+            .entries(table)
+            .into_iter()
+            .rev()
+            .find(|&(name, _)| name == key)
+        {
+            Some((_, Value::String(value))) => Ok(Some(value.as_str())),
+            Some((_, Value::Array(_))) => Err(format!("'{}' must be a quoted string", key)),
+            None => Ok(None),
+        }
+    }
+
+    fn entries(&self, table: &str) -> Vec<(&str, &Value)> {
+        self.tables
+            .iter()
+            .filter(|(name, _)| name == table)
+            .flat_map(|(_, entries)| entries)
+            .map(|(key, value)| (key.as_str(), value))
+            .collect()
+    }
+}
+
+// The inside of `["a", "b"]`; a trailing comma is allowed
+fn parse_array(items: &str) -> Option<Vec<String>> {
+    let mut values = Vec::new();
+    let mut rest = items.trim();
+    while !rest.is_empty() {
+        let (value, tail) = rest.strip_prefix('"')?.split_once('"')?;
+        values.push(value.to_string());
+        let tail = tail.trim_start();
+        rest = match tail.strip_prefix(',') {
+            Some(tail) => tail.trim_start(),
+            None if tail.is_empty() => tail,
+            None => return None,
+        };
+    }
+    Some(values)
+}
+
+// $XDG_CONFIG_HOME/chronorust, falling back to ~/.config
+fn config_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("chronorust"))
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+// Event hooks live next to the config file
+pub fn hooks_dir() -> Option<PathBuf> {
+    Some(config_dir()?.join("hooks"))
+}
+
+// And the Rhai script, beside them
+#[cfg(feature = "scripting")]
+pub fn hooks_script() -> Option<PathBuf> {
+    Some(config_dir()?.join("hooks.rhai"))
+}
+
+// $XDG_DATA_HOME/chronorust, falling back to ~/.local/share, for state
+// that outlives a run
+pub fn data_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+    Some(base.join("chronorust"))
+}
+
+// Where `chronorust daemon` listens: $XDG_RUNTIME_DIR/chronorust.sock,
+// falling back to the data directory
+pub fn socket_path() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR")
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| PathBuf::from(dir).join("chronorust.sock"))
+        .or_else(|| Some(data_dir()?.join("chronorust.sock")))
+}
+
+// A # inside a quoted value isn't a comment
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (index, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..index],
+            _ => {}
+        }
+    }
+    line
+}
@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+
+use crate::duration::parse_duration;
+
+// Named durations from the [presets] config table, e.g. `tea = "3m"`, in
+// file order
+pub fn parse_presets(entries: &[(&str, &str)]) -> Result<Vec<(String, Duration)>, String> {
+    entries
+        .iter()
+        .map(|&(name, value)| match parse_duration(value) {
+            Ok(duration) if !duration.is_zero() => Ok((name.to_string(), duration)),
+            Ok(_) => Err(format!("preset '{}' must be longer than zero", name)),
+            Err(err) => Err(format!("preset '{}': {}", name, err)),
+        })
+        .collect()
+}
+
+// A named egg-timer that runs on wall time, independent of the stopwatch
+pub struct Countdown {
+    pub name: String,
+    pub duration: Duration,
+    started: Instant,
+    pub done: bool,
+}
+
+impl Countdown {
+    pub fn new(name: String, duration: Duration) -> Self {
+        Self {
+            name,
+            duration,
+            started: Instant::now(),
+            done: false,
+        }
+    }
+
+    // Parses "pasta 11m": the last word is the duration, the rest the name
+    pub fn parse(spec: &str, fallback_name: String) -> Result<Self, String> {
+        let spec = spec.trim();
+        let (name, duration) = match spec.rsplit_once(char::is_whitespace) {
+            Some((name, duration)) => (name.trim().to_string(), duration),
+            None => (fallback_name, spec),
+        };
+        let duration = parse_duration(duration)?;
+        if duration.is_zero() {
+            return Err("timer duration must be greater than zero".to_string());
+        }
+        Ok(Self::new(name, duration))
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.duration.saturating_sub(self.started.elapsed())
+    }
+
+    pub fn progress(&self) -> f64 {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        (elapsed / self.duration.as_secs_f64()).min(1.0)
+    }
+
+    // Returns true exactly once, on the tick where the timer runs out
+    pub fn tick(&mut self) -> bool {
+        if !self.done && self.remaining().is_zero() {
+            self.done = true;
+            return true;
+        }
+        false
+    }
+}
@@ -0,0 +1,44 @@
+use std::{
+    ffi::OsString,
+    fs, io,
+    path::Path,
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::App;
+
+// --write-current: a file that always holds the selected timer's time as
+// shown, for streaming software such as OBS's "Text (from file)" source.
+// A thread of its own rewrites it, so a slow disk never holds up a frame,
+// and only when the text changed. Each version goes to a temporary file
+// next to it that is renamed over it, so a reader never sees half a time.
+pub fn spawn(path: &Path, interval: Duration, timers: Arc<Mutex<App>>) -> io::Result<()> {
+    let mut name = OsString::from(".");
+    name.push(path.file_name().unwrap_or(path.as_os_str()));
+    name.push(".tmp");
+    let temporary = path.with_file_name(name);
+
+    // Written once up front so a bad path fails before the TUI starts
+    let mut last = timers.lock().unwrap().active().display();
+    replace(path, &temporary, &last)?;
+
+    let path = path.to_path_buf();
+    thread::spawn(move || {
+        loop {
+            thread::sleep(interval);
+            let text = timers.lock().unwrap().active().display();
+            // A failed write is tried again on the next change
+            if text != last && replace(&path, &temporary, &text).is_ok() {
+                last = text;
+            }
+        }
+    });
+    Ok(())
+}
+
+fn replace(path: &Path, temporary: &Path, text: &str) -> io::Result<()> {
+    fs::write(temporary, text)?;
+    fs::rename(temporary, path)
+}
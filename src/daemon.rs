@@ -0,0 +1,169 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use chronorust::{
+    session::{self, SessionState},
+    Chronometer,
+};
+
+// How long a client gets to send its command, so one that connects and
+// says nothing doesn't hold the clock up
+const READ_TIMEOUT: Duration = Duration::from_secs(1);
+
+// `chronorust daemon`: the stopwatch with no terminal at all, so it
+// outlives the one it was started from. Each connection to the Unix socket
+// sends one command and gets one line back; `chronorust ctl`, scripts and
+// window manager keybindings all talk to it that way. SIGINT and SIGTERM
+// end it like `quit`, which is how systemd stops it.
+pub fn run(chronometer: &mut Chronometer, path: &Path) -> io::Result<()> {
+    let listener = bind(path)?;
+    listener.set_nonblocking(true)?;
+    let stop = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&stop))?;
+    signal_hook::flag::register(signal_hook::consts::SIGTERM, Arc::clone(&stop))?;
+    println!("chronorust daemon listening on {}", path.display());
+
+    let result = serve(chronometer, &listener, &stop);
+    let _ = fs::remove_file(path);
+    result
+}
+
+fn serve(
+    chronometer: &mut Chronometer,
+    listener: &UnixListener,
+    stop: &AtomicBool,
+) -> io::Result<()> {
+    while !stop.load(Ordering::Relaxed) {
+        chronometer.tick();
+        match listener.accept() {
+            // A client that goes away mid-command only loses its answer
+            Ok((stream, _)) => {
+                if let Ok(true) = answer(chronometer, stream) {
+                    break;
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(50));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+// A socket left behind by a daemon that died is taken over; one that still
+// answers belongs to a daemon that is running
+fn bind(path: &Path) -> io::Result<UnixListener> {
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                "another daemon is already listening there",
+            ));
+        }
+        fs::remove_file(path)?;
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    UnixListener::bind(path)
+}
+
+// Reads one command and writes its answer; true when it was `quit`
+fn answer(chronometer: &mut Chronometer, stream: UnixStream) -> io::Result<bool> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    chronometer.tick();
+    let (reply, quit) = respond(chronometer, line.trim());
+    writeln!(&stream, "{}", reply)?;
+    Ok(quit)
+}
+
+// The stdin mode's commands, plus `start` and `stop` for a clock that waits
+// to be started. Failures start with "error:" for clients to tell apart.
+fn respond(chronometer: &mut Chronometer, command: &str) -> (String, bool) {
+    let result = match command {
+        "start" if chronometer.is_paused() => chronometer.resume(),
+        "start" => chronometer.start(),
+        "stop" | "pause" => chronometer.pause(),
+        "resume" => chronometer.resume(),
+        "lap" if chronometer.is_started() => {
+            chronometer.lap();
+            let reply = format!(
+                "lap {} {}",
+                chronometer.lap_ids.last().copied().unwrap_or_default(),
+                chronometer
+                    .lap_durations
+                    .last()
+                    .map_or(String::new(), |&lap| chronometer.format_duration(lap))
+            );
+            return (reply, false);
+        }
+        "lap" => return ("error: cannot lap: not started".to_string(), false),
+        "reset" => {
+            chronometer.reset();
+            Ok(())
+        }
+        "status" => Ok(()),
+        // The whole session for `chronorust attach`, as --resume saves it
+        "state" if chronometer.is_started() => {
+            let json = session::state_json(&chronometer.session_state());
+            return (json.trim_end().to_string(), false);
+        }
+        "state" => Ok(()),
+        "quit" => {
+            let reply = format!(
+                "stopped {} laps={}",
+                chronometer.display(),
+                chronometer.lap_durations.len()
+            );
+            return (reply, true);
+        }
+        _ => return (format!("error: unknown command '{}'", command), false),
+    };
+    match result {
+        Ok(()) => (status(chronometer), false),
+        Err(err) => (format!("error: cannot {}: {}", command, err), false),
+    }
+}
+
+fn status(chronometer: &Chronometer) -> String {
+    format!(
+        "{} {} laps={}",
+        chronometer.state_label(),
+        chronometer.display(),
+        chronometer.lap_durations.len()
+    )
+}
+
+// `chronorust ctl`: sends one command to the daemon and returns its answer
+pub fn send(path: &Path, command: &str) -> io::Result<String> {
+    let mut stream = UnixStream::connect(path)?;
+    writeln!(stream, "{}", command)?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    Ok(reply.trim_end().to_string())
+}
+
+// The daemon's session, or None while it waits to be started
+pub fn fetch_state(path: &Path) -> io::Result<Option<SessionState>> {
+    let reply = send(path, "state")?;
+    if !reply.starts_with('{') {
+        return Ok(None);
+    }
+    session::parse_state_json(&reply)
+        .map(Some)
+        .map_err(|err| io::Error::other(err.to_string()))
+}
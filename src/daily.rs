@@ -0,0 +1,52 @@
+use std::{collections::BTreeMap, fs, io, path::Path, time::Duration};
+
+use crate::duration::parse_duration;
+use crate::format_millis;
+
+// Time tracked per day, like a simple time tracker: one line per local
+// date, "2026-10-14<TAB>01:02:03.000", oldest first so the file reads as a
+// log. Days are kept as text so BTreeMap orders them.
+pub fn load(path: &Path) -> io::Result<BTreeMap<String, Duration>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(err) => return Err(err),
+    };
+    // Lines that don't parse are skipped so one bad edit doesn't lose the rest
+    let mut days = BTreeMap::new();
+    for line in text.lines() {
+        let Some((day, time)) = line.split_once('\t') else {
+            continue;
+        };
+        if let Ok(time) = parse_duration(time) {
+            *days.entry(day.trim().to_string()).or_default() += time;
+        }
+    }
+    Ok(days)
+}
+
+pub fn total(path: &Path, day: &str) -> io::Result<Duration> {
+    Ok(load(path)?.get(day).copied().unwrap_or_default())
+}
+
+// Adds `time` to the day's total, writing the whole file back
+pub fn add(path: &Path, day: &str, time: Duration) -> io::Result<()> {
+    if time.is_zero() {
+        return Ok(());
+    }
+    let mut days = load(path)?;
+    *days.entry(day.to_string()).or_default() += time;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let text: String = days
+        .iter()
+        .map(|(day, time)| format!("{}\t{}\n", day, format_millis(*time)))
+        .collect();
+    fs::write(path, text)
+}
+
+// The local date a run's time is counted on
+pub fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
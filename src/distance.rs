@@ -0,0 +1,32 @@
+// Parses a lap distance such as "400m", "1.5km" or "1mi" into meters.
+// A bare number is taken as meters.
+pub fn parse_distance(input: &str) -> Result<f64, String> {
+    let input = input.trim();
+    let number_len = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(number_len);
+
+    let meters_per_unit = match unit.trim() {
+        "m" | "" => 1.0,
+        "km" => 1_000.0,
+        "mi" => 1_609.344,
+        _ => return Err(format!("unknown unit '{}' in '{}'", unit, input)),
+    };
+    let value: f64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a valid distance", input))?;
+    if value <= 0.0 {
+        return Err("distance must be greater than zero".to_string());
+    }
+
+    Ok(value * meters_per_unit)
+}
+
+pub fn format_distance(meters: f64) -> String {
+    if meters < 1_000.0 {
+        format!("{:.0} m", meters)
+    } else {
+        format!("{:.2} km", meters / 1_000.0)
+    }
+}
@@ -0,0 +1,243 @@
+use chrono::{DateTime, Local, NaiveDateTime, NaiveTime, TimeZone};
+use std::time::Duration;
+
+// Shared parser for every duration typed on the command line or in the UI.
+// Accepts clock style ("45:00", "01:30:00", "00:00:05.250") and unit style
+// ("25m", "1h30m", "90s", "500ms"). A bare number is taken as seconds.
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("empty duration".to_string());
+    }
+
+    if input.contains(':') {
+        parse_clock(input)
+    } else {
+        parse_units(input)
+    }
+}
+
+fn parse_clock(input: &str) -> Result<Duration, String> {
+    let parts: Vec<&str> = input.split(':').collect();
+    if parts.len() > 3 {
+        return Err(format!("'{}' has too many ':' fields", input));
+    }
+
+    // Only the last field (seconds) may carry a fraction
+    let (whole, last) = parts.split_at(parts.len() - 1);
+    let mut total = parse_seconds(last[0], input)?;
+    let mut scale = 60;
+    for field in whole.iter().rev() {
+        let value: u64 = field
+            .parse()
+            .map_err(|_| format!("'{}' is not a valid time", input))?;
+        total = value
+            .checked_mul(scale)
+            .and_then(|secs| total.checked_add(Duration::from_secs(secs)))
+            .ok_or_else(|| too_long(input))?;
+        scale *= 60;
+    }
+
+    Ok(total)
+}
+
+fn parse_units(input: &str) -> Result<Duration, String> {
+    let mut total = Duration::ZERO;
+    let mut rest = input;
+
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        if number_len == 0 {
+            return Err(format!("'{}' is not a valid duration", input));
+        }
+        let (number, tail) = rest.split_at(number_len);
+        let unit_len = tail
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(unit_len);
+
+        let nanos_per_unit = match unit {
+            "h" => 3_600_000_000_000,
+            "m" | "min" => 60_000_000_000,
+            "s" | "" => 1_000_000_000,
+            "ms" => 1_000_000,
+            _ => return Err(format!("unknown unit '{}' in '{}'", unit, input)),
+        };
+        let value = parse_decimal(number, nanos_per_unit)
+            .ok_or_else(|| format!("'{}' is not a valid duration", input))?;
+        total = total.checked_add(value).ok_or_else(|| too_long(input))?;
+        rest = tail;
+    }
+
+    Ok(total)
+}
+
+fn too_long(input: &str) -> String {
+    format!("'{}' is too long a duration", input)
+}
+
+// The fraction may group its digits with '_', as microsecond times are
+// shown and exported ("00:00:05.250_125")
+fn parse_seconds(field: &str, input: &str) -> Result<Duration, String> {
+    let field = match field.split_once('.') {
+        Some((whole, fraction)) => format!("{}.{}", whole, fraction.replace('_', "")),
+        None => field.to_string(),
+    };
+    parse_decimal(&field, 1_000_000_000).ok_or_else(|| format!("'{}' is not a valid time", input))
+}
+
+// Parses "12" or "1.250" exactly in integer nanoseconds, avoiding float rounding
+fn parse_decimal(number: &str, nanos_per_unit: u128) -> Option<Duration> {
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+    if !whole
+        .chars()
+        .chain(fraction.chars())
+        .all(|c| c.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let whole: u128 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().ok()?
+    };
+    let mut nanos = whole.checked_mul(nanos_per_unit)?;
+    let mut place = nanos_per_unit;
+    for digit in fraction.chars() {
+        place /= 10;
+        nanos = nanos.checked_add(place * u128::from(digit.to_digit(10)?))?;
+    }
+
+    let secs = u64::try_from(nanos / 1_000_000_000).ok()?;
+    Some(Duration::new(secs, (nanos % 1_000_000_000) as u32))
+}
+
+// A local datetime such as "2024-01-01 09:00:00"; on a DST fold the
+// earlier of the two instants is used
+fn parse_local_datetime(input: &str) -> Result<DateTime<Local>, String> {
+    const FORMATS: [&str; 4] = [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+    ];
+
+    let naive = FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input.trim(), format).ok())
+        .ok_or_else(|| format!("'{}' is not a datetime like 2024-01-01 09:00:00", input))?;
+
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(|| format!("'{}' does not exist in the local timezone", input))
+}
+
+// Time elapsed since an absolute local datetime such as "2024-01-01 09:00:00"
+pub fn elapsed_since(input: &str) -> Result<Duration, String> {
+    let since = parse_local_datetime(input)?;
+    (Local::now() - since)
+        .to_std()
+        .map_err(|_| format!("'{}' is in the future", input))
+}
+
+// A local datetime that is still ahead, for `alarm --at`
+pub fn future_datetime(input: &str) -> Result<DateTime<Local>, String> {
+    let at = parse_local_datetime(input)?;
+    if at <= Local::now() {
+        return Err(format!("'{}' is in the past", input));
+    }
+    Ok(at)
+}
+
+// The next time the clock shows "07:30" or "07:30:15": today while that is
+// still ahead, tomorrow otherwise
+pub fn next_time_of_day(input: &str) -> Result<DateTime<Local>, String> {
+    let time = ["%H:%M:%S", "%H:%M"]
+        .iter()
+        .find_map(|format| NaiveTime::parse_from_str(input.trim(), format).ok())
+        .ok_or_else(|| format!("'{}' is not a time of day like 07:30", input))?;
+
+    let now = Local::now();
+    let mut date = now.date_naive();
+    loop {
+        // A time skipped by a DST change falls through to the next day
+        if let Some(at) = Local.from_local_datetime(&date.and_time(time)).earliest() {
+            if at > now {
+                return Ok(at);
+            }
+        }
+        date = date
+            .succ_opt()
+            .ok_or_else(|| format!("'{}' is out of range", input))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::ms;
+
+    #[test]
+    fn clock_style() {
+        assert_eq!(parse_duration("45:00"), Ok(ms(2_700_000)));
+        assert_eq!(parse_duration("01:30:00"), Ok(ms(5_400_000)));
+        assert_eq!(parse_duration(" 00:00:05.250 "), Ok(ms(5_250)));
+        assert_eq!(
+            parse_duration("00:00:05.250_125"),
+            Ok(Duration::from_micros(5_250_125))
+        );
+        assert_eq!(parse_duration("1:2:3"), Ok(ms(3_723_000)));
+    }
+
+    #[test]
+    fn unit_style() {
+        assert_eq!(parse_duration("25m"), Ok(ms(1_500_000)));
+        assert_eq!(parse_duration("1h30m"), Ok(ms(5_400_000)));
+        assert_eq!(parse_duration("90"), Ok(ms(90_000)));
+        assert_eq!(parse_duration("500ms"), Ok(ms(500)));
+        assert_eq!(parse_duration("1.5min"), Ok(ms(90_000)));
+        assert_eq!(parse_duration(".25s"), Ok(ms(250)));
+        // Exact, where 0.1 + 0.2 in floats isn't
+        assert_eq!(parse_duration("0.1s0.2s"), Ok(ms(300)));
+    }
+
+    #[test]
+    fn bad_durations() {
+        assert_eq!(parse_duration(""), Err("empty duration".to_string()));
+        for input in ["1:2:3:4", "aa:00", "5x", "m", "1..5s", "00:00:0x", "."] {
+            assert!(parse_duration(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn too_long_is_an_error_not_a_panic() {
+        let max = u64::MAX.to_string();
+        for input in [
+            format!("{}:00:00", max),
+            format!("{}h", max),
+            format!("{}s{}s", max, max),
+            "99999999999999999999999999999999999999h".to_string(),
+        ] {
+            assert!(parse_duration(&input).is_err(), "{}", input);
+        }
+        assert_eq!(
+            parse_duration(&format!("{}s{}s", max, max)),
+            Err(format!("'{}s{}s' is too long a duration", max, max))
+        );
+    }
+
+    #[test]
+    fn next_time_of_day_is_ahead() {
+        let now = Local::now();
+        let at = next_time_of_day("00:00").unwrap();
+        assert!(at > now && at - now <= chrono::Duration::days(1));
+        assert!(next_time_of_day("25:00").is_err());
+    }
+}
@@ -0,0 +1,521 @@
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use crate::duration::parse_duration;
+use crate::logging::json_string;
+use crate::Chronometer;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    // LiveSplit splits, for speedrunners
+    Lss,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            "lss" => Some(ExportFormat::Lss),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+            ExportFormat::Lss => "lss",
+        }
+    }
+}
+
+// Lap CSV layout shared by exports and imports: lap number, cumulative time,
+// split time and the wall-clock timestamp of the lap. Columns for what the
+// session has follow: `excluded` with --skip-laps, `pace_delta` with
+// --pace, `reference_delta` with --compare, `distance_m` and `pace_per_km`
+// with --lap-distance, `project` with --tag and `note` once a lap has one.
+pub const CSV_HEADER: &str = "lap,cumulative,split,timestamp";
+
+// One row per lap: id, cumulative and split time in the display precision,
+// when the lap was recorded, and what else the session knows about it
+struct LapRow {
+    id: usize,
+    cumulative: Duration,
+    split: Duration,
+    timestamp: String,
+    // A --skip-laps warm-up lap, left out of the stats
+    excluded: bool,
+    // Ahead of or behind the --pace line, and the --compare reference
+    pace_delta: Option<String>,
+    reference_delta: Option<String>,
+    // Distance covered by the end of the lap and the lap's pace per km, if
+    // that fits in a Duration
+    distance: Option<(f64, Option<Duration>)>,
+    project: Option<String>,
+    note: Option<String>,
+}
+
+// Which optional columns a session's rows fill
+struct Columns {
+    excluded: bool,
+    pace: bool,
+    reference: bool,
+    distance: bool,
+    project: bool,
+    note: bool,
+}
+
+impl Columns {
+    fn of(chronometer: &Chronometer) -> Self {
+        Columns {
+            excluded: chronometer.skipped_laps() > 0,
+            pace: chronometer.target_pace.is_some(),
+            reference: !chronometer.reference_laps.is_empty(),
+            distance: chronometer.lap_distance.is_some(),
+            project: chronometer.has_projects(),
+            note: !chronometer.lap_notes.is_empty(),
+        }
+    }
+}
+
+fn lap_rows(chronometer: &Chronometer) -> Vec<LapRow> {
+    chronometer
+        .lap_ids
+        .iter()
+        .zip(&chronometer.lap_durations)
+        .zip(chronometer.lap_splits())
+        .zip(&chronometer.lap_timestamps)
+        .enumerate()
+        .map(
+            |(index, (((&id, &cumulative), split), &timestamp))| LapRow {
+                id,
+                cumulative,
+                split,
+                timestamp: rfc3339(timestamp),
+                excluded: chronometer.is_lap_skipped(index),
+                pace_delta: chronometer
+                    .pace_delta(index)
+                    .map(|delta| chronometer.format_pace_delta(delta)),
+                reference_delta: chronometer
+                    .reference_comparison(index)
+                    .map(|comparison| chronometer.format_pace_delta(comparison.delta)),
+                distance: chronometer.lap_distance.map(|meters| {
+                    let pace =
+                        Duration::try_from_secs_f64(split.as_secs_f64() * 1_000.0 / meters).ok();
+                    (meters * (index + 1) as f64, pace)
+                }),
+                project: chronometer.lap_project(id).map(str::to_string),
+                note: chronometer.lap_notes.get(&id).cloned(),
+            },
+        )
+        .collect()
+}
+
+// UTC like the log timestamps, but unambiguous and comma-free for CSV
+pub(crate) fn rfc3339(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time).to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+pub fn laps_csv(chronometer: &Chronometer) -> String {
+    laps_delimited(chronometer, ',')
+}
+
+// The CSV columns tab-separated, as spreadsheets paste them into cells
+pub fn laps_tsv(chronometer: &Chronometer) -> String {
+    laps_delimited(chronometer, '\t')
+}
+
+fn laps_delimited(chronometer: &Chronometer, separator: char) -> String {
+    let columns = Columns::of(chronometer);
+    let mut header: Vec<&str> = CSV_HEADER.split(',').collect();
+    let optional = [
+        (columns.excluded, &["excluded"][..]),
+        (columns.pace, &["pace_delta"]),
+        (columns.reference, &["reference_delta"]),
+        (columns.distance, &["distance_m", "pace_per_km"]),
+        (columns.project, &["project"]),
+        (columns.note, &["note"]),
+    ];
+    for (_, names) in optional.iter().filter(|(shown, _)| *shown) {
+        header.extend_from_slice(names);
+    }
+    let mut text = format!("{}\n", header.join(&separator.to_string()));
+    for row in lap_rows(chronometer) {
+        let mut fields = vec![
+            row.id.to_string(),
+            chronometer.format_duration(row.cumulative),
+            chronometer.format_duration(row.split),
+            row.timestamp,
+        ];
+        if columns.excluded {
+            fields.push(row.excluded.to_string());
+        }
+        if columns.pace {
+            fields.push(row.pace_delta.unwrap_or_default());
+        }
+        if columns.reference {
+            fields.push(row.reference_delta.unwrap_or_default());
+        }
+        if columns.distance {
+            let (meters, pace) = row.distance.unwrap_or_default();
+            fields.push(format!("{:.0}", meters));
+            fields.push(pace.map_or("--".to_string(), |pace| chronometer.format_duration(pace)));
+        }
+        if columns.project {
+            fields.push(row.project.unwrap_or_default());
+        }
+        if columns.note {
+            fields.push(csv_field(&row.note.unwrap_or_default(), separator));
+        }
+        text.push_str(&fields.join(&separator.to_string()));
+        text.push('\n');
+    }
+    text
+}
+
+// Quotes a free-text field that holds the separator, quotes or line breaks
+fn csv_field(value: &str, separator: char) -> String {
+    if value.contains([separator, '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// A JSON string whose braces are escaped too, so the lap objects of an
+// export can still be split apart on them when it's read back
+fn export_string(value: &str) -> String {
+    json_string(value)
+        .replace('{', "\\u007b")
+        .replace('}', "\\u007d")
+}
+
+pub fn laps_json(chronometer: &Chronometer) -> String {
+    let laps: Vec<String> = lap_rows(chronometer)
+        .iter()
+        .map(|row| {
+            let mut extra = String::new();
+            let mut field = |key: &str, value: String| extra.push_str(&format!(",\"{}\":{}", key, value));
+            if row.excluded {
+                field("excluded", "true".to_string());
+            }
+            if let Some(ref delta) = row.pace_delta {
+                field("pace_delta", json_string(delta));
+            }
+            if let Some(ref delta) = row.reference_delta {
+                field("reference_delta", json_string(delta));
+            }
+            if let Some((meters, pace)) = row.distance {
+                field("distance_m", format!("{:.0}", meters));
+                let pace = pace.map_or("null".to_string(), |pace| {
+                    json_string(&chronometer.format_duration(pace))
+                });
+                field("pace_per_km", pace);
+            }
+            if let Some(ref project) = row.project {
+                field("project", export_string(project));
+            }
+            if let Some(ref note) = row.note {
+                field("note", export_string(note));
+            }
+            format!(
+                "    {{\"lap\":{},\"cumulative\":\"{}\",\"cumulative_ms\":{},\"split\":\"{}\",\"split_ms\":{},\"timestamp\":\"{}\"{}}}",
+                row.id,
+                chronometer.format_duration(row.cumulative),
+                row.cumulative.as_millis(),
+                chronometer.format_duration(row.split),
+                row.split.as_millis(),
+                row.timestamp,
+                extra
+            )
+        })
+        .collect();
+    let laps = if laps.is_empty() {
+        String::new()
+    } else {
+        format!("\n{}\n  ", laps.join(",\n"))
+    };
+    // Time per tag goes ahead of the laps, which a --compare reads as every
+    // object after "laps"
+    let projects = if chronometer.has_projects() {
+        let totals: Vec<String> = chronometer
+            .project_totals()
+            .iter()
+            .map(|(project, time)| {
+                format!(
+                    "    {{\"project\":{},\"time\":\"{}\",\"time_ms\":{}}}",
+                    export_string(project),
+                    chronometer.format_duration(*time),
+                    time.as_millis()
+                )
+            })
+            .collect();
+        format!("\n  \"projects\":[\n{}\n  ],", totals.join(",\n"))
+    } else {
+        String::new()
+    };
+    format!(
+        "{{\n  \"started\":\"{}\",{}\n  \"laps\":[{}]\n}}\n",
+        rfc3339(chronometer.start_timestamp),
+        projects,
+        laps
+    )
+}
+
+// A LiveSplit run with one attempt: a segment per lap, named after the
+// --segments file, the lap note or else the lap number, with its split
+// time as both the personal best and the best segment
+pub fn laps_lss(chronometer: &Chronometer) -> String {
+    let rows = lap_rows(chronometer);
+    let ended = chronometer
+        .lap_timestamps
+        .last()
+        .copied()
+        .unwrap_or_else(SystemTime::now);
+    let total = rows.last().map(|row| row.cumulative);
+
+    let mut xml =
+        String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Run version=\"1.7.0\">\n");
+    xml.push_str("  <GameIcon />\n  <GameName>ChronoRust</GameName>\n  <CategoryName>Session</CategoryName>\n");
+    xml.push_str("  <Metadata>\n    <Run id=\"\" />\n    <Platform usesEmulator=\"False\"></Platform>\n    <Region></Region>\n    <Variables />\n  </Metadata>\n");
+    xml.push_str(
+        "  <Offset>00:00:00</Offset>\n  <AttemptCount>1</AttemptCount>\n  <AttemptHistory>\n",
+    );
+    xml.push_str(&format!(
+        "    <Attempt id=\"1\" started=\"{}\" isStartedSynced=\"True\" ended=\"{}\" isEndedSynced=\"True\">\n",
+        lss_date(chronometer.start_timestamp),
+        lss_date(ended)
+    ));
+    if let Some(total) = total {
+        xml.push_str(&format!("      <RealTime>{}</RealTime>\n", lss_time(total)));
+    }
+    xml.push_str("    </Attempt>\n  </AttemptHistory>\n  <Segments>\n");
+    for (index, row) in rows.iter().enumerate() {
+        let name = chronometer
+            .segment_name(index)
+            .or(chronometer.lap_notes.get(&row.id).map(String::as_str))
+            .map_or_else(|| format!("Lap {}", row.id), str::to_string);
+        xml.push_str(&format!(
+            "    <Segment>\n      <Name>{}</Name>\n      <Icon />\n",
+            xml_escape(&name)
+        ));
+        xml.push_str(&format!(
+            "      <SplitTimes>\n        <SplitTime name=\"Personal Best\">\n          <RealTime>{}</RealTime>\n        </SplitTime>\n      </SplitTimes>\n",
+            lss_time(row.cumulative)
+        ));
+        xml.push_str(&format!(
+            "      <BestSegmentTime>\n        <RealTime>{}</RealTime>\n      </BestSegmentTime>\n",
+            lss_time(row.split)
+        ));
+        xml.push_str(&format!(
+            "      <SegmentHistory>\n        <Time id=\"1\">\n          <RealTime>{}</RealTime>\n        </Time>\n      </SegmentHistory>\n    </Segment>\n",
+            lss_time(row.split)
+        ));
+    }
+    xml.push_str("  </Segments>\n  <AutoSplitterSettings />\n</Run>\n");
+    xml
+}
+
+// LiveSplit's TimeSpan text, with seven fractional digits
+fn lss_time(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:07}",
+        secs / 3_600,
+        (secs % 3_600) / 60,
+        secs % 60,
+        duration.subsec_nanos() / 100
+    )
+}
+
+// Attempt dates are UTC, month first
+fn lss_date(time: SystemTime) -> String {
+    DateTime::<Utc>::from(time)
+        .format("%m/%d/%Y %H:%M:%S")
+        .to_string()
+}
+
+// The segment names of a LiveSplit .lss file, in order. Only <Segment>
+// names are read; the rest of the file is ignored.
+pub fn parse_lss_segments(text: &str) -> Result<Vec<String>, String> {
+    let mut names = Vec::new();
+    for segment in text.split("<Segment>").skip(1) {
+        let segment = segment.split("</Segment>").next().unwrap_or(segment);
+        let name = match segment.split_once("<Name>") {
+            Some((_, rest)) => rest
+                .split_once("</Name>")
+                .map(|(name, _)| xml_unescape(name.trim()))
+                .ok_or_else(|| format!("segment {}: unclosed <Name>", names.len() + 1))?,
+            // <Name /> or no name at all
+            None => String::new(),
+        };
+        names.push(name);
+    }
+    if names.is_empty() {
+        return Err("no <Segment> elements found".to_string());
+    }
+    Ok(names)
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// Reads the cumulative lap times back from a lap CSV. Rows that don't parse
+// fail the whole import with their line number instead of being skipped.
+pub fn parse_lap_csv(text: &str) -> Result<Vec<Duration>, String> {
+    let mut laps = Vec::new();
+    for (index, row) in text.lines().enumerate() {
+        let line = index + 1;
+        let row = row.trim();
+        if row.is_empty() || (line == 1 && row.starts_with(CSV_HEADER)) {
+            continue;
+        }
+
+        let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+        if fields.len() < 3 {
+            return Err(format!("line {}: expected at least 3 fields", line));
+        }
+        fields[0]
+            .parse::<usize>()
+            .map_err(|_| format!("line {}: '{}' is not a lap number", line, fields[0]))?;
+        let cumulative =
+            parse_duration(fields[1]).map_err(|err| format!("line {}: {}", line, err))?;
+        parse_duration(fields[2]).map_err(|err| format!("line {}: {}", line, err))?;
+
+        if laps.last().is_some_and(|&previous| cumulative < previous) {
+            return Err(format!("line {}: cumulative time goes backwards", line));
+        }
+        laps.push(cumulative);
+    }
+    Ok(laps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock,
+        compare::parse_reference,
+        fixtures::{ms, with_laps},
+    };
+
+    // Every row but the timestamp, which follows the real wall clock
+    fn rows(csv: &str) -> Vec<Vec<String>> {
+        csv.lines()
+            .map(|line| {
+                let mut fields: Vec<String> = line.split(',').map(str::to_string).collect();
+                fields.remove(3);
+                fields
+            })
+            .collect()
+    }
+
+    #[test]
+    fn csv_has_one_row_per_lap() {
+        let mut chronometer = with_laps(&[1_500, 1_000, 2_250]);
+        chronometer.delete_lap(2);
+        let csv = laps_csv(&chronometer);
+        assert_eq!(
+            rows(&csv),
+            [
+                ["lap", "cumulative", "split"],
+                ["1", "00:00:01.500", "00:00:01.500"],
+                ["3", "00:00:04.750", "00:00:03.250"],
+            ]
+        );
+        let timestamp = csv.lines().nth(1).unwrap().split(',').nth(3).unwrap();
+        assert!(timestamp.ends_with('Z') && timestamp.contains('T'));
+        assert_eq!(parse_lap_csv(&csv), Ok(vec![ms(1_500), ms(4_750)]));
+    }
+
+    #[test]
+    fn csv_adds_the_columns_a_session_fills() {
+        let mut chronometer = with_laps(&[1_200, 1_000]);
+        chronometer.skip_laps = 1;
+        chronometer.target_pace = Some(ms(1_000));
+        chronometer.lap_distance = Some(400.0);
+        chronometer.set_lap_note(2, "wind, then \"rain\"");
+        let csv = laps_csv(&chronometer);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "lap,cumulative,split,timestamp,excluded,pace_delta,distance_m,pace_per_km,note"
+        );
+        // Pace deltas are against the target times the lap number
+        assert!(lines[1].ends_with(",true,+00:00:00.200,400,00:00:03.000,"));
+        assert!(
+            lines[2].ends_with(",false,+00:00:00.200,800,00:00:02.500,\"wind, then \"\"rain\"\"\"")
+        );
+        // The quoted note doesn't get in the way of reading it back
+        assert_eq!(parse_lap_csv(&csv), Ok(vec![ms(1_200), ms(2_200)]));
+    }
+
+    #[test]
+    fn tsv_is_the_csv_tab_separated() {
+        let chronometer = with_laps(&[1_000]);
+        assert_eq!(
+            laps_tsv(&chronometer),
+            laps_csv(&chronometer).replace(',', "\t")
+        );
+    }
+
+    #[test]
+    fn json_round_trips_through_compare() {
+        let mut chronometer = with_laps(&[1_000, 2_000]);
+        chronometer.set_lap_note(1, "a {tricky}, \"note\"");
+        let json = laps_json(&chronometer);
+        assert!(json.contains("\"cumulative\":\"00:00:03.000\",\"cumulative_ms\":3000"));
+        assert!(json.contains("\"note\":\"a \\u007btricky\\u007d, \\\"note\\\"\""));
+        assert_eq!(parse_reference(&json), Ok(vec![ms(1_000), ms(3_000)]));
+    }
+
+    #[test]
+    fn json_lists_project_time_ahead_of_the_laps() {
+        let mut chronometer = Chronometer::new();
+        chronometer.set_project(Some("client {a}".to_string()));
+        chronometer.start().unwrap();
+        clock::advance(ms(2_000));
+        chronometer.lap();
+        let json = laps_json(&chronometer);
+        let (projects, laps) = json.split_once("\"laps\"").unwrap();
+        assert!(
+            projects.contains("{\"project\":\"client \\u007ba\\u007d\",\"time\":\"00:00:02.000\"")
+        );
+        assert!(laps.contains("\"project\":\"client \\u007ba\\u007d\""));
+        assert_eq!(parse_reference(&json), Ok(vec![ms(2_000)]));
+    }
+
+    #[test]
+    fn empty_session_exports_headers_only() {
+        let chronometer = Chronometer::new();
+        assert_eq!(laps_csv(&chronometer), format!("{}\n", CSV_HEADER));
+        assert!(laps_json(&chronometer).contains("\"laps\":[]"));
+    }
+
+    #[test]
+    fn pace_too_slow_to_hold_is_left_blank() {
+        let mut chronometer = with_laps(&[1_000]);
+        chronometer.lap_distance = Some(1e-20);
+        let csv = laps_csv(&chronometer);
+        assert!(csv.lines().nth(1).unwrap().ends_with(",0,--"));
+        assert!(laps_json(&chronometer).contains("\"pace_per_km\":null"));
+        let stats = chronometer.distance_stats().unwrap();
+        assert!(stats.pace_per_km.is_none() && stats.speed_kmh.is_some());
+    }
+}
@@ -0,0 +1,29 @@
+// Helpers the test modules share, so each one builds its sessions the same
+// way on the hand-moved clock
+use std::{path::PathBuf, time::Duration};
+
+use crate::{clock, Chronometer};
+
+pub fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+pub fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+// A running chronometer with a lap after each step, in milliseconds
+pub fn with_laps(steps: &[u64]) -> Chronometer {
+    let mut chronometer = Chronometer::new();
+    chronometer.start().unwrap();
+    for &step in steps {
+        clock::advance(ms(step));
+        chronometer.lap();
+    }
+    chronometer
+}
+
+// Unique to the test run, for tests that write files
+pub fn temp_file(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("chronorust-{}-{}", std::process::id(), name))
+}
@@ -0,0 +1,78 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::session::{load_state_json, save_state_json, SessionError, SessionState};
+
+// A finished session, stored as a --resume style JSON file named after the
+// session's start so a resumed session keeps updating the same entry
+pub struct HistoryEntry {
+    pub path: PathBuf,
+    pub state: SessionState,
+}
+
+impl HistoryEntry {
+    pub fn started(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_millis(self.state.start_timestamp_ms)
+    }
+
+    pub fn total(&self) -> Duration {
+        Duration::from_millis(self.state.elapsed_ms)
+    }
+
+    pub fn projects(&self) -> Vec<(String, Duration)> {
+        self.state
+            .projects_ms
+            .iter()
+            .map(|(project, ms)| (project.clone(), Duration::from_millis(*ms)))
+            .collect()
+    }
+
+    pub fn laps(&self) -> Vec<Duration> {
+        self.state
+            .laps_ms
+            .iter()
+            .map(|&lap| Duration::from_millis(lap))
+            .collect()
+    }
+}
+
+// Saved as paused: a finished session doesn't keep counting when read back
+pub fn record(dir: &Path, state: &SessionState) -> Result<PathBuf, SessionError> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.json", state.start_timestamp_ms));
+    let finished = SessionState {
+        elapsed_ms: state.elapsed_ms,
+        laps_ms: state.laps_ms.clone(),
+        paused: true,
+        start_timestamp_ms: state.start_timestamp_ms,
+        projects_ms: state.projects_ms.clone(),
+    };
+    save_state_json(&path, &finished)?;
+    Ok(path)
+}
+
+// Newest first. A missing directory is an empty history; files that don't
+// parse are skipped so one bad entry doesn't hide the rest.
+pub fn load(dir: &Path) -> io::Result<Vec<HistoryEntry>> {
+    let read_dir = match fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut entries = Vec::new();
+    for dir_entry in read_dir {
+        let path = dir_entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        if let Ok(state) = load_state_json(&path) {
+            entries.push(HistoryEntry { path, state });
+        }
+    }
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.state.start_timestamp_ms));
+    Ok(entries)
+}
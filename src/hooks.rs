@@ -0,0 +1,311 @@
+#[cfg(feature = "scripting")]
+use std::{fs::OpenOptions, io::Write, path::Path};
+use std::{
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    sync::{
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::logging::LogEvent;
+
+// Events a hook can be attached to
+pub const HOOK_EVENTS: [&str; 6] = [
+    "on_lap",
+    "on_pause",
+    "on_resume",
+    "on_reset",
+    "on_countdown_finished",
+    "on_quit",
+];
+
+// What runs on timer events, in three flavors:
+//
+// - Executables in a hooks directory, named after the event, the way git
+//   runs its hooks. Each gets its arguments on the command line and the
+//   event name and elapsed time as CHRONORUST_EVENT and CHRONORUST_ELAPSED:
+//
+//     on_lap N ELAPSED
+//     on_pause ELAPSED, on_resume ELAPSED
+//     on_reset
+//     on_countdown_finished ELAPSED
+//     on_quit ELAPSED
+//
+// - Shell commands from the [hooks] config table, with {event}, {elapsed},
+//   and for laps {n} and {time}, filled in.
+//
+// - With the scripting feature, functions of the same names in a Rhai
+//   script, called with the executable's arguments (see load_script).
+//
+// Commands are started and scripts run by a worker thread so the clock never
+// waits on a fork, and nothing waits for commands to finish. A missing hook is
+// skipped and a failing one is ignored.
+pub struct Hooks {
+    dir: Option<PathBuf>,
+    // (event, command template), in config file order
+    commands: Vec<(String, String)>,
+    #[cfg(feature = "scripting")]
+    scripted: bool,
+    worker: Option<(Sender<Job>, JoinHandle<()>)>,
+}
+
+// What the worker thread is handed
+enum Job {
+    Spawn(Command),
+    #[cfg(feature = "scripting")]
+    Load(Box<Script>),
+    // A script function and its arguments
+    #[cfg(feature = "scripting")]
+    Call(String, Vec<String>),
+}
+
+impl Hooks {
+    // `entries` is the [hooks] table; "on_finish" is short for
+    // "on_countdown_finished"
+    pub fn new(dir: Option<PathBuf>, entries: &[(&str, &str)]) -> Result<Self, String> {
+        let mut commands = Vec::new();
+        for &(event, command) in entries {
+            let event = match event {
+                "on_finish" => "on_countdown_finished",
+                event if HOOK_EVENTS.contains(&event) => event,
+                _ => {
+                    return Err(format!(
+                        "unknown hook '{}', expected one of: {}",
+                        event,
+                        HOOK_EVENTS.join(", ")
+                    ))
+                }
+            };
+            commands.push((event.to_string(), command.to_string()));
+        }
+
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let worker = thread::spawn(move || {
+            let running = Running::default();
+            #[cfg(feature = "scripting")]
+            let mut script: Option<Box<Script>> = None;
+            loop {
+                match receiver.recv_timeout(Duration::from_secs(1)) {
+                    Ok(Job::Spawn(command)) => running.spawn(command),
+                    #[cfg(feature = "scripting")]
+                    Ok(Job::Load(mut loaded)) => {
+                        loaded.start(&running);
+                        script = Some(loaded);
+                    }
+                    #[cfg(feature = "scripting")]
+                    Ok(Job::Call(name, args)) => {
+                        if let Some(ref script) = script {
+                            script.call(&name, &args);
+                        }
+                    }
+                    Err(RecvTimeoutError::Timeout) => {}
+                    // Hooks still running when the program exits are left to finish
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                running.reap();
+            }
+        });
+        Ok(Self {
+            dir,
+            commands,
+            #[cfg(feature = "scripting")]
+            scripted: false,
+            worker: Some((sender, worker)),
+        })
+    }
+
+    // Compiles a Rhai script whose on_lap(n, time), on_pause(elapsed) and
+    // so on are called on the worker thread with the same arguments as the
+    // executables, the lap number as an integer. A missing file is no
+    // script; a syntax error is reported here, while a failing call is
+    // ignored like a failing hook. Its top-level statements run once, as it
+    // is loaded.
+    #[cfg(feature = "scripting")]
+    pub fn load_script(&mut self, path: &Path) -> Result<(), String> {
+        if !path.is_file() {
+            return Ok(());
+        }
+        let script = Script::compile(path)?;
+        if let Some((ref sender, _)) = self.worker {
+            let _ = sender.send(Job::Load(Box::new(script)));
+            self.scripted = true;
+        }
+        Ok(())
+    }
+
+    // The hooks matching a log event, if the event has any
+    pub fn fire(&self, event: &LogEvent, elapsed: &str) {
+        match *event {
+            LogEvent::Lap { id, formatted, .. } => {
+                let id = id.to_string();
+                let vars = [("n", id.as_str()), ("time", formatted)];
+                self.run("on_lap", &[&id, formatted], elapsed, &vars)
+            }
+            LogEvent::Pause { .. } => self.run("on_pause", &[elapsed], elapsed, &[]),
+            LogEvent::Resume { .. } => self.run("on_resume", &[elapsed], elapsed, &[]),
+            LogEvent::Reset { .. } => self.run("on_reset", &[], elapsed, &[]),
+            LogEvent::Quit { .. } => self.run("on_quit", &[elapsed], elapsed, &[]),
+            LogEvent::SessionStart | LogEvent::Message(_) => {}
+        }
+    }
+
+    // `args` go to the executable, `vars` fill the command templates on top
+    // of {event} and {elapsed}
+    pub fn run(&self, name: &str, args: &[&str], elapsed: &str, vars: &[(&str, &str)]) {
+        let Some((ref sender, _)) = self.worker else {
+            return;
+        };
+        let quiet = |command: &mut Command| {
+            command
+                .env("CHRONORUST_EVENT", name)
+                .env("CHRONORUST_ELAPSED", elapsed)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+        };
+
+        if let Some(path) = self.dir.as_ref().map(|dir| dir.join(name)) {
+            if path.is_file() {
+                let mut command = Command::new(path);
+                command.args(args);
+                quiet(&mut command);
+                let _ = sender.send(Job::Spawn(command));
+            }
+        }
+
+        for (_, template) in self.commands.iter().filter(|(event, _)| event == name) {
+            let mut line = template
+                .replace("{event}", name)
+                .replace("{elapsed}", elapsed);
+            for (key, value) in vars {
+                line = line.replace(&format!("{{{}}}", key), value);
+            }
+            let mut command = shell(&line);
+            quiet(&mut command);
+            let _ = sender.send(Job::Spawn(command));
+        }
+
+        #[cfg(feature = "scripting")]
+        if self.scripted {
+            let args = args.iter().map(|arg| arg.to_string()).collect();
+            let _ = sender.send(Job::Call(name.to_string(), args));
+        }
+    }
+}
+
+// Hooks the worker has started and not yet seen exit, shared with the
+// script's shell()
+#[derive(Default, Clone)]
+struct Running(Arc<Mutex<Vec<Child>>>);
+
+impl Running {
+    fn spawn(&self, mut command: Command) {
+        if let (Ok(child), Ok(mut running)) = (command.spawn(), self.0.lock()) {
+            running.push(child);
+        }
+    }
+
+    fn reap(&self) {
+        if let Ok(mut running) = self.0.lock() {
+            running.retain_mut(|child| !matches!(child.try_wait(), Ok(Some(_))));
+        }
+    }
+}
+
+// A compiled hooks.rhai. Besides Rhai's own functions a script gets
+// shell(line), which starts a command the way [hooks] entries are started,
+// and append(path, text), which adds a line to a file; print() output is
+// dropped so it can't draw over the display.
+#[cfg(feature = "scripting")]
+struct Script {
+    engine: rhai::Engine,
+    ast: rhai::AST,
+}
+
+#[cfg(feature = "scripting")]
+impl Script {
+    fn compile(path: &Path) -> Result<Self, String> {
+        let mut engine = rhai::Engine::new();
+        engine.on_print(|_| {});
+        engine.on_debug(|_, _, _| {});
+        let ast = engine
+            .compile_file(path.to_path_buf())
+            .map_err(|err| err.to_string())?;
+        Ok(Self { engine, ast })
+    }
+
+    // The functions that need the worker's state, then the top-level code
+    fn start(&mut self, running: &Running) {
+        let running = running.clone();
+        self.engine.register_fn("shell", move |line: &str| {
+            let mut command = shell(line);
+            command
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+            running.spawn(command);
+        });
+        self.engine.register_fn("append", |path: &str, text: &str| {
+            let file = OpenOptions::new().create(true).append(true).open(path);
+            if let Ok(mut file) = file {
+                let _ = writeln!(file, "{}", text);
+            }
+        });
+        let _ = self.engine.run_ast(&self.ast);
+    }
+
+    // Events the script has no function for are skipped
+    fn call(&self, name: &str, args: &[String]) {
+        if !self
+            .ast
+            .iter_functions()
+            .any(|function| function.name == name)
+        {
+            return;
+        }
+        let args: Vec<rhai::Dynamic> = args
+            .iter()
+            .map(|arg| match arg.parse::<rhai::INT>() {
+                Ok(number) => number.into(),
+                Err(_) => arg.clone().into(),
+            })
+            .collect();
+        let options = rhai::CallFnOptions::new().eval_ast(false);
+        let _ = self.engine.call_fn_with_options::<rhai::Dynamic>(
+            options,
+            &mut rhai::Scope::new(),
+            &self.ast,
+            name,
+            args,
+        );
+    }
+}
+
+// Dropping the hooks waits until the queued ones have started, so an
+// on_quit hook still runs as the program exits
+impl Drop for Hooks {
+    fn drop(&mut self) {
+        if let Some((sender, worker)) = self.worker.take() {
+            drop(sender);
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn shell(line: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.args(["-c", line]);
+    command
+}
+
+#[cfg(windows)]
+fn shell(line: &str) -> Command {
+    let mut command = Command::new("cmd");
+    command.args(["/C", line]);
+    command
+}
@@ -0,0 +1,100 @@
+use std::{
+    io,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use tiny_http::{Header, Method, Response, Server};
+
+use chronorust::Chronometer;
+
+use crate::App;
+
+// A bare port binds to localhost; anything else is used as given
+pub fn bind_address(value: &str) -> String {
+    if value.chars().all(|c| c.is_ascii_digit()) {
+        format!("127.0.0.1:{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+// Binds up front so a bad address fails before the TUI starts, then serves
+// requests on a background thread. The lock is only held while a response
+// is built, so the draw loop never waits on the network. Requests act on
+// whichever timer tab is selected.
+pub fn serve(addr: &str, timers: Arc<Mutex<App>>) -> io::Result<()> {
+    let server = Server::http(addr).map_err(|err| io::Error::other(err.to_string()))?;
+
+    thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let (status, body) = match (request.method(), request.url()) {
+                (Method::Get, "/status") => (200, status_json(timers.lock().unwrap().active())),
+                (Method::Post, _) if timers.lock().unwrap().active().read_only => {
+                    (409, "{\"error\":\"read-only\"}".to_string())
+                }
+                (Method::Post, "/lap") => {
+                    let mut timers = timers.lock().unwrap();
+                    let chronometer = timers.active_mut();
+                    if chronometer.is_started() {
+                        chronometer.lap();
+                        (200, status_json(chronometer))
+                    } else {
+                        (409, "{\"error\":\"not running\"}".to_string())
+                    }
+                }
+                // Toggles like the pause key
+                (Method::Post, "/pause") => {
+                    let mut timers = timers.lock().unwrap();
+                    let chronometer = timers.active_mut();
+                    match chronometer.toggle_pause() {
+                        Ok(()) => (200, status_json(chronometer)),
+                        Err(err) => (409, format!("{{\"error\":\"{}\"}}", err)),
+                    }
+                }
+                (Method::Post, "/reset") => {
+                    let mut timers = timers.lock().unwrap();
+                    let chronometer = timers.active_mut();
+                    chronometer.reset();
+                    (200, status_json(chronometer))
+                }
+                _ => (404, "{\"error\":\"not found\"}".to_string()),
+            };
+
+            // Browser overlays (e.g. an OBS browser source) fetch from
+            // another origin
+            let response = Response::from_string(body)
+                .with_status_code(status)
+                .with_header(Header::from_bytes("Content-Type", "application/json").unwrap())
+                .with_header(Header::from_bytes("Access-Control-Allow-Origin", "*").unwrap());
+            let _ = request.respond(response);
+        }
+    });
+
+    Ok(())
+}
+
+fn status_json(chronometer: &Chronometer) -> String {
+    let elapsed = chronometer.elapsed();
+    let laps: Vec<String> = chronometer
+        .lap_ids
+        .iter()
+        .zip(&chronometer.lap_durations)
+        .map(|(id, &lap)| {
+            format!(
+                "{{\"id\":{},\"time\":\"{}\",\"ms\":{}}}",
+                id,
+                chronometer.format_duration(lap),
+                lap.as_millis()
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"state\":\"{}\",\"elapsed\":\"{}\",\"elapsed_ms\":{},\"laps\":[{}]}}",
+        chronometer.state_label(),
+        chronometer.format_duration(elapsed),
+        elapsed.as_millis(),
+        laps.join(",")
+    )
+}
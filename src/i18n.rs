@@ -0,0 +1,700 @@
+use std::{env, fmt};
+
+// The languages the TUI and the exit report speak. Logs, exports, summary
+// lines and the stdin and daemon protocols stay in English so scripts and
+// the importers can read them whatever the language.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Lang {
+    En,
+    Tr,
+}
+
+impl Lang {
+    // "tr", or a locale name such as "tr_TR.UTF-8" or "en-US"
+    pub fn parse(value: &str) -> Option<Self> {
+        let language = value
+            .split(['_', '-', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Lang::En),
+            "tr" => Some(Lang::Tr),
+            _ => None,
+        }
+    }
+
+    // Same precedence as gettext: the first non-empty variable wins, and a
+    // language there's no catalog for falls back to English
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Lang::parse(&value))
+            .unwrap_or(Lang::En)
+    }
+
+    pub fn text(self, msg: Msg) -> &'static str {
+        let (en, tr) = catalog(msg);
+        match self {
+            Lang::En => en,
+            Lang::Tr => tr,
+        }
+    }
+
+    // The message with its "{name}" placeholders filled in, so a language
+    // can put them in its own order
+    pub fn fill(self, msg: Msg, args: &[(&str, &dyn fmt::Display)]) -> String {
+        let mut text = self.text(msg).to_string();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        text
+    }
+
+    // "1 lap", "3 laps"; Turkish doesn't inflect after a number
+    pub fn laps(self, count: usize) -> String {
+        match count {
+            1 => self.text(Msg::OneLap).to_string(),
+            count => self.fill(Msg::LapCount, &[("count", &count)]),
+        }
+    }
+}
+
+// Every string the UI shows, keyed so the catalog below is the only place
+// that holds the text
+#[derive(Clone, Copy)]
+pub enum Msg {
+    // Title and time box
+    Title,
+    Time,
+    TargetDelta,
+    ReadOnly,
+    ProjectTag,
+    AlarmAt,
+    // Compact view
+    TabOf,
+    LapShort,
+    PlanDone,
+    SegmentOf,
+    // Controls bar
+    Controls,
+    ControlsList,
+    HintHelp,
+    HintReset,
+    HintLap,
+    HintDeleteLap,
+    HintLapNote,
+    HintFilter,
+    HintSnapshot,
+    HintExport,
+    HintCopy,
+    HintHeatmap,
+    HintClock,
+    HintSplits,
+    HintChart,
+    HintLapStats,
+    HintHistory,
+    HintPrecision,
+    HintResetTo,
+    HintUndo,
+    HintRedoLap,
+    HintPause,
+    HintNewTimer,
+    HintQuit,
+    ButtonPause,
+    // Lap list
+    LapTimes,
+    LapTimesNext,
+    LapTimesFiltered,
+    LapRow,
+    LapAuto,
+    LapExcluded,
+    LapNoteLine,
+    LapPage,
+    FooterFastest,
+    FooterSlowest,
+    FooterLast,
+    Reference,
+    SplitColumn,
+    OneLap,
+    LapCount,
+    // Lap statistics panel
+    LapStats,
+    StatLaps,
+    StatBest,
+    StatWorst,
+    StatMean,
+    StatMedian,
+    StatStdDev,
+    NoLapsYet,
+    // Stats box
+    Stats,
+    RateLine,
+    Pace,
+    DistanceLine,
+    PauseLeft,
+    TargetPassed,
+    ToTarget,
+    NeedPace,
+    Projects,
+    Today,
+    // Phase boxes and progress
+    PomodoroTitle,
+    PomodoroLabel,
+    Work,
+    Break,
+    WorkCaps,
+    RestCaps,
+    DoneCaps,
+    IntervalTitle,
+    RoundsIn,
+    TimeLeft,
+    TimeOver,
+    PlanTitle,
+    KeyForNext,
+    NextUp,
+    SegmentsPlanned,
+    SequenceTitle,
+    SegmentsDone,
+    LeftInSegment,
+    ProgressTitle,
+    ProgressLabel,
+    TimersTitle,
+    CountdownDone,
+    #[cfg(feature = "zones")]
+    WorldClock,
+    LapsPer,
+    LapsPerMinute,
+    LapsPerHour,
+    CommandRunning,
+    CommandExit,
+    CommandKilled,
+    CommandTerminated,
+    // Prompts and popups
+    PromptResetOffset,
+    PromptAddCountdown,
+    PromptLapNote,
+    PromptLapFilter,
+    PromptProject,
+    FilterNeedsSign,
+    ResetTitle,
+    QuitTitle,
+    ResetQuestion,
+    QuitQuestion,
+    ConfirmKeys,
+    PresetsTitle,
+    HistoryTitle,
+    SessionTitle,
+    NoSessions,
+    // Key help
+    KeysTitle,
+    HelpTiming,
+    HelpLaps,
+    HelpTimers,
+    HelpView,
+    HelpExport,
+    HelpHelp,
+    KeyPause,
+    KeyFreeze,
+    KeyReset,
+    KeyResetTo,
+    KeyUndo,
+    KeyNextSegment,
+    KeyProject,
+    KeyLock,
+    KeySuspend,
+    KeyDetach,
+    KeyQuit,
+    KeyLap,
+    KeyDeleteLap,
+    KeyRedoLap,
+    KeySelectLap,
+    KeyPageLaps,
+    KeyFirstLap,
+    KeyLapNote,
+    KeyFilter,
+    KeyNewTab,
+    KeySwitchTab,
+    KeyAddTimer,
+    KeyPreset,
+    KeyPrevTimer,
+    KeyNextTimer,
+    KeyRemoveTimer,
+    KeyPrecision,
+    KeyBigDigits,
+    KeySplitView,
+    KeyHeatmap,
+    KeyLapStats,
+    KeyChart,
+    KeyWorldClock,
+    KeyHistory,
+    KeyExport,
+    KeySnapshot,
+    KeyCopy,
+    KeyCopyLaps,
+    KeyHelp,
+    KeyScrollHelp,
+    KeyCloseHelp,
+    // Status bar
+    #[cfg(feature = "zones")]
+    NoClockZones,
+    #[cfg(not(feature = "zones"))]
+    NoZonesFeature,
+    LostDaemon,
+    NoPlan,
+    NoHistoryDir,
+    CannotReadHistory,
+    TimerStarted,
+    LogWriteFailed,
+    ControlsUnlocked,
+    PressEnterToUnlock,
+    LockedHint,
+    ControlsLocked,
+    ImportedReadOnly,
+    NotWhileAttached,
+    StatusPaused,
+    StatusResumed,
+    ResetUndone,
+    LapRestored,
+    NothingToUndo,
+    NoPresets,
+    TimeFrozen,
+    ShowingLive,
+    LapRecorded,
+    LapDeleted,
+    PrecisionSet,
+    PrecisionSeconds,
+    PrecisionMillis,
+    PrecisionMicros,
+    NoLapsToCopy,
+    SnapshotWritten,
+    SnapshotFailed,
+    Exported,
+    ExportFailed,
+    CantPause,
+    Copied,
+    CopiedTerminal,
+    CopyFailed,
+    TimeIdle,
+    TimeFrozenNote,
+    // Desktop notifications
+    TimerFinished,
+    TimeIsUp,
+    CountdownFinished,
+    AlarmTitle,
+    ItsTime,
+    PhaseStarted,
+    UntilNextPhase,
+    WorkoutDone,
+    PhaseRound,
+    TimeInTotal,
+    // Exit report
+    Summary,
+    ReportStarted,
+    ReportEnded,
+    ReportElapsed,
+    ReportPaused,
+    OnePause,
+    PauseCount,
+    ReportLaps,
+    BestWorst,
+    ReportTarget,
+    Ahead,
+    Behind,
+    OverBy,
+    ReportProject,
+    Goodbye,
+    Detached,
+    CommandEnded,
+}
+
+// English, then Turkish
+fn catalog(msg: Msg) -> (&'static str, &'static str) {
+    match msg {
+        Msg::Title => (
+            "ChronoRust v{version} - High Precision Chronometer",
+            "ChronoRust v{version} - Yüksek Hassasiyetli Kronometre",
+        ),
+        Msg::Time => ("Time", "Süre"),
+        Msg::TargetDelta => ("Target {delta}", "Hedef {delta}"),
+        Msg::ReadOnly => ("Imported, read-only", "İçe aktarıldı, salt okunur"),
+        Msg::ProjectTag => ("Project: {project}", "Proje: {project}"),
+        Msg::AlarmAt => ("Alarm {time}", "Alarm {time}"),
+
+        Msg::TabOf => ("Tab {index}/{count}", "Sekme {index}/{count}"),
+        Msg::LapShort => ("Lap {number} {time}", "Tur {number} {time}"),
+        Msg::PlanDone => ("Plan done", "Plan bitti"),
+        Msg::SegmentOf => ("Segment {index}/{count}", "Bölüm {index}/{count}"),
+
+        Msg::Controls => ("Controls", "Kontroller"),
+        Msg::ControlsList => ("Controls: {keys}", "Kontroller: {keys}"),
+        Msg::HintHelp => ("Help", "Yardım"),
+        Msg::HintReset => ("Reset", "Sıfırla"),
+        Msg::HintLap => ("Lap", "Tur"),
+        Msg::HintDeleteLap => ("Delete Lap", "Turu Sil"),
+        Msg::HintLapNote => ("Lap Note", "Tur Notu"),
+        Msg::HintFilter => ("Filter", "Süz"),
+        Msg::HintSnapshot => ("Snapshot", "Anlık Görüntü"),
+        Msg::HintExport => ("Export", "Dışa Aktar"),
+        Msg::HintCopy => ("Copy", "Kopyala"),
+        Msg::HintHeatmap => ("Heatmap", "Isı Haritası"),
+        Msg::HintClock => ("Clock", "Saat"),
+        Msg::HintSplits => ("Splits", "Ara Süreler"),
+        Msg::HintChart => ("Chart", "Grafik"),
+        Msg::HintLapStats => ("Lap Stats", "Tur İstatistikleri"),
+        Msg::HintHistory => ("History", "Geçmiş"),
+        Msg::HintPrecision => ("Precision", "Hassasiyet"),
+        Msg::HintResetTo => ("Reset To", "Değere Sıfırla"),
+        Msg::HintUndo => ("Undo", "Geri Al"),
+        Msg::HintRedoLap => ("Redo Lap", "Turu Yinele"),
+        Msg::HintPause => ("Pause/Resume", "Duraklat/Sürdür"),
+        Msg::HintNewTimer => ("New Timer", "Yeni Sayaç"),
+        Msg::HintQuit => ("Quit", "Çık"),
+        Msg::ButtonPause => ("Pause", "Duraklat"),
+
+        Msg::LapTimes => ("Lap Times", "Tur Süreleri"),
+        Msg::LapTimesNext => (
+            "Lap Times (next: {next})",
+            "Tur Süreleri (sıradaki: {next})",
+        ),
+        Msg::LapTimesFiltered => (
+            "Lap Times (split {filter}: {shown} of {total}, Esc clears)",
+            "Tur Süreleri (ara süre {filter}: {total} turdan {shown}, Esc temizler)",
+        ),
+        Msg::LapRow => (
+            "Lap {number} [#{id}]{name}: {time}",
+            "Tur {number} [#{id}]{name}: {time}",
+        ),
+        Msg::LapAuto => ("(auto)", "(otomatik)"),
+        Msg::LapExcluded => ("(excluded)", "(hariç)"),
+        Msg::LapNoteLine => ("note: {note}", "not: {note}"),
+        Msg::LapPage => ("laps {from}-{to} of {count}", "tur {from}-{to} / {count}"),
+        Msg::FooterFastest => ("fastest {time}", "en hızlı {time}"),
+        Msg::FooterSlowest => ("slowest {time}", "en yavaş {time}"),
+        Msg::FooterLast => ("last {delta} {time}", "son {delta} {time}"),
+        Msg::Reference => ("ref", "ref"),
+        Msg::SplitColumn => ("split", "ara"),
+        Msg::OneLap => ("1 lap", "1 tur"),
+        Msg::LapCount => ("{count} laps", "{count} tur"),
+
+        Msg::LapStats => ("Lap Stats", "Tur İstatistikleri"),
+        Msg::StatLaps => ("Laps", "Tur"),
+        Msg::StatBest => ("Best", "En iyi"),
+        Msg::StatWorst => ("Worst", "En kötü"),
+        Msg::StatMean => ("Mean", "Ortalama"),
+        Msg::StatMedian => ("Median", "Medyan"),
+        Msg::StatStdDev => ("Std dev", "Std sapma"),
+        Msg::NoLapsYet => ("No laps yet", "Henüz tur yok"),
+
+        Msg::Stats => ("Stats", "İstatistikler"),
+        Msg::RateLine => ("Rate: {rate} | Last: {last}", "Hız: {rate} | Son: {last}"),
+        Msg::Pace => ("Pace", "Tempo"),
+        Msg::DistanceLine => (
+            "Distance: {distance} | Speed: {speed} | Pace: {pace}/km",
+            "Mesafe: {distance} | Hız: {speed} | Tempo: {pace}/km",
+        ),
+        Msg::PauseLeft => ("Pause left: {time}", "Kalan duraklama: {time}"),
+        Msg::TargetPassed => ("Target {target} passed", "Hedef {target} geçildi"),
+        Msg::ToTarget => (
+            "To target: {remaining} | Laps left: {laps}",
+            "Hedefe: {remaining} | Kalan tur: {laps}",
+        ),
+        Msg::NeedPace => (" | Need: {pace}/lap", " | Gereken: {pace}/tur"),
+        Msg::Projects => ("Projects: {list}", "Projeler: {list}"),
+        Msg::Today => ("Today: {time}", "Bugün: {time}"),
+
+        Msg::PomodoroTitle => ("Pomodoro", "Pomodoro"),
+        Msg::PomodoroLabel => (
+            "{phase} {cycle} - {time} left",
+            "{phase} {cycle} - {time} kaldı",
+        ),
+        Msg::Work => ("Work", "Çalışma"),
+        Msg::Break => ("Break", "Mola"),
+        Msg::WorkCaps => ("WORK", "ÇALIŞ"),
+        Msg::RestCaps => ("REST", "DİNLEN"),
+        Msg::DoneCaps => ("DONE", "BİTTİ"),
+        Msg::IntervalTitle => (
+            "Interval - Round {round}/{rounds}",
+            "Aralık - Raunt {round}/{rounds}",
+        ),
+        Msg::RoundsIn => ("{rounds} rounds in {time}", "{time} içinde {rounds} raunt"),
+        Msg::TimeLeft => ("{time} left", "{time} kaldı"),
+        Msg::TimeOver => ("+{time} over", "+{time} aşıldı"),
+        Msg::PlanTitle => ("Plan", "Plan"),
+        Msg::KeyForNext => ("({key} for the next)", "(sonraki için {key})"),
+        Msg::NextUp => ("Next: {name} ({length})", "Sıradaki: {name} ({length})"),
+        Msg::SegmentsPlanned => (
+            "{count} segments, {time} planned",
+            "{count} bölüm, {time} planlandı",
+        ),
+        Msg::SequenceTitle => ("Sequence", "Sıra"),
+        Msg::SegmentsDone => ("{count} segments done", "{count} bölüm bitti"),
+        Msg::LeftInSegment => ("{time} left in this segment", "Bu bölümde {time} kaldı"),
+        Msg::ProgressTitle => ("Progress", "İlerleme"),
+        // Turkish puts the percent sign first
+        Msg::ProgressLabel => ("{percent}% - {time} left", "%{percent} - {time} kaldı"),
+        Msg::TimersTitle => ("Timers", "Sayaçlar"),
+        Msg::CountdownDone => ("done", "bitti"),
+        #[cfg(feature = "zones")]
+        Msg::WorldClock => ("World Clock", "Dünya Saati"),
+        Msg::LapsPer => ("Laps per {bucket}", "{bucket} başına tur"),
+        Msg::LapsPerMinute => ("laps/min", "tur/dk"),
+        Msg::LapsPerHour => ("laps/h", "tur/sa"),
+        Msg::CommandRunning => ("running", "çalışıyor"),
+        Msg::CommandExit => ("exit {code}", "çıkış {code}"),
+        Msg::CommandKilled => (
+            "killed by signal {signal}",
+            "{signal} sinyaliyle sonlandırıldı",
+        ),
+        Msg::CommandTerminated => ("terminated", "sonlandırıldı"),
+
+        Msg::PromptResetOffset => (
+            "Reset to offset (e.g. 45:00)",
+            "Bu değere sıfırla (ör. 45:00)",
+        ),
+        Msg::PromptAddCountdown => (
+            "New timer: name and duration (e.g. pasta 11m)",
+            "Yeni sayaç: ad ve süre (ör. makarna 11m)",
+        ),
+        Msg::PromptLapNote => (
+            "Note for lap #{id} (empty removes)",
+            "#{id} turu için not (boş bırakmak siler)",
+        ),
+        Msg::PromptLapFilter => (
+            "Show laps with split >D or <D (e.g. >01:30)",
+            "Ara süresi >D veya <D olan turlar (ör. >01:30)",
+        ),
+        Msg::PromptProject => (
+            "Project for new laps (empty clears)",
+            "Yeni turların projesi (boş bırakmak kaldırır)",
+        ),
+        Msg::FilterNeedsSign => (
+            "start the filter with '>' or '<'",
+            "süzgece '>' veya '<' ile başlayın",
+        ),
+        Msg::ResetTitle => ("Reset?", "Sıfırlansın mı?"),
+        Msg::QuitTitle => ("Quit?", "Çıkılsın mı?"),
+        Msg::ResetQuestion => (
+            "Reset the timer and its {laps}?",
+            "Sayaç ve {laps} sıfırlansın mı?",
+        ),
+        Msg::QuitQuestion => (
+            "Quit with {laps} recorded?",
+            "{laps} kayıtlıyken çıkılsın mı?",
+        ),
+        Msg::ConfirmKeys => (
+            "Y or Enter: yes, N or Esc: no",
+            "Y veya Enter: evet, N veya Esc: hayır",
+        ),
+        Msg::PresetsTitle => (
+            "Presets (Enter starts, Esc closes)",
+            "Hazır sayaçlar (Enter başlatır, Esc kapatır)",
+        ),
+        Msg::HistoryTitle => (
+            "History ({count} sessions, Enter shows laps, Esc closes)",
+            "Geçmiş ({count} oturum, Enter turları gösterir, Esc kapatır)",
+        ),
+        Msg::SessionTitle => (
+            "Session {session} (Esc goes back)",
+            "Oturum {session} (Esc geri döner)",
+        ),
+        Msg::NoSessions => ("(none)", "(yok)"),
+
+        Msg::KeysTitle => ("Keys (Esc closes)", "Tuşlar (Esc kapatır)"),
+        Msg::HelpTiming => ("Timing", "Zamanlama"),
+        Msg::HelpLaps => ("Laps", "Turlar"),
+        Msg::HelpTimers => ("Timers", "Sayaçlar"),
+        Msg::HelpView => ("View", "Görünüm"),
+        Msg::HelpExport => ("Export", "Dışa aktarma"),
+        Msg::HelpHelp => ("Help", "Yardım"),
+        Msg::KeyPause => ("Pause/Resume", "Duraklat/Sürdür"),
+        Msg::KeyFreeze => (
+            "Freeze the shown time (the clock runs on)",
+            "Gösterilen süreyi dondur (saat işlemeye devam eder)",
+        ),
+        Msg::KeyReset => ("Reset and restart", "Sıfırla ve yeniden başlat"),
+        Msg::KeyResetTo => ("Reset to a typed offset", "Yazılan değere sıfırla"),
+        Msg::KeyUndo => (
+            "Undo the last lap deletion or reset",
+            "Son tur silmeyi veya sıfırlamayı geri al",
+        ),
+        Msg::KeyNextSegment => ("Next --session segment", "Sonraki --session bölümü"),
+        Msg::KeyProject => ("Switch the project tag", "Proje etiketini değiştir"),
+        Msg::KeyLock => (
+            "Lock the controls (then Enter unlocks)",
+            "Kontrolleri kilitle (ardından Enter açar)",
+        ),
+        Msg::KeySuspend => ("Suspend to the shell", "Askıya alıp kabuğa dön"),
+        Msg::KeyDetach => (
+            "Detach from the daemon (attach)",
+            "Arka plan sürecinden ayrıl (attach)",
+        ),
+        Msg::KeyQuit => ("Quit", "Çık"),
+        Msg::KeyLap => ("Record a lap", "Tur kaydet"),
+        Msg::KeyDeleteLap => (
+            "Delete the selected lap (or Delete)",
+            "Seçili turu sil (veya Delete)",
+        ),
+        Msg::KeyRedoLap => ("Restart the current lap", "Geçerli turu yeniden başlat"),
+        Msg::KeySelectLap => ("Select a lap", "Tur seç"),
+        Msg::KeyPageLaps => ("Move ten laps", "On tur ilerle"),
+        Msg::KeyFirstLap => ("First lap / follow new laps", "İlk tur / yeni turları izle"),
+        Msg::KeyLapNote => ("Note on the selected lap", "Seçili tura not"),
+        Msg::KeyFilter => (
+            "Filter laps by split (Esc clears)",
+            "Turları ara süreye göre süz (Esc temizler)",
+        ),
+        Msg::KeyNewTab => ("New stopwatch tab", "Yeni kronometre sekmesi"),
+        Msg::KeySwitchTab => ("Next/previous tab", "Sonraki/önceki sekme"),
+        Msg::KeyAddTimer => ("Add a countdown timer", "Geri sayım sayacı ekle"),
+        Msg::KeyPreset => ("Start a preset timer", "Hazır sayaç başlat"),
+        Msg::KeyPrevTimer => ("Previous countdown timer", "Önceki geri sayım sayacı"),
+        Msg::KeyNextTimer => ("Next countdown timer", "Sonraki geri sayım sayacı"),
+        Msg::KeyRemoveTimer => ("Remove the countdown timer", "Geri sayım sayacını kaldır"),
+        Msg::KeyPrecision => ("Cycle precision", "Hassasiyeti değiştir"),
+        Msg::KeyBigDigits => ("Large digits", "Büyük rakamlar"),
+        Msg::KeySplitView => ("Split times first", "Önce ara süreler"),
+        Msg::KeyHeatmap => ("Lap heatmap", "Tur ısı haritası"),
+        Msg::KeyLapStats => ("Lap statistics", "Tur istatistikleri"),
+        Msg::KeyChart => ("Laps-per-minute chart", "Dakika başına tur grafiği"),
+        Msg::KeyWorldClock => ("World clock", "Dünya saati"),
+        Msg::KeyHistory => ("Past sessions", "Geçmiş oturumlar"),
+        Msg::KeyExport => ("Export laps", "Turları dışa aktar"),
+        Msg::KeySnapshot => ("Text snapshot of the screen", "Ekranın metin görüntüsü"),
+        Msg::KeyCopy => ("Copy the time", "Süreyi kopyala"),
+        Msg::KeyCopyLaps => ("Copy every lap", "Bütün turları kopyala"),
+        Msg::KeyHelp => ("Show or close this help", "Bu yardımı aç veya kapat"),
+        Msg::KeyScrollHelp => ("Scroll it", "Kaydır"),
+        Msg::KeyCloseHelp => ("Close it", "Kapat"),
+
+        #[cfg(feature = "zones")]
+        Msg::NoClockZones => (
+            "No clock zones (--zones or clock_zones in the config)",
+            "Saat dilimi yok (--zones veya yapılandırmada clock_zones)",
+        ),
+        #[cfg(not(feature = "zones"))]
+        Msg::NoZonesFeature => (
+            "The world clock needs a build with the 'zones' feature",
+            "Dünya saati 'zones' özelliğiyle derlenmiş bir sürüm ister",
+        ),
+        Msg::LostDaemon => (
+            "Lost the daemon: {error}",
+            "Arka plan süreciyle bağlantı koptu: {error}",
+        ),
+        Msg::NoPlan => (
+            "No --session plan to advance",
+            "İlerletilecek --session planı yok",
+        ),
+        Msg::NoHistoryDir => (
+            "No history without HOME or XDG_DATA_HOME",
+            "HOME veya XDG_DATA_HOME olmadan geçmiş tutulamaz",
+        ),
+        Msg::CannotReadHistory => ("Cannot read history: {error}", "Geçmiş okunamadı: {error}"),
+        Msg::TimerStarted => ("Timer '{name}' started", "'{name}' sayacı başladı"),
+        Msg::LogWriteFailed => ("Log write failed: {error}", "Kayda yazılamadı: {error}"),
+        Msg::ControlsUnlocked => ("Controls unlocked", "Kontrollerin kilidi açıldı"),
+        Msg::PressEnterToUnlock => ("Press Enter to unlock", "Kilidi açmak için Enter'a basın"),
+        Msg::LockedHint => (
+            "Locked: press {key}, then Enter to unlock",
+            "Kilitli: açmak için {key}, ardından Enter'a basın",
+        ),
+        Msg::ControlsLocked => (
+            "Controls locked: {key}, then Enter unlocks",
+            "Kontroller kilitlendi: {key}, ardından Enter kilidi açar",
+        ),
+        Msg::ImportedReadOnly => (
+            "Imported laps are read-only",
+            "İçe aktarılan turlar salt okunur",
+        ),
+        Msg::NotWhileAttached => (
+            "Not while attached to the daemon",
+            "Arka plan sürecine bağlıyken yapılamaz",
+        ),
+        Msg::StatusPaused => ("Paused", "Duraklatıldı"),
+        Msg::StatusResumed => ("Resumed", "Sürdürüldü"),
+        Msg::ResetUndone => ("Reset undone", "Sıfırlama geri alındı"),
+        Msg::LapRestored => ("Lap {lap} restored", "Tur {lap} geri getirildi"),
+        Msg::NothingToUndo => ("Nothing to undo", "Geri alınacak bir şey yok"),
+        Msg::NoPresets => (
+            "No presets; add a [presets] table to the config file",
+            "Hazır ayar yok; yapılandırma dosyasına bir [presets] tablosu ekleyin",
+        ),
+        Msg::TimeFrozen => (
+            "Time frozen, the clock runs on; {key} shows it live",
+            "Süre donduruldu, saat işlemeyi sürdürüyor; {key} canlı gösterir",
+        ),
+        Msg::ShowingLive => ("Showing the live time", "Canlı süre gösteriliyor"),
+        Msg::LapRecorded => ("Lap {lap} recorded", "Tur {lap} kaydedildi"),
+        Msg::LapDeleted => (
+            "Lap {lap} deleted ({key} to undo)",
+            "Tur {lap} silindi (geri almak için {key})",
+        ),
+        Msg::PrecisionSet => ("Precision: {precision}", "Hassasiyet: {precision}"),
+        Msg::PrecisionSeconds => ("seconds", "saniye"),
+        Msg::PrecisionMillis => ("milliseconds", "milisaniye"),
+        Msg::PrecisionMicros => ("microseconds", "mikrosaniye"),
+        Msg::NoLapsToCopy => ("No laps to copy", "Kopyalanacak tur yok"),
+        Msg::SnapshotWritten => (
+            "Snapshot written to {path}",
+            "Görüntü {path} dosyasına yazıldı",
+        ),
+        Msg::SnapshotFailed => ("Snapshot failed: {error}", "Görüntü yazılamadı: {error}"),
+        Msg::Exported => ("Exported {path}", "{path} dışa aktarıldı"),
+        Msg::ExportFailed => ("Export failed: {error}", "Dışa aktarılamadı: {error}"),
+        Msg::CantPause => ("Can't pause: {error}", "Duraklatılamıyor: {error}"),
+        Msg::Copied => ("Copied {what}", "{what} kopyalandı"),
+        Msg::CopiedTerminal => (
+            "Copied {what} (via the terminal)",
+            "{what} kopyalandı (terminal üzerinden)",
+        ),
+        Msg::CopyFailed => ("Copy failed: {error}", "Kopyalanamadı: {error}"),
+        Msg::TimeIdle => (" (idle)", " (boşta)"),
+        Msg::TimeFrozenNote => (" (frozen)", " (donduruldu)"),
+
+        Msg::TimerFinished => ("Timer '{name}' finished", "'{name}' sayacı bitti"),
+        Msg::TimeIsUp => ("{time} is up", "{time} doldu"),
+        Msg::CountdownFinished => ("Countdown finished", "Geri sayım bitti"),
+        Msg::AlarmTitle => ("Alarm", "Alarm"),
+        Msg::ItsTime => ("It's {time}", "Saat {time}"),
+        Msg::PhaseStarted => ("{phase} {cycle} started", "{phase} {cycle} başladı"),
+        Msg::UntilNextPhase => ("{time} until the next phase", "Sonraki aşamaya {time} var"),
+        Msg::WorkoutDone => ("Workout done", "Antrenman bitti"),
+        Msg::PhaseRound => (
+            "{phase} - round {round}/{rounds}",
+            "{phase} - raunt {round}/{rounds}",
+        ),
+        Msg::TimeInTotal => ("{time} in total", "Toplam {time}"),
+
+        Msg::Summary => ("Session summary", "Oturum özeti"),
+        Msg::ReportStarted => ("Started", "Başladı"),
+        Msg::ReportEnded => ("Ended", "Bitti"),
+        Msg::ReportElapsed => ("Elapsed", "Geçen"),
+        Msg::ReportPaused => ("Paused", "Duraklama"),
+        Msg::OnePause => ("1 pause", "1 duraklama"),
+        Msg::PauseCount => ("{count} pauses", "{count} duraklama"),
+        Msg::ReportLaps => ("Laps", "Turlar"),
+        Msg::BestWorst => (
+            "{laps} (best {best}, worst {worst})",
+            "{laps} (en iyi {best}, en kötü {worst})",
+        ),
+        Msg::ReportTarget => ("Target", "Hedef"),
+        Msg::Ahead => ("ahead", "önde"),
+        Msg::Behind => ("behind", "geride"),
+        Msg::OverBy => ("Over by", "Aşım"),
+        Msg::ReportProject => ("Project", "Proje"),
+        Msg::Goodbye => (
+            "ChronoRust stopped. Goodbye!",
+            "ChronoRust durdu. Hoşça kalın!",
+        ),
+        Msg::Detached => (
+            "Detached; the daemon keeps timing on {socket}",
+            "Ayrıldı; arka plan süreci {socket} üzerinde saymayı sürdürüyor",
+        ),
+        Msg::CommandEnded => (
+            "'{command}' {state} after {time}",
+            "'{command}' {time} sonra: {state}",
+        ),
+    }
+}
@@ -0,0 +1,133 @@
+use std::time::Duration;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum IntervalPhase {
+    Work,
+    Rest,
+    Done,
+}
+
+impl IntervalPhase {
+    pub fn label(self) -> &'static str {
+        match self {
+            IntervalPhase::Work => "WORK",
+            IntervalPhase::Rest => "REST",
+            IntervalPhase::Done => "DONE",
+        }
+    }
+}
+
+// Tabata-style work/rest rounds laid over the stopwatch's elapsed time, like
+// Pomodoro but for a fixed number of rounds. The last work phase isn't
+// followed by a rest; the workout is done when it ends.
+pub struct Interval {
+    pub work: Duration,
+    pub rest: Duration,
+    pub rounds: u32,
+    pub phase: IntervalPhase,
+    // 1-based; stays on the last round once done
+    pub round: u32,
+    phase_start: Duration,
+    elapsed: Duration,
+    cycle: Duration,
+    total: Duration,
+}
+
+impl Interval {
+    // None when the whole workout is longer than a Duration can hold
+    pub fn new(work: Duration, rest: Duration, rounds: u32) -> Option<Self> {
+        let cycle = work.checked_add(rest)?;
+        let total = cycle.checked_mul(rounds)?.checked_sub(rest)?;
+        Some(Self {
+            work,
+            rest,
+            rounds,
+            phase: IntervalPhase::Work,
+            round: 1,
+            phase_start: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            cycle,
+            total,
+        })
+    }
+
+    pub fn phase_length(&self) -> Duration {
+        match self.phase {
+            IntervalPhase::Work => self.work,
+            IntervalPhase::Rest => self.rest,
+            IntervalPhase::Done => Duration::ZERO,
+        }
+    }
+
+    // Work and rest of every round but the last, which has no rest
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    // Moves to `elapsed`, returning true when a new phase began. The phase
+    // is worked out from the elapsed time alone, so a reset starts over.
+    pub fn tick(&mut self, elapsed: Duration) -> bool {
+        let before = (self.phase, self.round);
+        self.elapsed = elapsed;
+
+        if elapsed >= self.total {
+            self.phase = IntervalPhase::Done;
+            self.round = self.rounds;
+            self.phase_start = self.total;
+        } else {
+            // Short of the total, so the round and its start both fit
+            let index = (elapsed.as_nanos() / self.cycle.as_nanos()) as u32;
+            let cycle_start = self.cycle * index;
+            self.round = index + 1;
+            if elapsed - cycle_start < self.work {
+                self.phase = IntervalPhase::Work;
+                self.phase_start = cycle_start;
+            } else {
+                self.phase = IntervalPhase::Rest;
+                self.phase_start = cycle_start + self.work;
+            }
+        }
+        (self.phase, self.round) != before
+    }
+
+    pub fn remaining(&self) -> Duration {
+        (self.phase_start + self.phase_length()).saturating_sub(self.elapsed)
+    }
+
+    pub fn progress(&self) -> f64 {
+        if self.phase == IntervalPhase::Done {
+            return 1.0;
+        }
+        let done = self.elapsed.saturating_sub(self.phase_start).as_secs_f64();
+        (done / self.phase_length().as_secs_f64()).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::secs;
+
+    #[test]
+    fn phases_follow_the_elapsed_time() {
+        let mut interval = Interval::new(secs(20), secs(10), 2).unwrap();
+        assert_eq!(interval.total(), secs(50));
+        assert!(!interval.tick(secs(5)));
+        assert_eq!(interval.remaining(), secs(15));
+        assert!(interval.tick(secs(25)));
+        assert!(interval.phase == IntervalPhase::Rest && interval.round == 1);
+        assert!(interval.tick(secs(30)));
+        assert!(interval.phase == IntervalPhase::Work && interval.round == 2);
+        assert!(interval.tick(secs(50)));
+        assert!(interval.phase == IntervalPhase::Done && interval.round == 2);
+    }
+
+    #[test]
+    fn workouts_too_long_to_add_up_are_refused() {
+        assert!(Interval::new(secs(u64::MAX / 2), secs(u64::MAX / 2), 3).is_none());
+        assert!(Interval::new(secs(u64::MAX), secs(1), 1).is_none());
+        let mut longest = Interval::new(secs(u64::MAX / 4), Duration::ZERO, 4).unwrap();
+        assert!(longest.tick(secs(u64::MAX / 4 * 3 + 1)));
+        assert!(longest.phase == IntervalPhase::Work && longest.round == 4);
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+// Every remappable TUI action. Up/Down, Esc and the prompt keys are fixed;
+// CopyLaps and Presets have no key of their own but are Shift with the copy
+// and precision keys.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Reset,
+    ResetTo,
+    Undo,
+    AddTimer,
+    RemoveTimer,
+    PrevTimer,
+    NextTimer,
+    Lap,
+    LapNote,
+    Filter,
+    DeleteLap,
+    RedoLap,
+    Precision,
+    Chart,
+    Heatmap,
+    BigDigits,
+    Snapshot,
+    Pause,
+    NewTimer,
+    Export,
+    LapStats,
+    History,
+    SplitView,
+    WorldClock,
+    Copy,
+    CopyLaps,
+    Presets,
+    NextSegment,
+    Help,
+    Lock,
+    Freeze,
+    Project,
+}
+
+// Config name and default key of each action
+const BINDINGS: [(Action, &str, char); 31] = [
+    (Action::Quit, "quit", 'q'),
+    (Action::Reset, "reset", 'r'),
+    (Action::ResetTo, "reset_to", 'o'),
+    (Action::Undo, "undo", 'u'),
+    (Action::AddTimer, "add_timer", 'a'),
+    (Action::RemoveTimer, "remove_timer", 'z'),
+    (Action::PrevTimer, "prev_timer", '['),
+    (Action::NextTimer, "next_timer", ']'),
+    (Action::Lap, "lap", 'l'),
+    (Action::LapNote, "lap_note", 'n'),
+    (Action::Filter, "filter", '/'),
+    (Action::DeleteLap, "delete_lap", 'x'),
+    (Action::RedoLap, "redo_lap", 'b'),
+    (Action::Precision, "precision", 'p'),
+    (Action::Chart, "chart", 'v'),
+    (Action::Heatmap, "heatmap", 'h'),
+    (Action::BigDigits, "big_digits", 'm'),
+    (Action::Snapshot, "snapshot", 'w'),
+    (Action::Pause, "pause", 's'),
+    (Action::NewTimer, "new_timer", '+'),
+    (Action::Export, "export", 'e'),
+    (Action::LapStats, "lap_stats", 't'),
+    (Action::History, "history", 'i'),
+    (Action::SplitView, "split_view", 'd'),
+    (Action::WorldClock, "world_clock", 'c'),
+    (Action::Copy, "copy", 'y'),
+    (Action::NextSegment, "next_segment", 'j'),
+    (Action::Help, "help", '?'),
+    (Action::Lock, "lock", 'k'),
+    (Action::Freeze, "freeze", 'f'),
+    (Action::Project, "project", 'g'),
+];
+
+// Letters match in either case, like the built-in bindings always have
+pub struct KeyMap {
+    keys: HashMap<Action, char>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            keys: BINDINGS
+                .iter()
+                .map(|&(action, _, key)| (action, key))
+                .collect(),
+        }
+    }
+}
+
+impl KeyMap {
+    // Applies `action = "key"` entries from the [keys] table on top of the
+    // defaults. Two actions ending up on the same key is an error.
+    pub fn from_entries(entries: &[(&str, &str)]) -> Result<Self, String> {
+        let mut keymap = KeyMap::default();
+        for &(name, value) in entries {
+            // Undo covered only resets when it was called undo_reset
+            let name = if name == "undo_reset" { "undo" } else { name };
+            let action = BINDINGS
+                .iter()
+                .find(|(_, config_name, _)| *config_name == name)
+                .map(|&(action, _, _)| action)
+                .ok_or_else(|| format!("unknown action '{}'", name))?;
+            let mut chars = value.chars();
+            let key = match (chars.next(), chars.next()) {
+                _ if value == "space" => ' ',
+                (Some(key), None) if !key.is_whitespace() => key.to_ascii_lowercase(),
+                _ => {
+                    return Err(format!(
+                        "key for '{}' must be a single character or \"space\"",
+                        name
+                    ))
+                }
+            };
+            keymap.keys.insert(action, key);
+        }
+
+        for &(action, name, _) in BINDINGS.iter() {
+            let key = keymap.keys[&action];
+            if let Some(&(_, other, _)) = BINDINGS
+                .iter()
+                .find(|&&(other, _, _)| other != action && keymap.keys[&other] == key)
+            {
+                return Err(format!(
+                    "'{}' and '{}' are both bound to '{}'",
+                    name, other, key
+                ));
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn action(&self, key: char) -> Option<Action> {
+        let key = key.to_ascii_lowercase();
+        self.keys
+            .iter()
+            .find(|&(_, &bound)| bound == key)
+            .map(|(&action, _)| action)
+    }
+
+    // Upper-case label for help text, e.g. "L"
+    pub fn label(&self, action: Action) -> String {
+        match self.keys[&action] {
+            ' ' => "Space".to_string(),
+            key => key.to_ascii_uppercase().to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_match_either_case() {
+        let keymap = KeyMap::default();
+        assert!(keymap.action('l') == Some(Action::Lap));
+        assert!(keymap.action('L') == Some(Action::Lap));
+        assert!(keymap.action('?') == Some(Action::Help));
+        assert!(keymap.action('!').is_none());
+        assert_eq!(keymap.label(Action::Pause), "S");
+    }
+
+    #[test]
+    fn entries_rebind_on_top_of_the_defaults() {
+        let keymap = KeyMap::from_entries(&[("pause", "space"), ("undo_reset", "U")]).unwrap();
+        assert!(keymap.action(' ') == Some(Action::Pause));
+        assert!(keymap.action('s').is_none());
+        assert!(keymap.action('u') == Some(Action::Undo));
+        assert_eq!(keymap.label(Action::Pause), "Space");
+        assert!(keymap.action('l') == Some(Action::Lap));
+    }
+
+    #[test]
+    fn bad_entries_are_refused() {
+        let err = |entries: &[(&str, &str)]| KeyMap::from_entries(entries).err().unwrap();
+        assert_eq!(err(&[("jump", "j")]), "unknown action 'jump'");
+        assert_eq!(
+            err(&[("lap", "ll")]),
+            "key for 'lap' must be a single character or \"space\""
+        );
+        assert_eq!(
+            err(&[("lap", " ")]),
+            "key for 'lap' must be a single character or \"space\""
+        );
+        assert_eq!(
+            err(&[("lap", "s")]),
+            "'lap' and 'pause' are both bound to 's'"
+        );
+        // Moving the other action away first makes room
+        assert!(KeyMap::from_entries(&[("pause", "space"), ("lap", "s")]).is_ok());
+    }
+}
@@ -0,0 +1,2023 @@
+// Core stopwatch engine: timing, laps, pace and stats, with no terminal
+// dependencies so other programs can embed it. The chronorust binary adds
+// the TUI, line mode and scripting front ends on top.
+use chrono::format::{Item, StrftimeItems};
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::mpsc::Sender,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+mod clock;
+pub mod compare;
+pub mod countdown;
+pub mod daily;
+pub mod distance;
+pub mod duration;
+pub mod export;
+#[cfg(test)]
+mod fixtures;
+pub mod history;
+pub mod hooks;
+pub mod interval;
+pub mod logging;
+pub mod plan;
+pub mod pomodoro;
+pub mod project;
+pub mod sequence;
+pub mod session;
+pub mod state;
+pub mod stats;
+pub mod target;
+pub mod timefmt;
+
+use export::ExportFormat;
+use hooks::Hooks;
+use logging::{LogEvent, LogFormat, Logger};
+use project::ProjectTimes;
+use session::SessionState;
+use state::{ChronoState, TransitionError};
+use stats::SplitStats;
+use target::Target;
+use timefmt::TimeFormat;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RateUnit {
+    PerMinute,
+    PerHour,
+}
+
+impl RateUnit {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "min" | "minute" => Some(RateUnit::PerMinute),
+            "hour" | "h" => Some(RateUnit::PerHour),
+            _ => None,
+        }
+    }
+
+    pub fn window_ms(self) -> f64 {
+        match self {
+            RateUnit::PerMinute => 60_000.0,
+            RateUnit::PerHour => 3_600_000.0,
+        }
+    }
+}
+
+pub fn format_seconds(duration: Duration) -> String {
+    let total_secs = duration.as_secs();
+    let hours = total_secs / 3_600;
+    let minutes = (total_secs % 3_600) / 60;
+    let seconds = total_secs % 60;
+
+    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+}
+
+pub fn format_millis(duration: Duration) -> String {
+    let total_ms = duration.as_millis();
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms % 3_600_000) / 60_000;
+    let seconds = (total_ms % 60_000) / 1_000;
+    let milliseconds = total_ms % 1_000;
+
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        hours, minutes, seconds, milliseconds
+    )
+}
+
+// Works on the u128 nanosecond total throughout so nothing is truncated to
+// milliseconds first and even 99 hours stays far from overflow
+pub fn format_micros(duration: Duration) -> String {
+    let total_ns = duration.as_nanos();
+    let hours = total_ns / 3_600_000_000_000;
+    let minutes = (total_ns % 3_600_000_000_000) / 60_000_000_000;
+    let seconds = (total_ns % 60_000_000_000) / 1_000_000_000;
+    let millis = (total_ns % 1_000_000_000) / 1_000_000;
+    let micros = (total_ns % 1_000_000) / 1_000;
+
+    format!(
+        "{:02}:{:02}:{:02}.{:03}_{:03}",
+        hours, minutes, seconds, millis, micros
+    )
+}
+
+fn utc(time: SystemTime) -> chrono::DateTime<chrono::Utc> {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap();
+    chrono::DateTime::from_timestamp(since_epoch.as_secs() as i64, since_epoch.subsec_nanos())
+        .unwrap()
+}
+
+// Rejects strftime strings chrono can't render, before they reach a log line
+pub fn validate_datetime_format(format: &str) -> Result<(), String> {
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("'{}' is not a valid strftime format", format));
+    }
+    Ok(())
+}
+
+// Keeps a formatted timestamp usable as part of a file name
+fn sanitize_filename(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' | ' ' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Precision {
+    Seconds,
+    Millis,
+    Micro,
+}
+
+impl Precision {
+    // Value of the least significant digit shown
+    pub fn unit(self) -> Duration {
+        match self {
+            Precision::Seconds => Duration::from_secs(1),
+            Precision::Millis => Duration::from_millis(1),
+            Precision::Micro => Duration::from_micros(1),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Precision::Seconds => Precision::Millis,
+            Precision::Millis => Precision::Micro,
+            Precision::Micro => Precision::Seconds,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Precision::Seconds => "seconds",
+            Precision::Millis => "milliseconds",
+            Precision::Micro => "microseconds",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RoundingMode {
+    Truncate,
+    Nearest,
+}
+
+impl RoundingMode {
+    // The formatters always truncate, so nearest adds half a unit first
+    // (halves round up)
+    pub fn apply(self, duration: Duration, unit: Duration) -> Duration {
+        match self {
+            RoundingMode::Truncate => duration,
+            RoundingMode::Nearest => duration.saturating_add(unit / 2),
+        }
+    }
+}
+
+pub struct Projection {
+    pub remaining: Duration,
+    pub laps_remaining: Option<usize>,
+    pub required_pace: Option<Duration>,
+    pub past_target: bool,
+}
+
+pub struct LapStats {
+    pub best: Duration,
+    pub worst: Duration,
+    pub average: Duration,
+}
+
+// What the session came to, for the exit report. Pauses are measured on the
+// wall clock between their start and end.
+pub struct SessionReport {
+    pub started: SystemTime,
+    pub ended: SystemTime,
+    pub elapsed: Duration,
+    // Start and end of every pause, one still going ending now
+    pub pauses: Vec<(SystemTime, SystemTime)>,
+    pub paused: Duration,
+    pub laps: usize,
+    pub stats: Option<LapStats>,
+}
+
+pub struct DistanceStats {
+    pub total_meters: f64,
+    pub speed_kmh: Option<f64>,
+    pub pace_per_km: Option<Duration>,
+}
+
+struct ResetBackup {
+    lap_durations: Vec<Duration>,
+    lap_timestamps: Vec<SystemTime>,
+    lap_ids: Vec<usize>,
+    lap_notes: HashMap<usize, String>,
+    auto_lap_ids: HashSet<usize>,
+    lap_projects: HashMap<usize, String>,
+    projects: Vec<(String, Duration)>,
+    next_lap_id: usize,
+    elapsed: Duration,
+    begun_at: Duration,
+}
+
+struct DeletedLap {
+    id: usize,
+    duration: Duration,
+    timestamp: SystemTime,
+    note: Option<String>,
+    auto: bool,
+    project: Option<String>,
+}
+
+// What undo can put back, newest last
+enum UndoEntry {
+    Reset(ResetBackup),
+    DeleteLap(DeletedLap),
+}
+
+// Older entries are dropped past this many
+const UNDO_LIMIT: usize = 50;
+
+// What undo() put back, for the status line
+#[derive(Clone, Copy, PartialEq)]
+pub enum Undone {
+    Reset,
+    DeleteLap(usize),
+}
+
+pub struct Chronometer {
+    state: ChronoState,
+    // Laps are kept as durations and only formatted for display, so a
+    // precision change applies to them as well
+    pub lap_durations: Vec<Duration>,
+    // Wall-clock time each lap was recorded, for exports
+    pub lap_timestamps: Vec<SystemTime>,
+    pub lap_ids: Vec<usize>,
+    pub lap_notes: HashMap<usize, String>,
+    // Laps --auto-lap recorded rather than a key press
+    auto_lap_ids: HashSet<usize>,
+    // The --tag project each lap was taken under, and the time per tag
+    lap_projects: HashMap<usize, String>,
+    projects: ProjectTimes,
+    pub next_lap_id: usize,
+    // Completed pauses this session; the pause budget is measured on this
+    // plus any pause in progress
+    paused_before: Duration,
+    // The same pauses on the wall clock, and when the current one began
+    pauses: Vec<(SystemTime, SystemTime)>,
+    paused_at: Option<SystemTime>,
+    pub prestart_until: Option<Instant>,
+    undo_stack: Vec<UndoEntry>,
+    pub idle_pause: Option<Duration>,
+    pub target_pace: Option<Duration>,
+    pub precision: Precision,
+    pub rounding: RoundingMode,
+    pub target_laps: Option<usize>,
+    pub skip_laps: usize,
+    last_activity: Instant,
+    pub auto_paused: bool,
+    // Like a stopwatch's split button, the shown time holds still while
+    // the clock runs on: the elapsed and wall-clock time when it froze
+    pub frozen: Option<(Duration, SystemTime)>,
+    // Time counted this run, for the daily total: what resets cleared, and
+    // the elapsed time the current session began from, which a resumed or
+    // backdated start didn't count here
+    reset_tracked: Duration,
+    begun_at: Duration,
+    logger: Option<Logger>,
+    // The last write the log refused, until a front end picks it up
+    log_error: Option<String>,
+    // Run on the same events the log records
+    pub hooks: Option<Hooks>,
+    // And sent as JSON to whoever listens, like the WebSocket broadcast
+    pub broadcast: Option<Sender<String>>,
+    pub start_timestamp: SystemTime,
+    pub datetime_format: Option<String>,
+    pub time_format: TimeFormat,
+    pub lap_distance: Option<f64>,
+    pub pause_budget: Option<Duration>,
+    pub output_dir: Option<PathBuf>,
+    pub reference_laps: Vec<Duration>,
+    pub target: Target,
+    // Laps of an earlier session loaded for review; the front ends don't
+    // change them
+    pub read_only: bool,
+    // Names for the laps in order, from a LiveSplit file
+    pub segment_names: Vec<String>,
+    pub countdown_from: Option<Duration>,
+    // The countdown keeps going below zero instead of stopping there
+    pub overtime: bool,
+    // Alarm mode counts down to this moment instead
+    pub alarm_at: Option<SystemTime>,
+    pub auto_lap: Option<Duration>,
+    // Multiple of the auto-lap interval last lapped, or skipped over when
+    // the elapsed time jumped
+    auto_lap_mark: Duration,
+}
+
+impl Default for Chronometer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Chronometer {
+    pub fn new() -> Self {
+        Self {
+            state: ChronoState::Idle,
+            lap_durations: Vec::new(),
+            lap_timestamps: Vec::new(),
+            lap_ids: Vec::new(),
+            lap_notes: HashMap::new(),
+            auto_lap_ids: HashSet::new(),
+            lap_projects: HashMap::new(),
+            projects: ProjectTimes::default(),
+            next_lap_id: 1,
+            paused_before: Duration::ZERO,
+            pauses: Vec::new(),
+            paused_at: None,
+            prestart_until: None,
+            undo_stack: Vec::new(),
+            idle_pause: None,
+            target_pace: None,
+            precision: Precision::Millis,
+            rounding: RoundingMode::Truncate,
+            target_laps: None,
+            skip_laps: 0,
+            last_activity: clock::now(),
+            auto_paused: false,
+            frozen: None,
+            reset_tracked: Duration::ZERO,
+            begun_at: Duration::ZERO,
+            logger: None,
+            log_error: None,
+            hooks: None,
+            broadcast: None,
+            start_timestamp: clock::system_now(),
+            datetime_format: None,
+            time_format: TimeFormat::default(),
+            lap_distance: None,
+            pause_budget: None,
+            output_dir: None,
+            reference_laps: Vec::new(),
+            target: Target::default(),
+            read_only: false,
+            segment_names: Vec::new(),
+            countdown_from: None,
+            overtime: false,
+            alarm_at: None,
+            auto_lap: None,
+            auto_lap_mark: Duration::ZERO,
+        }
+    }
+
+    // A fresh, stopped chronometer with the same display and lap settings.
+    // Logging isn't carried over; each log file belongs to one session.
+    pub fn new_like(&self) -> Self {
+        let mut chronometer = Self::new();
+        chronometer.idle_pause = self.idle_pause;
+        chronometer.target_pace = self.target_pace;
+        chronometer.precision = self.precision;
+        chronometer.rounding = self.rounding;
+        chronometer.target_laps = self.target_laps;
+        chronometer.skip_laps = self.skip_laps;
+        chronometer.datetime_format = self.datetime_format.clone();
+        chronometer.time_format = self.time_format;
+        chronometer.lap_distance = self.lap_distance;
+        chronometer.pause_budget = self.pause_budget;
+        chronometer.output_dir = self.output_dir.clone();
+        chronometer.reference_laps = self.reference_laps.clone();
+        chronometer.target = self.target.clone();
+        chronometer.segment_names = self.segment_names.clone();
+        chronometer.countdown_from = self.countdown_from;
+        chronometer.overtime = self.overtime;
+        chronometer.auto_lap = self.auto_lap;
+        chronometer.projects = ProjectTimes::new(self.projects.current.clone());
+        chronometer
+    }
+
+    pub fn state(&self) -> ChronoState {
+        self.state
+    }
+
+    // Started, whether counting or paused
+    pub fn is_started(&self) -> bool {
+        self.state != ChronoState::Idle
+    }
+
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, ChronoState::Paused { .. })
+    }
+
+    pub fn is_counting(&self) -> bool {
+        matches!(self.state, ChronoState::Running { .. })
+    }
+
+    pub fn start(&mut self) -> Result<(), TransitionError> {
+        self.start_since(Duration::ZERO)
+    }
+
+    // Counts on as if the time since the event had already elapsed
+    pub fn start_since(&mut self, elapsed: Duration) -> Result<(), TransitionError> {
+        if self.is_started() {
+            return Err(TransitionError::AlreadyStarted);
+        }
+        self.begin(clock::now(), elapsed);
+        Ok(())
+    }
+
+    // Starts a new session from any state; callers decide what to keep
+    fn begin(&mut self, since: Instant, elapsed: Duration) {
+        self.state = ChronoState::Running {
+            since,
+            accumulated: elapsed,
+        };
+        self.frozen = None;
+        self.begun_at = elapsed;
+        self.projects.restart(elapsed, elapsed);
+        self.paused_before = Duration::ZERO;
+        self.pauses.clear();
+        self.paused_at = None;
+        self.start_timestamp = clock::system_now();
+        self.align_auto_lap();
+    }
+
+    // Auto-laps go on from the elapsed time rather than filling in the
+    // multiples a start, reset or undo jumped over
+    fn align_auto_lap(&mut self) {
+        if let Some(interval) = self.auto_lap {
+            let elapsed = self.elapsed();
+            let past = elapsed.as_nanos() % interval.as_nanos();
+            self.auto_lap_mark = elapsed - Duration::from_nanos(past as u64);
+        }
+    }
+
+    pub fn restore(&mut self, state: &SessionState) -> Result<(), TransitionError> {
+        self.start_since(Duration::from_millis(state.elapsed_ms))?;
+        self.start_timestamp = UNIX_EPOCH + Duration::from_millis(state.start_timestamp_ms);
+        for &lap_ms in &state.laps_ms {
+            let lap = Duration::from_millis(lap_ms);
+            self.lap_durations.push(lap);
+            // The state file has no lap timestamps; this ignores pauses
+            self.lap_timestamps.push(self.start_timestamp + lap);
+            self.lap_ids.push(self.next_lap_id);
+            self.next_lap_id += 1;
+        }
+        let projects: Vec<_> = state
+            .projects_ms
+            .iter()
+            .map(|(project, ms)| (project.clone(), Duration::from_millis(*ms)))
+            .collect();
+        let elapsed = self.elapsed();
+        self.projects.restore(&projects, elapsed, elapsed);
+        if state.paused {
+            self.pause()?;
+        }
+
+        // Log resume event
+        self.log_line(&format!("Session restored at: {}", self.wall_clock()));
+        Ok(())
+    }
+
+    // Shows `laps`, cumulative, as a finished session: the clock stands
+    // still at the last one
+    pub fn review(&mut self, laps: &[Duration]) -> Result<(), TransitionError> {
+        let end = laps.last().copied().unwrap_or_default();
+        self.start_since(end)?;
+        self.start_timestamp = clock::system_now() - end;
+        for &lap in laps {
+            self.lap_durations.push(lap);
+            self.lap_timestamps.push(self.start_timestamp + lap);
+            self.lap_ids.push(self.next_lap_id);
+            self.next_lap_id += 1;
+        }
+        // Not a pause of the session's own, so the report shows none
+        self.state = ChronoState::Paused {
+            accumulated: end,
+            since: clock::now(),
+        };
+        self.read_only = true;
+        Ok(())
+    }
+
+    // Takes on another process's session as `chronorust attach` sees the
+    // daemon's, with None for one that hasn't started. Nothing is logged;
+    // the session and its log belong to the other process.
+    pub fn follow(&mut self, state: Option<&SessionState>) {
+        let Some(state) = state else {
+            self.state = ChronoState::Idle;
+            self.lap_durations.clear();
+            self.lap_timestamps.clear();
+            self.lap_ids.clear();
+            self.projects = ProjectTimes::default();
+            return;
+        };
+        let accumulated = Duration::from_millis(state.elapsed_ms);
+        self.state = match self.state {
+            // Kept still between syncs, like the other side
+            ChronoState::Paused { since, .. } if state.paused => {
+                ChronoState::Paused { accumulated, since }
+            }
+            _ if state.paused => ChronoState::Paused {
+                accumulated,
+                since: clock::now(),
+            },
+            _ => ChronoState::Running {
+                since: clock::now(),
+                accumulated,
+            },
+        };
+        self.start_timestamp = UNIX_EPOCH + Duration::from_millis(state.start_timestamp_ms);
+        self.lap_durations = state
+            .laps_ms
+            .iter()
+            .map(|&ms| Duration::from_millis(ms))
+            .collect();
+        self.lap_timestamps = self
+            .lap_durations
+            .iter()
+            .map(|&lap| self.start_timestamp + lap)
+            .collect();
+        self.lap_ids = (1..=self.lap_durations.len()).collect();
+        self.next_lap_id = self.lap_ids.len() + 1;
+        let projects: Vec<_> = state
+            .projects_ms
+            .iter()
+            .map(|(project, ms)| (project.clone(), Duration::from_millis(*ms)))
+            .collect();
+        self.projects = ProjectTimes::default();
+        self.projects
+            .restore(&projects, Duration::ZERO, accumulated);
+    }
+
+    pub fn session_state(&self) -> SessionState {
+        let millis = |d: Duration| d.as_millis() as u64;
+        SessionState {
+            elapsed_ms: millis(self.elapsed()),
+            laps_ms: self.lap_durations.iter().map(|&lap| millis(lap)).collect(),
+            paused: self.is_paused(),
+            projects_ms: self
+                .project_totals()
+                .into_iter()
+                .map(|(project, time)| (project, millis(time)))
+                .collect(),
+            start_timestamp_ms: self
+                .start_timestamp
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, millis),
+        }
+    }
+
+    // Stays idle until the "get ready" countdown crosses zero in tick()
+    pub fn start_with_prestart(&mut self, countdown: Duration) -> Result<(), TransitionError> {
+        if self.is_started() {
+            return Err(TransitionError::AlreadyStarted);
+        }
+        let go = clock::now()
+            .checked_add(countdown)
+            .ok_or(TransitionError::CountdownTooLong)?;
+        self.prestart_until = Some(go);
+        Ok(())
+    }
+
+    pub fn tick(&mut self) {
+        if let Some(go) = self.prestart_until {
+            if clock::now() >= go {
+                self.prestart_until = None;
+                // Anchor to the exact zero crossing rather than the poll wake-up
+                self.begin(go, Duration::ZERO);
+            }
+        }
+
+        // Auto-laps land on the exact multiples of the interval, however late
+        // the tick that notices them
+        if let Some(interval) = self.auto_lap.filter(|_| self.is_counting()) {
+            let elapsed = self.elapsed();
+            while self.auto_lap_mark + interval <= elapsed {
+                self.auto_lap_mark += interval;
+                let late = elapsed - self.auto_lap_mark;
+                let (now, timestamp) = (clock::now(), clock::system_now());
+                let at = (now.checked_sub(late).unwrap_or(now), timestamp - late);
+                self.record_lap(self.auto_lap_mark, at, true);
+            }
+        }
+
+        // Only key events refresh last_activity, so idle redraws add up
+        if let Some(threshold) = self.idle_pause {
+            if self.is_counting()
+                && clock::now().saturating_duration_since(self.last_activity) >= threshold
+                && self.pause_for(Some("idle")).is_ok()
+            {
+                self.auto_paused = true;
+            }
+        }
+
+        // A pause that runs out of budget ends on its own
+        if self.pause_remaining() == Some(Duration::ZERO)
+            && self.resume_for(Some("pause budget used up")).is_ok()
+        {
+            self.auto_paused = false;
+        }
+    }
+
+    pub fn paused_total(&self) -> Duration {
+        let current = match self.state {
+            ChronoState::Paused { since, .. } => clock::now().saturating_duration_since(since),
+            _ => Duration::ZERO,
+        };
+        self.paused_before + current
+    }
+
+    pub fn pause_remaining(&self) -> Option<Duration> {
+        self.pause_budget
+            .map(|budget| budget.saturating_sub(self.paused_total()))
+    }
+
+    // Warn over the last tenth of the budget
+    pub fn pause_budget_low(&self) -> bool {
+        match (self.pause_budget, self.pause_remaining()) {
+            (Some(budget), Some(remaining)) => remaining <= budget / 10,
+            _ => false,
+        }
+    }
+
+    pub fn prestart_remaining(&self) -> Option<Duration> {
+        self.prestart_until
+            .map(|go| go.saturating_duration_since(clock::now()))
+    }
+
+    // A moment in --datetime-format if one was given, else --time-format
+    pub fn format_datetime<Tz: chrono::TimeZone>(&self, datetime: &chrono::DateTime<Tz>) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        match self.datetime_format {
+            Some(ref format) => datetime.format(format).to_string(),
+            None => self.time_format.datetime(datetime),
+        }
+    }
+
+    pub fn wall_clock(&self) -> String {
+        self.format_time(clock::system_now())
+    }
+
+    // A wall-clock moment as log lines show it
+    pub fn format_time(&self, time: SystemTime) -> String {
+        self.format_datetime(&utc(time))
+    }
+
+    // A lap's moment, to the millisecond unless --datetime-format says
+    // otherwise, so laps line up with other systems' logs
+    fn format_lap_time(&self, time: SystemTime) -> String {
+        match self.datetime_format {
+            Some(ref format) => utc(time).format(format).to_string(),
+            None => self.time_format.datetime_millis(&utc(time)),
+        }
+    }
+
+    // Timestamp part of generated file names such as the log
+    pub fn file_stamp(&self, datetime: chrono::DateTime<chrono::Utc>) -> String {
+        // The default name keeps its historical compact layout
+        match self.datetime_format {
+            Some(ref format) => sanitize_filename(&datetime.format(format).to_string()),
+            None => datetime.format("%d-%m-%y-%H-%M-%S").to_string(),
+        }
+    }
+
+    // Generated files go under --output-dir when one is set
+    pub fn output_path(&self, filename: &str) -> PathBuf {
+        match self.output_dir {
+            Some(ref dir) => dir.join(filename),
+            None => PathBuf::from(filename),
+        }
+    }
+
+    // Writes every lap to ChronoRust-<timestamp>.csv or .json
+    pub fn export_laps(&self, format: ExportFormat) -> io::Result<PathBuf> {
+        let filename = self.output_path(&format!(
+            "ChronoRust-{}.{}",
+            self.file_stamp(chrono::Utc::now()),
+            format.extension()
+        ));
+        let contents = match format {
+            ExportFormat::Csv => export::laps_csv(self),
+            ExportFormat::Json => export::laps_json(self),
+            ExportFormat::Lss => export::laps_lss(self),
+        };
+        std::fs::write(&filename, contents)?;
+        Ok(filename)
+    }
+
+    // Logs to `path`, or to ChronoRust-<timestamp>-log.txt (.jsonl) under
+    // --output-dir, returning the file written to
+    pub fn enable_logging(
+        &mut self,
+        path: Option<&Path>,
+        format: LogFormat,
+    ) -> io::Result<PathBuf> {
+        let filename = match path {
+            Some(path) => path.to_path_buf(),
+            None => self.output_path(&format!(
+                "ChronoRust-{}-log.{}",
+                self.file_stamp(chrono::Utc::now()),
+                format.extension()
+            )),
+        };
+        self.logger = Some(Logger::create(&filename, format)?);
+        self.log_event(LogEvent::SessionStart);
+        Ok(filename)
+    }
+
+    pub fn reset(&mut self) {
+        self.restart_session(Duration::new(0, 0));
+        self.log_event(LogEvent::Reset { to: None });
+    }
+
+    pub fn reset_to(&mut self, offset: Duration) {
+        self.restart_session(offset);
+        let offset_text = self.format_duration(offset);
+        self.log_event(LogEvent::Reset {
+            to: Some(&offset_text),
+        });
+    }
+
+    fn restart_session(&mut self, offset: Duration) {
+        let backup = ResetBackup {
+            lap_durations: std::mem::take(&mut self.lap_durations),
+            lap_timestamps: std::mem::take(&mut self.lap_timestamps),
+            lap_ids: std::mem::take(&mut self.lap_ids),
+            lap_notes: std::mem::take(&mut self.lap_notes),
+            auto_lap_ids: std::mem::take(&mut self.auto_lap_ids),
+            lap_projects: std::mem::take(&mut self.lap_projects),
+            projects: self.projects.restart(self.elapsed(), offset),
+            next_lap_id: self.next_lap_id,
+            elapsed: self.elapsed(),
+            begun_at: self.begun_at,
+        };
+        self.reset_tracked += backup.elapsed.saturating_sub(backup.begun_at);
+        self.push_undo(UndoEntry::Reset(backup));
+
+        self.begin(clock::now(), offset);
+        self.next_lap_id = 1;
+        self.prestart_until = None;
+    }
+
+    // Makes elapsed() continue from `elapsed`, keeping the run/pause state
+    // and the pause total that a pause budget is measured against
+    fn rebase(&mut self, elapsed: Duration) {
+        self.state = match self.state {
+            ChronoState::Idle => ChronoState::Idle,
+            ChronoState::Running { .. } => ChronoState::Running {
+                since: clock::now(),
+                accumulated: elapsed,
+            },
+            ChronoState::Paused { since, .. } => ChronoState::Paused {
+                accumulated: elapsed,
+                since,
+            },
+        };
+    }
+
+    // Discards the split in progress, e.g. after a false start mid-session
+    pub fn restart_from_lap(&mut self) -> bool {
+        if !self.is_started() {
+            return false;
+        }
+        let boundary = self.lap_durations.last().copied().unwrap_or_default();
+        self.rebase(boundary);
+        self.align_auto_lap();
+
+        // Log restart event
+        self.log_line(&format!(
+            "Restarted from {} at: {}",
+            self.format_duration(boundary),
+            self.wall_clock()
+        ));
+
+        true
+    }
+
+    fn push_undo(&mut self, entry: UndoEntry) {
+        if self.undo_stack.len() == UNDO_LIMIT {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(entry);
+    }
+
+    // Reverts the latest reset or lap deletion not undone yet
+    pub fn undo(&mut self) -> Option<Undone> {
+        let undone = match self.undo_stack.pop()? {
+            UndoEntry::Reset(backup) => {
+                self.undo_reset(backup);
+                Undone::Reset
+            }
+            UndoEntry::DeleteLap(lap) => {
+                let lap_id = lap.id;
+                self.undo_delete_lap(lap);
+                Undone::DeleteLap(lap_id)
+            }
+        };
+        Some(undone)
+    }
+
+    fn undo_reset(&mut self, backup: ResetBackup) {
+        // Time spent since the reset is carried on top of the restored
+        // elapsed time
+        let since_reset = self.elapsed();
+        self.projects
+            .restore(&backup.projects, since_reset, backup.elapsed + since_reset);
+        self.rebase(backup.elapsed + since_reset);
+        self.reset_tracked = self
+            .reset_tracked
+            .saturating_sub(backup.elapsed.saturating_sub(backup.begun_at));
+        self.begun_at = backup.begun_at;
+        self.align_auto_lap();
+
+        // Laps taken after the reset are rebased onto the restored session
+        let mut next_lap_id = backup.next_lap_id;
+        let mut lap_durations = backup.lap_durations;
+        let mut lap_timestamps = backup.lap_timestamps;
+        let mut lap_ids = backup.lap_ids;
+        let mut lap_notes = backup.lap_notes;
+        let mut auto_lap_ids = backup.auto_lap_ids;
+        let mut lap_projects = backup.lap_projects;
+        let reset_lap_ids = std::mem::take(&mut self.lap_ids);
+        for (lap, old_id) in std::mem::take(&mut self.lap_durations)
+            .into_iter()
+            .zip(reset_lap_ids)
+        {
+            let rebased = backup.elapsed + lap;
+            lap_durations.push(rebased);
+            lap_ids.push(next_lap_id);
+            // Notes and auto-lap marks follow their lap onto its new id
+            if let Some(note) = self.lap_notes.remove(&old_id) {
+                lap_notes.insert(next_lap_id, note);
+            }
+            if self.auto_lap_ids.remove(&old_id) {
+                auto_lap_ids.insert(next_lap_id);
+            }
+            if let Some(project) = self.lap_projects.remove(&old_id) {
+                lap_projects.insert(next_lap_id, project);
+            }
+            next_lap_id += 1;
+        }
+        lap_timestamps.append(&mut self.lap_timestamps);
+        self.lap_durations = lap_durations;
+        self.lap_timestamps = lap_timestamps;
+        self.lap_ids = lap_ids;
+        self.lap_notes = lap_notes;
+        self.auto_lap_ids = auto_lap_ids;
+        self.lap_projects = lap_projects;
+        self.next_lap_id = next_lap_id;
+
+        // Log undo event
+        self.log_line(&format!("Reset undone at: {}", self.wall_clock()));
+    }
+
+    // Ids only grow, so the lap goes back in front of the first later one
+    fn undo_delete_lap(&mut self, lap: DeletedLap) {
+        let index = self.lap_ids.partition_point(|&id| id < lap.id);
+        self.lap_durations.insert(index, lap.duration);
+        self.lap_timestamps.insert(index, lap.timestamp);
+        self.lap_ids.insert(index, lap.id);
+        if let Some(note) = lap.note {
+            self.lap_notes.insert(lap.id, note);
+        }
+        if lap.auto {
+            self.auto_lap_ids.insert(lap.id);
+        }
+        if let Some(project) = lap.project {
+            self.lap_projects.insert(lap.id, project);
+        }
+
+        // Log undo event
+        self.log_line(&format!(
+            "Lap {} restored at: {}",
+            lap.id,
+            self.wall_clock()
+        ));
+    }
+
+    pub fn record_activity(&mut self) -> bool {
+        self.last_activity = clock::now();
+        if !self.auto_paused {
+            return false;
+        }
+
+        self.auto_paused = false;
+        let _ = self.resume_for(Some("activity"));
+        true
+    }
+
+    pub fn pause(&mut self) -> Result<(), TransitionError> {
+        self.pause_for(None)
+    }
+
+    pub fn resume(&mut self) -> Result<(), TransitionError> {
+        self.resume_for(None)
+    }
+
+    // `reason` tells the log why the clock paused on its own
+    fn pause_for(&mut self, reason: Option<&str>) -> Result<(), TransitionError> {
+        let ChronoState::Running { since, accumulated } = self.state else {
+            return Err(match self.state {
+                ChronoState::Idle => TransitionError::NotStarted,
+                _ => TransitionError::AlreadyPaused,
+            });
+        };
+        // An exhausted pause budget refuses further pauses
+        if self.pause_remaining() == Some(Duration::ZERO) {
+            return Err(TransitionError::PauseBudgetUsed);
+        }
+        let now = clock::now();
+        self.state = ChronoState::Paused {
+            accumulated: accumulated + now.saturating_duration_since(since),
+            since: now,
+        };
+        self.paused_at = Some(clock::system_now());
+        self.log_event(LogEvent::Pause { reason });
+        Ok(())
+    }
+
+    fn resume_for(&mut self, reason: Option<&str>) -> Result<(), TransitionError> {
+        let ChronoState::Paused { accumulated, since } = self.state else {
+            return Err(TransitionError::NotPaused);
+        };
+        let now = clock::now();
+        self.paused_before += now.saturating_duration_since(since);
+        if let Some(start) = self.paused_at.take() {
+            self.pauses.push((start, clock::system_now()));
+        }
+        self.state = ChronoState::Running {
+            since: now,
+            accumulated,
+        };
+        self.log_event(LogEvent::Resume { reason });
+        Ok(())
+    }
+
+    // The pause key: resumes a paused clock, pauses a running one
+    pub fn toggle_pause(&mut self) -> Result<(), TransitionError> {
+        if self.is_paused() {
+            self.resume()
+        } else {
+            self.pause()
+        }
+    }
+
+    pub fn lap(&mut self) {
+        if self.is_started() {
+            // Both clocks read back to back, so the lap's wall-clock time
+            // and its elapsed time are the same moment
+            let (now, timestamp) = (clock::now(), clock::system_now());
+            self.record_lap(self.state.elapsed_at(now), (now, timestamp), false);
+        }
+    }
+
+    fn record_lap(&mut self, elapsed: Duration, at: (Instant, SystemTime), auto: bool) {
+        let lap_id = self.next_lap_id;
+        self.next_lap_id += 1;
+        self.lap_durations.push(elapsed);
+        self.lap_timestamps.push(at.1);
+        self.lap_ids.push(lap_id);
+        if auto {
+            self.auto_lap_ids.insert(lap_id);
+        }
+        let project = self.projects.current.clone();
+        if let Some(ref project) = project {
+            self.lap_projects.insert(lap_id, project.clone());
+        }
+
+        let formatted = self.format_duration(elapsed);
+        let pace = self
+            .pace_delta(self.lap_durations.len() - 1)
+            .map(|delta| self.format_pace_delta(delta));
+        self.log_event(LogEvent::Lap {
+            id: lap_id,
+            time: elapsed,
+            formatted: &formatted,
+            pace: pace.as_deref(),
+            auto,
+            project: project.as_deref(),
+            at,
+        });
+    }
+
+    pub fn is_auto_lap(&self, index: usize) -> bool {
+        self.auto_lap_ids.contains(&self.lap_ids[index])
+    }
+
+    pub fn current_project(&self) -> Option<&str> {
+        self.projects.current.as_deref()
+    }
+
+    // New laps go under `project` from now on, and so does the time
+    pub fn set_project(&mut self, project: Option<String>) {
+        let elapsed = self.elapsed();
+        self.projects.switch(project, elapsed);
+        let line = match self.current_project() {
+            Some(project) => format!("Project set to {} at: {}", project, self.wall_clock()),
+            None => format!("Project cleared at: {}", self.wall_clock()),
+        };
+        self.log_line(&line);
+    }
+
+    pub fn lap_project(&self, lap_id: usize) -> Option<&str> {
+        self.lap_projects.get(&lap_id).map(String::as_str)
+    }
+
+    // Time per tag this session, in the order the tags were first used
+    pub fn project_totals(&self) -> Vec<(String, Duration)> {
+        self.projects.totals(self.elapsed())
+    }
+
+    pub fn has_projects(&self) -> bool {
+        !self.projects.is_empty()
+    }
+
+    // Cumulative time of every recorded lap, oldest first
+    pub fn laps(&self) -> &[Duration] {
+        &self.lap_durations
+    }
+
+    pub fn delete_lap(&mut self, lap_id: usize) -> bool {
+        // Ids are never reused, so the remaining laps keep their identity
+        let Some(index) = self.lap_ids.iter().position(|&id| id == lap_id) else {
+            return false;
+        };
+        let deleted = DeletedLap {
+            id: self.lap_ids.remove(index),
+            duration: self.lap_durations.remove(index),
+            timestamp: self.lap_timestamps.remove(index),
+            note: self.lap_notes.remove(&lap_id),
+            auto: self.auto_lap_ids.remove(&lap_id),
+            project: self.lap_projects.remove(&lap_id),
+        };
+        self.push_undo(UndoEntry::DeleteLap(deleted));
+
+        // Log delete event
+        self.log_line(&format!("Lap {} deleted at: {}", lap_id, self.wall_clock()));
+
+        true
+    }
+
+    pub fn set_lap_note(&mut self, lap_id: usize, note: &str) {
+        let note = note.trim();
+        if note.is_empty() {
+            self.lap_notes.remove(&lap_id);
+            self.log_line(&format!(
+                "Lap {} note removed at: {}",
+                lap_id,
+                self.wall_clock()
+            ));
+        } else {
+            self.lap_notes.insert(lap_id, note.to_string());
+            self.log_line(&format!(
+                "Lap {} note at: {} - {}",
+                lap_id,
+                self.wall_clock(),
+                note
+            ));
+        }
+    }
+
+    pub fn log_line(&mut self, line: &str) {
+        self.log_event(LogEvent::Message(line));
+    }
+
+    pub fn log_event(&mut self, event: LogEvent) {
+        if let Some(ref hooks) = self.hooks {
+            hooks.fire(&event, &self.format_duration(self.elapsed()));
+        }
+        if let Some(ref broadcast) = self.broadcast {
+            let _ = broadcast.send(logging::event_json(&event));
+        }
+        if self.logger.is_none() {
+            return;
+        }
+        let wall_clock = match event.moment() {
+            Some((_, time)) => self.format_lap_time(time),
+            None => self.wall_clock(),
+        };
+        if let Some(ref mut logger) = self.logger {
+            if let Err(err) = logger.write(&event, &wall_clock) {
+                self.log_error = Some(err.to_string());
+            }
+        }
+    }
+
+    // A hook for an event the log has no entry of its own for
+    pub fn run_hook(&self, name: &str) {
+        if let Some(ref hooks) = self.hooks {
+            let elapsed = self.format_duration(self.elapsed());
+            hooks.run(name, &[&elapsed], &elapsed, &[]);
+        }
+    }
+
+    pub fn take_log_error(&mut self) -> Option<String> {
+        self.log_error.take()
+    }
+
+    // The last log entry, with the exit report
+    pub fn log_quit(&mut self) {
+        let report = self.report();
+        let format = |duration| self.format_duration(duration);
+        let elapsed = format(report.elapsed);
+        let paused = format(report.paused);
+        let best = report.stats.as_ref().map(|stats| format(stats.best));
+        let worst = report.stats.as_ref().map(|stats| format(stats.worst));
+        let target = self
+            .target_delta()
+            .map(|delta| self.format_pace_delta(delta));
+        self.log_event(LogEvent::Quit {
+            elapsed: (report.elapsed, &elapsed),
+            paused: (report.paused, &paused),
+            pauses: report.pauses.len(),
+            laps: report.laps,
+            best: best.as_deref(),
+            worst: worst.as_deref(),
+            target: target.as_deref(),
+        });
+        // Nothing fires after the quit; dropping the hooks lets the last
+        // ones start before the program exits
+        self.hooks = None;
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.state.elapsed()
+    }
+
+    // Elapsed time counted since the program started, across resets
+    pub fn tracked(&self) -> Duration {
+        self.reset_tracked + self.elapsed().saturating_sub(self.begun_at)
+    }
+
+    pub fn cycle_precision(&mut self) {
+        self.precision = self.precision.next();
+
+        // Log precision change
+        self.log_line(&format!(
+            "Precision set to {} at: {}",
+            self.precision.label(),
+            self.wall_clock()
+        ));
+    }
+
+    pub fn format_duration(&self, duration: Duration) -> String {
+        let duration = self.rounding.apply(duration, self.precision.unit());
+        match self.precision {
+            Precision::Seconds => format_seconds(duration),
+            Precision::Millis => format_millis(duration),
+            Precision::Micro => format_micros(duration),
+        }
+    }
+
+    // The time shown, which is the moment it froze while frozen
+    pub fn display(&self) -> String {
+        let (elapsed, now) = self
+            .frozen
+            .unwrap_or_else(|| (self.elapsed(), clock::system_now()));
+        if let Some(remaining) = self.prestart_remaining() {
+            // Count -3, -2, -1 so the last second before zero still shows -1
+            let secs = remaining.as_millis().div_ceil(1_000);
+            format!("GET READY  -{}", secs)
+        } else if let Some(remaining) = self.remaining_at(elapsed, now) {
+            if let Some(over) = self.overtime_at(elapsed) {
+                format!("-{}", self.format_duration(over))
+            } else if remaining.is_zero() && self.is_started() {
+                format!("{}  TIME UP", self.format_duration(remaining))
+            } else {
+                self.format_duration(remaining)
+            }
+        } else if self.is_started() {
+            self.format_duration(elapsed)
+        } else {
+            self.format_duration(Duration::new(0, 0))
+        }
+    }
+
+    // Freezes the shown time, or unfreezes it; true when now frozen. Only
+    // a started clock freezes.
+    pub fn toggle_freeze(&mut self) -> bool {
+        self.frozen = match self.frozen {
+            None if self.is_started() => Some((self.elapsed(), clock::system_now())),
+            _ => None,
+        };
+        self.frozen.is_some()
+    }
+
+    // In countdown mode the main display shows the time left instead. An
+    // alarm follows the wall clock, so pauses and resets don't move it.
+    pub fn countdown_remaining(&self) -> Option<Duration> {
+        self.remaining_at(self.elapsed(), clock::system_now())
+    }
+
+    fn remaining_at(&self, elapsed: Duration, now: SystemTime) -> Option<Duration> {
+        if let Some(at) = self.alarm_at {
+            return Some(at.duration_since(now).unwrap_or_default());
+        }
+        self.countdown_from.map(|from| from.saturating_sub(elapsed))
+    }
+
+    // How far past zero a countdown with overtime on has run
+    pub fn countdown_overtime(&self) -> Option<Duration> {
+        self.overtime_at(self.elapsed())
+    }
+
+    fn overtime_at(&self, elapsed: Duration) -> Option<Duration> {
+        let from = self
+            .countdown_from
+            .filter(|_| self.overtime && self.is_started())?;
+        elapsed.checked_sub(from)
+    }
+
+    pub fn state_label(&self) -> &'static str {
+        if self.prestart_until.is_some() {
+            "prestart"
+        } else if self.is_paused() {
+            "paused"
+        } else if self.is_started() {
+            "running"
+        } else {
+            "stopped"
+        }
+    }
+
+    pub fn get_lap_differences(&self) -> Vec<String> {
+        let mut differences = Vec::new();
+
+        if self.lap_durations.len() <= 1 {
+            return differences;
+        }
+
+        for i in 1..self.lap_durations.len() {
+            let prev_lap = self.lap_durations[i - 1];
+            let current_lap = self.lap_durations[i];
+            let diff = current_lap - prev_lap;
+            differences.push(self.format_duration(diff));
+        }
+
+        differences
+    }
+
+    pub fn lap_splits(&self) -> Vec<Duration> {
+        // Unlike get_lap_differences, the first lap counts from zero
+        let mut previous = Duration::new(0, 0);
+        self.lap_durations
+            .iter()
+            .map(|&lap| {
+                let split = lap.saturating_sub(previous);
+                previous = lap;
+                split
+            })
+            .collect()
+    }
+
+    // Lap N is compared against N x target pace; true means ahead of it
+    pub fn pace_delta(&self, index: usize) -> Option<(Duration, bool)> {
+        let pace = self.target_pace?;
+        let actual = *self.lap_durations.get(index)?;
+        // A --pace too large to multiply out has no target to compare with
+        let target = pace.checked_mul(u32::try_from(index + 1).ok()?)?;
+        if actual <= target {
+            Some((target - actual, true))
+        } else {
+            Some((actual - target, false))
+        }
+    }
+
+    // Cumulative laps from a JSON or CSV lap export, used as the read-only
+    // reference set
+    pub fn import_reference(path: &Path) -> io::Result<Vec<Duration>> {
+        let text = std::fs::read_to_string(path)?;
+        let laps = compare::parse_reference(&text)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if laps.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no laps found"));
+        }
+        Ok(laps)
+    }
+
+    // An empty name in the file leaves the lap unnamed
+    pub fn segment_name(&self, index: usize) -> Option<&str> {
+        self.segment_names
+            .get(index)
+            .map(String::as_str)
+            .filter(|name| !name.is_empty())
+    }
+
+    // Laps from a session log, text or JSON lines, or else a lap CSV
+    pub fn import_laps(path: &Path) -> io::Result<Vec<Duration>> {
+        let text = std::fs::read_to_string(path)?;
+        let first = text.lines().map(str::trim).find(|line| !line.is_empty());
+        let is_log = first.is_some_and(|line| {
+            line.starts_with('{') || line.starts_with("ChronoRust Session Started")
+        });
+        let laps = if is_log {
+            logging::parse_log_laps(&text)
+        } else {
+            export::parse_lap_csv(&text)
+        }
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        if laps.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no laps found"));
+        }
+        Ok(laps)
+    }
+
+    pub fn import_lss_segments(path: &Path) -> io::Result<Vec<String>> {
+        let text = std::fs::read_to_string(path)?;
+        export::parse_lss_segments(&text)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    // Lap `index` against the same lap of the reference session
+    pub fn reference_comparison(&self, index: usize) -> Option<compare::LapComparison> {
+        compare::compare_lap(&self.reference_laps, &self.lap_durations, index)
+    }
+
+    // Ahead of or behind --target, live
+    pub fn target_delta(&self) -> Option<(Duration, bool)> {
+        self.target.delta(self.elapsed(), &self.lap_durations)
+    }
+
+    pub fn running_pace_delta(&self) -> Option<(Duration, bool)> {
+        self.pace_delta(self.lap_durations.len().checked_sub(1)?)
+    }
+
+    pub fn format_pace_delta(&self, (amount, ahead): (Duration, bool)) -> String {
+        let sign = if ahead { '-' } else { '+' };
+        format!("{}{}", sign, self.format_duration(amount))
+    }
+
+    // Warm-up laps excluded by --skip-laps, clamped to the laps recorded
+    pub fn skipped_laps(&self) -> usize {
+        self.skip_laps.min(self.lap_durations.len())
+    }
+
+    pub fn is_lap_skipped(&self, index: usize) -> bool {
+        index < self.skipped_laps()
+    }
+
+    pub fn counted_splits(&self) -> Vec<Duration> {
+        self.lap_splits().split_off(self.skipped_laps())
+    }
+
+    pub fn report(&self) -> SessionReport {
+        let ended = clock::system_now();
+        let mut pauses = self.pauses.clone();
+        pauses.extend(self.paused_at.map(|start| (start, ended)));
+        let paused = pauses
+            .iter()
+            .map(|(start, end)| end.duration_since(*start).unwrap_or_default())
+            .sum();
+        SessionReport {
+            started: self.start_timestamp,
+            ended,
+            elapsed: self.elapsed(),
+            pauses,
+            paused,
+            laps: self.lap_durations.len(),
+            stats: self.lap_stats(),
+        }
+    }
+
+    pub fn lap_stats(&self) -> Option<LapStats> {
+        let splits = self.counted_splits();
+        let total: Duration = splits.iter().sum();
+        Some(LapStats {
+            best: *splits.iter().min()?,
+            worst: *splits.iter().max()?,
+            average: total / splits.len() as u32,
+        })
+    }
+
+    // Min, max, mean, median and spread of the counted splits
+    pub fn split_stats(&self) -> Option<SplitStats> {
+        stats::split_stats(&self.counted_splits())
+    }
+
+    pub fn distance_stats(&self) -> Option<DistanceStats> {
+        let lap_meters = self.lap_distance?;
+        let total_meters = lap_meters * self.lap_durations.len() as f64;
+
+        // Speed and pace follow the counted laps, like the other lap stats
+        let splits = self.counted_splits();
+        let seconds: f64 = splits.iter().map(Duration::as_secs_f64).sum();
+        let km = lap_meters * splits.len() as f64 / 1_000.0;
+        let (speed_kmh, pace_per_km) = if seconds > 0.0 && km > 0.0 {
+            (
+                Some(km / (seconds / 3_600.0)),
+                Duration::try_from_secs_f64(seconds / km).ok(),
+            )
+        } else {
+            (None, None)
+        };
+
+        Some(DistanceStats {
+            total_meters,
+            speed_kmh,
+            pace_per_km,
+        })
+    }
+
+    pub fn project(&self, target: Duration) -> Projection {
+        let elapsed = self.elapsed();
+        let remaining = target.saturating_sub(elapsed);
+        let past_target = elapsed >= target;
+
+        // Whole laps that still fit before the target at the average pace
+        let laps_remaining = self.lap_stats().and_then(|stats| {
+            let average = stats.average.as_nanos();
+            (average > 0).then(|| (remaining.as_nanos() / average) as usize)
+        });
+
+        // Pace needed to finish the target lap count exactly on target
+        let required_pace = self.target_laps.and_then(|laps| {
+            let left = laps.checked_sub(self.lap_durations.len())?;
+            if past_target || left == 0 {
+                return None;
+            }
+            // Time from the last recorded lap boundary, not from now
+            let since = self.lap_durations.last().copied().unwrap_or_default();
+            Some(target.saturating_sub(since) / left as u32)
+        });
+
+        Projection {
+            remaining,
+            laps_remaining,
+            required_pace,
+            past_target,
+        }
+    }
+
+    // Laps per `bucket`-sized window of the session, from window 0 up to the
+    // last lap's window (empty windows included). A lap exactly on a
+    // boundary opens the next window.
+    pub fn lap_histogram(&self, bucket: Duration) -> Vec<(usize, usize)> {
+        let bucket_ns = bucket.as_nanos().max(1);
+        let mut counts: Vec<usize> = Vec::new();
+        for lap in &self.lap_durations {
+            let index = (lap.as_nanos() / bucket_ns) as usize;
+            if counts.len() <= index {
+                counts.resize(index + 1, 0);
+            }
+            counts[index] += 1;
+        }
+        counts.into_iter().enumerate().collect()
+    }
+
+    pub fn lap_rate(&self, unit: RateUnit) -> Option<f64> {
+        let splits = self.counted_splits();
+        if splits.is_empty() {
+            return None;
+        }
+        let total_ms: f64 = splits.iter().map(|d| d.as_secs_f64() * 1_000.0).sum();
+        rate_from_ms(total_ms / splits.len() as f64, unit)
+    }
+
+    pub fn last_lap_rate(&self, unit: RateUnit) -> Option<f64> {
+        self.counted_splits()
+            .last()
+            .and_then(|d| rate_from_ms(d.as_secs_f64() * 1_000.0, unit))
+    }
+}
+
+fn rate_from_ms(delta_ms: f64, unit: RateUnit) -> Option<f64> {
+    // A zero-length lap has no meaningful rate
+    if delta_ms > 0.0 {
+        Some(unit.window_ms() / delta_ms)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{ms, with_laps};
+
+    #[test]
+    fn counts_from_start() {
+        let mut chronometer = Chronometer::new();
+        assert_eq!(chronometer.elapsed(), Duration::ZERO);
+        assert_eq!(chronometer.state_label(), "stopped");
+
+        chronometer.start().unwrap();
+        clock::advance(ms(1_500));
+        assert_eq!(chronometer.elapsed(), ms(1_500));
+        assert_eq!(chronometer.state_label(), "running");
+        assert_eq!(chronometer.start(), Err(TransitionError::AlreadyStarted));
+    }
+
+    #[test]
+    fn pause_holds_the_time() {
+        let mut chronometer = Chronometer::new();
+        assert_eq!(chronometer.pause(), Err(TransitionError::NotStarted));
+        chronometer.start().unwrap();
+        clock::advance(ms(1_000));
+        chronometer.pause().unwrap();
+        assert_eq!(chronometer.pause(), Err(TransitionError::AlreadyPaused));
+        clock::advance(ms(5_000));
+        assert_eq!(chronometer.elapsed(), ms(1_000));
+        assert_eq!(chronometer.paused_total(), ms(5_000));
+
+        chronometer.resume().unwrap();
+        assert_eq!(chronometer.resume(), Err(TransitionError::NotPaused));
+        clock::advance(ms(250));
+        assert_eq!(chronometer.elapsed(), ms(1_250));
+    }
+
+    #[test]
+    fn laps_are_cumulative() {
+        let mut chronometer = Chronometer::new();
+        chronometer.lap();
+        assert!(chronometer.laps().is_empty());
+
+        chronometer.start().unwrap();
+        for step in [1_000, 1_500, 500] {
+            clock::advance(ms(step));
+            chronometer.lap();
+        }
+        assert_eq!(chronometer.laps(), [ms(1_000), ms(2_500), ms(3_000)]);
+        assert_eq!(chronometer.lap_splits(), [ms(1_000), ms(1_500), ms(500)]);
+        assert_eq!(
+            chronometer.get_lap_differences(),
+            ["00:00:01.500", "00:00:00.500"]
+        );
+        assert_eq!(chronometer.lap_ids, [1, 2, 3]);
+    }
+
+    #[test]
+    fn reset_starts_over() {
+        let mut chronometer = Chronometer::new();
+        chronometer.start().unwrap();
+        clock::advance(ms(2_000));
+        chronometer.lap();
+        chronometer.reset();
+        assert_eq!(chronometer.elapsed(), Duration::ZERO);
+        assert!(chronometer.laps().is_empty());
+        assert!(chronometer.is_counting());
+        assert_eq!(chronometer.next_lap_id, 1);
+
+        clock::advance(ms(300));
+        assert_eq!(chronometer.elapsed(), ms(300));
+    }
+
+    #[test]
+    fn lap_stats_skip_warm_up_laps() {
+        let mut chronometer = Chronometer::new();
+        assert!(chronometer.lap_stats().is_none());
+        chronometer.start().unwrap();
+        for step in [5_000, 1_000, 3_000, 2_000] {
+            clock::advance(ms(step));
+            chronometer.lap();
+        }
+        let stats = chronometer.lap_stats().unwrap();
+        assert_eq!(
+            (stats.best, stats.worst, stats.average),
+            (ms(1_000), ms(5_000), ms(2_750))
+        );
+
+        chronometer.skip_laps = 1;
+        let stats = chronometer.lap_stats().unwrap();
+        assert_eq!(
+            (stats.best, stats.worst, stats.average),
+            (ms(1_000), ms(3_000), ms(2_000))
+        );
+    }
+
+    #[test]
+    fn formats_each_precision() {
+        let duration = Duration::new(3_723, 456_789_000);
+        assert_eq!(format_seconds(duration), "01:02:03");
+        assert_eq!(format_millis(duration), "01:02:03.456");
+        assert_eq!(format_micros(duration), "01:02:03.456_789");
+        assert_eq!(
+            format_millis(Duration::from_secs(99 * 3_600)),
+            "99:00:00.000"
+        );
+    }
+
+    #[test]
+    fn display_follows_the_clock() {
+        let mut chronometer = Chronometer::new();
+        assert_eq!(chronometer.display(), "00:00:00.000");
+        chronometer.start().unwrap();
+        clock::advance(ms(61_042));
+        assert_eq!(chronometer.display(), "00:01:01.042");
+
+        chronometer.precision = Precision::Seconds;
+        assert_eq!(chronometer.display(), "00:01:01");
+        assert!(chronometer.toggle_freeze());
+        clock::advance(ms(5_000));
+        assert_eq!(chronometer.display(), "00:01:01");
+        assert!(!chronometer.toggle_freeze());
+        assert_eq!(chronometer.display(), "00:01:06");
+    }
+
+    #[test]
+    fn countdown_shows_time_left() {
+        let mut chronometer = Chronometer::new();
+        chronometer.countdown_from = Some(ms(10_000));
+        chronometer.start().unwrap();
+        clock::advance(ms(4_000));
+        assert_eq!(chronometer.display(), "00:00:06.000");
+        clock::advance(ms(7_000));
+        assert_eq!(chronometer.display(), "00:00:00.000  TIME UP");
+
+        chronometer.overtime = true;
+        assert_eq!(chronometer.display(), "-00:00:01.000");
+    }
+
+    #[test]
+    fn prestart_starts_at_zero_crossing() {
+        let mut chronometer = Chronometer::new();
+        chronometer.start_with_prestart(ms(3_000)).unwrap();
+        assert_eq!(chronometer.state_label(), "prestart");
+        clock::advance(ms(800));
+        assert_eq!(chronometer.display(), "GET READY  -3");
+
+        // The tick comes late, but the clock starts from the crossing
+        clock::advance(ms(2_500));
+        chronometer.tick();
+        assert!(chronometer.is_counting());
+        assert_eq!(chronometer.elapsed(), ms(300));
+    }
+
+    #[test]
+    fn deleted_lap_ids_are_not_reused() {
+        let mut chronometer = with_laps(&[1_000, 1_000, 1_000, 1_000]);
+        assert!(chronometer.delete_lap(2));
+        assert!(!chronometer.delete_lap(2));
+        assert_eq!(chronometer.lap_ids, [1, 3, 4]);
+        assert_eq!(chronometer.laps(), [ms(1_000), ms(3_000), ms(4_000)]);
+
+        chronometer.lap();
+        assert_eq!(chronometer.lap_ids, [1, 3, 4, 5]);
+    }
+
+    #[test]
+    fn undo_puts_a_reset_back() {
+        let mut chronometer = with_laps(&[1_000, 2_000]);
+        chronometer.set_lap_note(2, "windy");
+        clock::advance(ms(500));
+        chronometer.reset();
+        assert!(chronometer.laps().is_empty());
+
+        // The time since the reset carries on top of the restored session
+        clock::advance(ms(250));
+        assert!(chronometer.undo() == Some(Undone::Reset));
+        assert_eq!(chronometer.elapsed(), ms(3_750));
+        assert_eq!(chronometer.laps(), [ms(1_000), ms(3_000)]);
+        assert_eq!(chronometer.lap_ids, [1, 2]);
+        assert_eq!(chronometer.lap_notes[&2], "windy");
+        assert_eq!(chronometer.next_lap_id, 3);
+
+        // The backup is used up
+        assert!(chronometer.undo().is_none());
+    }
+
+    #[test]
+    fn laps_after_a_reset_follow_the_restored_ones() {
+        let mut chronometer = with_laps(&[1_000]);
+        chronometer.reset();
+        clock::advance(ms(400));
+        chronometer.lap();
+        chronometer.undo();
+        assert_eq!(chronometer.laps(), [ms(1_000), ms(1_400)]);
+        assert_eq!(chronometer.lap_ids, [1, 2]);
+    }
+
+    #[test]
+    fn paused_reset_stays_paused_after_undo() {
+        let mut chronometer = with_laps(&[1_000]);
+        chronometer.reset();
+        chronometer.pause().unwrap();
+        clock::advance(ms(5_000));
+        chronometer.undo();
+        assert!(chronometer.is_paused());
+        assert_eq!(chronometer.elapsed(), ms(1_000));
+    }
+
+    #[test]
+    fn micro_precision_round_trips() {
+        for nanos in [0, 1_000, 999_999_000, 1_234_567_000, 359_999_999_999_000] {
+            let duration = Duration::from_nanos(nanos);
+            let text = format_micros(duration);
+            assert_eq!(duration::parse_duration(&text), Ok(duration), "{}", text);
+        }
+        // Nanoseconds below the last digit are dropped, not rounded
+        let text = format_micros(Duration::from_nanos(1_000_999));
+        assert_eq!(text, "00:00:00.001_000");
+        assert_eq!(
+            duration::parse_duration(&text),
+            Ok(Duration::from_micros(1_000))
+        );
+    }
+
+    #[test]
+    fn micro_precision_shows_the_clock_to_the_microsecond() {
+        let mut chronometer = Chronometer::new();
+        chronometer.precision = Precision::Micro;
+        chronometer.start().unwrap();
+        clock::advance(Duration::from_nanos(3_723_000_042_500));
+        assert_eq!(chronometer.display(), "01:02:03.000_042");
+
+        // Far beyond 99 hours the hours just keep growing
+        let huge = Duration::from_secs(u64::MAX / 1_000);
+        assert!(format_micros(huge).starts_with(&format!("{}:", huge.as_secs() / 3_600)));
+    }
+
+    #[test]
+    fn pause_cycles_add_up_exactly() {
+        let mut chronometer = Chronometer::new();
+        let started = clock::system_now();
+        chronometer.start().unwrap();
+        let mut active = Duration::ZERO;
+        for (run, pause) in [(1_000, 300), (2_500, 1_700), (10, 0), (333, 4_000)] {
+            clock::advance(ms(run));
+            active += ms(run);
+            chronometer.pause().unwrap();
+            clock::advance(ms(pause));
+            chronometer.resume().unwrap();
+        }
+        clock::advance(ms(7));
+        assert_eq!(chronometer.elapsed(), active + ms(7));
+        assert_eq!(chronometer.paused_total(), ms(6_000));
+
+        let report = chronometer.report();
+        assert_eq!(report.paused, ms(6_000));
+        assert_eq!(report.pauses.len(), 4);
+        assert_eq!(report.pauses[0], (started + ms(1_000), started + ms(1_300)));
+        assert_eq!(report.ended, started + active + ms(6_007));
+    }
+
+    #[test]
+    fn pause_in_progress_counts_until_now() {
+        let mut chronometer = Chronometer::new();
+        chronometer.start().unwrap();
+        clock::advance(ms(1_000));
+        chronometer.pause().unwrap();
+        clock::advance(ms(2_000));
+        assert_eq!(chronometer.paused_total(), ms(2_000));
+        assert_eq!(chronometer.report().paused, ms(2_000));
+        chronometer.lap();
+        assert_eq!(chronometer.laps(), [ms(1_000)]);
+    }
+
+    fn formatted(precision: Precision, rounding: RoundingMode, duration: Duration) -> String {
+        let mut chronometer = Chronometer::new();
+        chronometer.precision = precision;
+        chronometer.rounding = rounding;
+        chronometer.format_duration(duration)
+    }
+
+    #[test]
+    fn rounding_at_the_last_digit() {
+        use RoundingMode::{Nearest, Truncate};
+        let micros = Duration::from_micros;
+        let cases = [
+            (
+                Precision::Millis,
+                micros(1_499_499),
+                "00:00:01.499",
+                "00:00:01.499",
+            ),
+            (
+                Precision::Millis,
+                micros(1_499_500),
+                "00:00:01.499",
+                "00:00:01.500",
+            ),
+            (
+                Precision::Millis,
+                micros(1_999_900),
+                "00:00:01.999",
+                "00:00:02.000",
+            ),
+            (Precision::Seconds, ms(1_499), "00:00:01", "00:00:01"),
+            (Precision::Seconds, ms(1_500), "00:00:01", "00:00:02"),
+            (Precision::Seconds, ms(3_599_500), "00:59:59", "01:00:00"),
+            (
+                Precision::Micro,
+                Duration::from_nanos(1_499),
+                "00:00:00.000_001",
+                "00:00:00.000_001",
+            ),
+            (
+                Precision::Micro,
+                Duration::from_nanos(1_500),
+                "00:00:00.000_001",
+                "00:00:00.000_002",
+            ),
+        ];
+        for (precision, duration, truncated, nearest) in cases {
+            assert_eq!(formatted(precision, Truncate, duration), truncated);
+            assert_eq!(formatted(precision, Nearest, duration), nearest);
+        }
+    }
+
+    #[test]
+    fn nearest_rounding_does_not_overflow() {
+        let text = formatted(Precision::Seconds, RoundingMode::Nearest, Duration::MAX);
+        assert_eq!(text, format_seconds(Duration::MAX));
+    }
+
+    #[test]
+    fn pause_budget_runs_out() {
+        let mut chronometer = Chronometer::new();
+        chronometer.pause_budget = Some(ms(5_000));
+        chronometer.start().unwrap();
+        assert_eq!(chronometer.pause_remaining(), Some(ms(5_000)));
+
+        // Spread over two pauses
+        chronometer.pause().unwrap();
+        clock::advance(ms(3_000));
+        chronometer.resume().unwrap();
+        clock::advance(ms(1_000));
+        chronometer.pause().unwrap();
+        clock::advance(ms(1_600));
+        chronometer.tick();
+        assert!(chronometer.is_paused());
+        assert_eq!(chronometer.pause_remaining(), Some(ms(400)));
+        assert!(chronometer.pause_budget_low());
+
+        // The tick that finds it used up resumes, however late
+        clock::advance(ms(900));
+        assert_eq!(chronometer.pause_remaining(), Some(Duration::ZERO));
+        chronometer.tick();
+        assert!(chronometer.is_counting());
+        assert_eq!(chronometer.elapsed(), ms(1_000));
+        assert_eq!(chronometer.pause(), Err(TransitionError::PauseBudgetUsed));
+    }
+
+    #[test]
+    fn no_budget_never_warns() {
+        let mut chronometer = Chronometer::new();
+        chronometer.start().unwrap();
+        chronometer.pause().unwrap();
+        clock::advance(ms(3_600_000));
+        chronometer.tick();
+        assert!(chronometer.is_paused());
+        assert_eq!(chronometer.pause_remaining(), None);
+        assert!(!chronometer.pause_budget_low());
+    }
+
+    #[test]
+    fn restart_from_lap_drops_the_split_in_progress() {
+        let mut chronometer = with_laps(&[1_000, 1_500]);
+        clock::advance(ms(800));
+        assert!(chronometer.restart_from_lap());
+        assert_eq!(chronometer.elapsed(), ms(2_500));
+        assert_eq!(chronometer.laps(), [ms(1_000), ms(2_500)]);
+
+        clock::advance(ms(600));
+        assert_eq!(chronometer.elapsed(), ms(3_100));
+        chronometer.lap();
+        assert_eq!(chronometer.lap_splits(), [ms(1_000), ms(1_500), ms(600)]);
+    }
+
+    #[test]
+    fn restart_from_lap_before_any_lap_goes_to_zero() {
+        let mut chronometer = Chronometer::new();
+        assert!(!chronometer.restart_from_lap());
+        chronometer.start().unwrap();
+        clock::advance(ms(4_000));
+        chronometer.pause().unwrap();
+        assert!(chronometer.restart_from_lap());
+        assert!(chronometer.is_paused());
+        assert_eq!(chronometer.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    fn laps_leave_out_paused_time() {
+        let mut chronometer = Chronometer::new();
+        chronometer.start().unwrap();
+        clock::advance(ms(2_000));
+        chronometer.lap();
+        chronometer.toggle_pause().unwrap();
+        clock::advance(ms(60_000));
+        chronometer.toggle_pause().unwrap();
+        clock::advance(ms(500));
+        chronometer.lap();
+        assert_eq!(chronometer.laps(), [ms(2_000), ms(2_500)]);
+        assert_eq!(chronometer.get_lap_differences(), ["00:00:00.500"]);
+        assert_eq!(chronometer.elapsed(), ms(2_500));
+    }
+
+    #[test]
+    fn lap_differences_follow_the_laps_left() {
+        let mut chronometer = with_laps(&[1_000, 250, 4_000]);
+        chronometer.precision = Precision::Seconds;
+        assert_eq!(chronometer.get_lap_differences(), ["00:00:00", "00:00:04"]);
+
+        chronometer.precision = Precision::Millis;
+        chronometer.delete_lap(2);
+        assert_eq!(chronometer.get_lap_differences(), ["00:00:04.250"]);
+        chronometer.delete_lap(1);
+        assert!(chronometer.get_lap_differences().is_empty());
+        assert_eq!(chronometer.lap_splits(), [ms(5_250)]);
+    }
+
+    #[test]
+    fn late_ticks_catch_up_on_the_exact_multiples() {
+        let mut chronometer = Chronometer::new();
+        chronometer.auto_lap = Some(ms(1_000));
+        chronometer.start().unwrap();
+        clock::advance(ms(900));
+        chronometer.tick();
+        assert!(chronometer.laps().is_empty());
+        clock::advance(ms(2_350));
+        chronometer.tick();
+        assert_eq!(chronometer.laps(), [ms(1_000), ms(2_000), ms(3_000)]);
+        assert!((0..3).all(|index| chronometer.is_auto_lap(index)));
+
+        chronometer.lap();
+        assert_eq!(chronometer.laps()[3], ms(3_250));
+        assert!(!chronometer.is_auto_lap(3));
+    }
+
+    #[test]
+    fn auto_laps_wait_out_a_pause() {
+        let mut chronometer = Chronometer::new();
+        chronometer.auto_lap = Some(ms(1_000));
+        chronometer.start().unwrap();
+        clock::advance(ms(600));
+        chronometer.pause().unwrap();
+        clock::advance(ms(5_000));
+        chronometer.tick();
+        assert!(chronometer.laps().is_empty());
+        chronometer.resume().unwrap();
+        clock::advance(ms(400));
+        chronometer.tick();
+        assert_eq!(chronometer.laps(), [ms(1_000)]);
+    }
+
+    #[test]
+    fn auto_laps_realign_after_a_jump() {
+        let mut chronometer = Chronometer::new();
+        chronometer.auto_lap = Some(ms(1_000));
+        chronometer.start_since(ms(2_500)).unwrap();
+        clock::advance(ms(600));
+        chronometer.tick();
+        // The multiples the start skipped over aren't filled in
+        assert_eq!(chronometer.laps(), [ms(3_000)]);
+
+        // A running reset counts on from zero
+        chronometer.reset();
+        clock::advance(ms(1_000));
+        chronometer.tick();
+        assert_eq!(chronometer.laps(), [ms(1_000)]);
+    }
+
+    #[test]
+    fn pace_delta_against_the_target() {
+        let mut chronometer = with_laps(&[900, 1_300]);
+        chronometer.target_pace = Some(ms(1_000));
+        assert_eq!(chronometer.pace_delta(0), Some((ms(100), true)));
+        assert_eq!(chronometer.pace_delta(1), Some((ms(200), false)));
+        assert_eq!(chronometer.pace_delta(2), None);
+    }
+
+    #[test]
+    fn huge_pace_has_no_delta_rather_than_a_panic() {
+        let mut chronometer = with_laps(&[1_000, 1_000]);
+        chronometer.target_pace = Some(Duration::from_secs(u64::MAX));
+        assert_eq!(
+            chronometer.pace_delta(0),
+            Some((Duration::from_secs(u64::MAX) - ms(1_000), true))
+        );
+        assert_eq!(chronometer.pace_delta(1), None);
+        assert_eq!(chronometer.running_pace_delta(), None);
+        chronometer.lap();
+        assert_eq!(chronometer.laps().len(), 3);
+    }
+
+    #[test]
+    fn restart_from_lap_fires_the_auto_lap_it_went_back_over() {
+        let mut chronometer = Chronometer::new();
+        chronometer.auto_lap = Some(ms(1_000));
+        chronometer.start().unwrap();
+        clock::advance(ms(600));
+        chronometer.lap();
+        clock::advance(ms(700));
+        chronometer.tick();
+        assert_eq!(chronometer.laps(), [ms(600), ms(1_000)]);
+
+        // With the auto-lap deleted the restart goes back to 0.6s, before
+        // the 1s mark, which then comes round again
+        assert!(chronometer.delete_lap(2));
+        assert!(chronometer.restart_from_lap());
+        assert_eq!(chronometer.elapsed(), ms(600));
+        clock::advance(ms(500));
+        chronometer.tick();
+        assert_eq!(chronometer.laps(), [ms(600), ms(1_000)]);
+        assert!(chronometer.is_auto_lap(1));
+    }
+}
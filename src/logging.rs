@@ -0,0 +1,381 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::duration::parse_duration;
+use crate::export::rfc3339;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    // One readable line per event, as -C has always written
+    Text,
+    // One JSON object per line, for tools that read the log back
+    Jsonl,
+}
+
+impl LogFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "text" => Some(LogFormat::Text),
+            "jsonl" => Some(LogFormat::Jsonl),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            LogFormat::Text => "txt",
+            LogFormat::Jsonl => "jsonl",
+        }
+    }
+}
+
+// What the session log records. Times also come formatted in the display
+// precision, so text lines read like the screen.
+pub enum LogEvent<'a> {
+    SessionStart,
+    Lap {
+        id: usize,
+        time: Duration,
+        formatted: &'a str,
+        pace: Option<&'a str>,
+        // Recorded by --auto-lap rather than a key press
+        auto: bool,
+        // The --tag project in force
+        project: Option<&'a str>,
+        // When the lap was taken, both clocks read together
+        at: (Instant, SystemTime),
+    },
+    // `reason` is set when the clock paused or resumed on its own
+    Pause {
+        reason: Option<&'a str>,
+    },
+    Resume {
+        reason: Option<&'a str>,
+    },
+    // `to` is the offset of a reset that doesn't start from zero
+    Reset {
+        to: Option<&'a str>,
+    },
+    // The exit report; durations come with their formatted text
+    Quit {
+        elapsed: (Duration, &'a str),
+        paused: (Duration, &'a str),
+        pauses: usize,
+        laps: usize,
+        best: Option<&'a str>,
+        worst: Option<&'a str>,
+        // Against --target, e.g. "-00:01:30.000" when ahead
+        target: Option<&'a str>,
+    },
+    // Everything else: timers, phases, notes, exports
+    Message(&'a str),
+}
+
+impl LogEvent<'_> {
+    fn name(&self) -> &'static str {
+        match self {
+            LogEvent::SessionStart => "session_start",
+            LogEvent::Lap { .. } => "lap",
+            LogEvent::Pause { .. } => "pause",
+            LogEvent::Resume { .. } => "resume",
+            LogEvent::Reset { .. } => "reset",
+            LogEvent::Quit { .. } => "quit",
+            LogEvent::Message(_) => "message",
+        }
+    }
+
+    // The moment an event took place, when that isn't when it's written
+    pub fn moment(&self) -> Option<(Instant, SystemTime)> {
+        match *self {
+            LogEvent::Lap { at, .. } => Some(at),
+            _ => None,
+        }
+    }
+}
+
+pub struct Logger {
+    file: File,
+    format: LogFormat,
+    // JSON lines carry the time since the log was opened, which unlike the
+    // wall clock never jumps
+    opened: Instant,
+}
+
+impl Logger {
+    // Appends, so a --log-file kept across sessions keeps the earlier ones
+    pub fn create(path: &Path, format: LogFormat) -> io::Result<Self> {
+        Ok(Self {
+            file: OpenOptions::new().create(true).append(true).open(path)?,
+            format,
+            opened: Instant::now(),
+        })
+    }
+
+    // `wall_clock` is the --datetime-format timestamp text lines show
+    pub fn write(&mut self, event: &LogEvent, wall_clock: &str) -> io::Result<()> {
+        let line = match self.format {
+            LogFormat::Text => text_line(event, wall_clock, self.monotonic(event)),
+            LogFormat::Jsonl => self.json_line(event),
+        };
+        writeln!(self.file, "{}", line)
+    }
+
+    // Since the log was opened, up to the moment of the event
+    fn monotonic(&self, event: &LogEvent) -> Duration {
+        match event.moment() {
+            Some((instant, _)) => instant.saturating_duration_since(self.opened),
+            None => self.opened.elapsed(),
+        }
+    }
+
+    fn json_line(&self, event: &LogEvent) -> String {
+        format!(
+            "{{\"ts\":\"{}\",\"monotonic_ms\":{},{}}}",
+            rfc3339(timestamp(event)),
+            self.monotonic(event).as_millis(),
+            json_fields(event)
+        )
+    }
+}
+
+// An event as a JSON object of its own, the way the log writes it but
+// without the time since the log was opened
+pub fn event_json(event: &LogEvent) -> String {
+    format!(
+        "{{\"ts\":\"{}\",{}}}",
+        rfc3339(timestamp(event)),
+        json_fields(event)
+    )
+}
+
+fn timestamp(event: &LogEvent) -> SystemTime {
+    event
+        .moment()
+        .map_or_else(SystemTime::now, |(_, time)| time)
+}
+
+// "event" and the event's own fields, for inside a JSON object
+fn json_fields(event: &LogEvent) -> String {
+    let mut line = format!("\"event\":\"{}\"", event.name());
+    let mut field = |key: &str, value: String| line.push_str(&format!(",\"{}\":{}", key, value));
+    match *event {
+        LogEvent::SessionStart => {}
+        LogEvent::Lap {
+            id,
+            time,
+            formatted,
+            pace,
+            auto,
+            project,
+            ..
+        } => {
+            field("lap", id.to_string());
+            field("time", json_string(formatted));
+            field("time_ms", time.as_millis().to_string());
+            if let Some(pace) = pace {
+                field("pace", json_string(pace));
+            }
+            if auto {
+                field("auto", "true".to_string());
+            }
+            if let Some(project) = project {
+                field("project", json_string(project));
+            }
+        }
+        LogEvent::Pause { reason } | LogEvent::Resume { reason } => {
+            if let Some(reason) = reason {
+                field("reason", json_string(reason));
+            }
+        }
+        LogEvent::Reset { to } => {
+            if let Some(to) = to {
+                field("to", json_string(to));
+            }
+        }
+        LogEvent::Quit {
+            elapsed,
+            paused,
+            pauses,
+            laps,
+            best,
+            worst,
+            target,
+        } => {
+            field("elapsed", json_string(elapsed.1));
+            field("elapsed_ms", elapsed.0.as_millis().to_string());
+            field("paused", json_string(paused.1));
+            field("paused_ms", paused.0.as_millis().to_string());
+            field("pauses", pauses.to_string());
+            field("laps", laps.to_string());
+            let optional = |value: Option<&str>| value.map_or("null".to_string(), json_string);
+            field("best", optional(best));
+            field("worst", optional(worst));
+            if let Some(target) = target {
+                field("target_delta", json_string(target));
+            }
+        }
+        LogEvent::Message(message) => field("message", json_string(message)),
+    }
+    line
+}
+
+// The laps of a session log in either format, for looking back at them:
+// those recorded since the last reset, less the ones deleted. Undos aren't
+// in the log as such, so they aren't replayed.
+pub fn parse_log_laps(text: &str) -> Result<Vec<Duration>, String> {
+    let mut laps: Vec<(usize, Duration)> = Vec::new();
+    for (index, row) in text.lines().enumerate() {
+        let line = index + 1;
+        let row = row.trim();
+        let number = |text: &str, key: &str| -> Option<u64> {
+            let (_, rest) = text.split_once(key)?;
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            rest[..digits].parse().ok()
+        };
+
+        // JSON lines carry deletions in their message
+        let message = if row.starts_with('{') {
+            if row.contains("\"event\":\"reset\"") {
+                laps.clear();
+            } else if row.contains("\"event\":\"lap\"") {
+                let (Some(id), Some(millis)) =
+                    (number(row, "\"lap\":"), number(row, "\"time_ms\":"))
+                else {
+                    return Err(format!("line {}: lap without a number or time_ms", line));
+                };
+                laps.push((id as usize, Duration::from_millis(millis)));
+            }
+            match row.split_once("\"message\":\"") {
+                Some((_, rest)) => rest,
+                None => continue,
+            }
+        } else if row.starts_with("Reset ") && !row.starts_with("Reset undone") {
+            laps.clear();
+            continue;
+        } else if let Some((head, rest)) = row.split_once(" - Time: ") {
+            let Some(id) = head.strip_prefix("Lap ").and_then(|head| number(head, "")) else {
+                continue;
+            };
+            let time = rest.split(" - ").next().unwrap_or(rest);
+            let time = parse_duration(time).map_err(|err| format!("line {}: {}", line, err))?;
+            laps.push((id as usize, time));
+            continue;
+        } else {
+            row
+        };
+
+        if let Some(id) = message
+            .strip_prefix("Lap ")
+            .filter(|rest| rest.contains(" deleted at: "))
+            .and_then(|rest| number(rest, ""))
+        {
+            laps.retain(|&(lap, _)| lap as u64 != id);
+        }
+    }
+    Ok(laps.into_iter().map(|(_, time)| time).collect())
+}
+
+fn text_line(event: &LogEvent, wall_clock: &str, monotonic: Duration) -> String {
+    match *event {
+        LogEvent::SessionStart => format!(
+            "ChronoRust Session Started: {}\n================================================",
+            wall_clock
+        ),
+        LogEvent::Lap {
+            id,
+            formatted,
+            pace,
+            auto,
+            project,
+            ..
+        } => {
+            let kind = if auto { " (auto)" } else { "" };
+            let mut line = format!(
+                "Lap {}{} at: {} - Time: {}",
+                id, kind, wall_clock, formatted
+            );
+            if let Some(pace) = pace {
+                line.push_str(&format!(" - Pace: {}", pace));
+            }
+            if let Some(project) = project {
+                line.push_str(&format!(" - Project: {}", project));
+            }
+            line.push_str(&format!(" - Monotonic: {} ms", monotonic.as_millis()));
+            line
+        }
+        LogEvent::Pause { reason: None } => format!("Paused at: {}", wall_clock),
+        LogEvent::Pause {
+            reason: Some(reason),
+        } => format!("Paused ({}) at: {}", reason, wall_clock),
+        LogEvent::Resume { reason: None } => format!("Resumed at: {}", wall_clock),
+        LogEvent::Resume {
+            reason: Some(reason),
+        } => format!("Resumed ({}) at: {}", reason, wall_clock),
+        LogEvent::Reset { to: None } => format!("Reset at: {}", wall_clock),
+        LogEvent::Reset { to: Some(to) } => format!("Reset to {} at: {}", to, wall_clock),
+        LogEvent::Quit {
+            elapsed,
+            paused,
+            pauses,
+            laps,
+            best,
+            worst,
+            target,
+        } => {
+            let mut line = format!(
+                "Session ended at: {} - Total: {} - Paused: {} ({} pauses) - Laps: {}",
+                wall_clock, elapsed.1, paused.1, pauses, laps
+            );
+            if let (Some(best), Some(worst)) = (best, worst) {
+                line.push_str(&format!(" - Best: {} - Worst: {}", best, worst));
+            }
+            if let Some(target) = target {
+                line.push_str(&format!(" - Target: {}", target));
+            }
+            line
+        }
+        LogEvent::Message(message) => message.to_string(),
+    }
+}
+
+pub(crate) fn json_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::temp_file;
+
+    #[test]
+    fn a_log_file_is_appended_to() {
+        let path = temp_file("append.jsonl");
+        for _ in 0..2 {
+            let mut logger = Logger::create(&path, LogFormat::Jsonl).unwrap();
+            logger.write(&LogEvent::SessionStart, "").unwrap();
+        }
+        let log = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(log.lines().count(), 2);
+        assert!(log.lines().all(|line| line.starts_with("{\"ts\":")));
+    }
+}
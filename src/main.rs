@@ -1,364 +1,5278 @@
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent},
+    cursor,
+    event::{
+        self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
     execute,
-    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
-    backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
-    Frame, Terminal,
+    symbols::{self, bar, border},
+    text::{Line, Span},
+    widgets::{
+        block::{Position, Title},
+        BarChart, Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Tabs,
+    },
+    Frame,
 };
+mod bench;
+mod big_digits_ascii;
+mod big_digits_unicode;
+mod cli;
+mod clipboard;
+mod config;
+mod current_file;
+#[cfg(unix)]
+mod daemon;
+#[cfg(feature = "http")]
+mod http;
+mod i18n;
+mod keymap;
+mod notifications;
+mod process;
+mod sound;
+mod terminal;
+mod theme;
+mod ws;
+#[cfg(feature = "zones")]
+mod zones;
+
+use big_digits_ascii::format_big_time_ascii;
+use big_digits_unicode::{format_big_time_unicode, BIG_HEIGHT};
+use chronorust::countdown::{parse_presets, Countdown};
+use chronorust::daily;
+use chronorust::distance::format_distance;
+use chronorust::duration::{elapsed_since, future_datetime, next_time_of_day, parse_duration};
+use chronorust::export::{self, ExportFormat};
+use chronorust::history::{self, HistoryEntry};
+use chronorust::hooks::Hooks;
+use chronorust::interval::{Interval, IntervalPhase};
+use chronorust::plan::Plan;
+use chronorust::pomodoro::{Phase, Pomodoro};
+use chronorust::project::{self, parse_project};
+use chronorust::sequence::Sequence;
+use chronorust::session::{
+    load_state_binary, load_state_json, save_state_binary, save_state_json, SessionState,
+};
+use chronorust::stats::{classify_pace, LapPace};
+use chronorust::target::Target;
+use chronorust::{format_seconds, Chronometer, LapStats, Precision, RateUnit, Undone};
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use cli::{Cli, Mode};
+use clipboard::Copied;
+use config::Config;
+use i18n::{Lang, Msg};
+use keymap::{Action, KeyMap};
+use process::RunningCommand;
+use sound::{ring_bell, Sound};
 use std::{
+    collections::VecDeque,
     env,
     fs::File,
-    io::{self, stdout, Write},
-    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+    io::{self, stdout, BufRead, IsTerminal, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime},
+};
+use terminal::Tui;
+use theme::Theme;
+
+#[derive(Clone, Copy, PartialEq)]
+enum InputPurpose {
+    ResetOffset,
+    AddCountdown,
+    LapNote(usize),
+    LapFilter,
+    Project,
+}
+
+impl InputPurpose {
+    fn prompt(self, lang: Lang) -> String {
+        let msg = match self {
+            InputPurpose::ResetOffset => Msg::PromptResetOffset,
+            InputPurpose::AddCountdown => Msg::PromptAddCountdown,
+            InputPurpose::LapNote(lap_id) => {
+                return lang.fill(Msg::PromptLapNote, &[("id", &lap_id)]);
+            }
+            InputPurpose::LapFilter => Msg::PromptLapFilter,
+            InputPurpose::Project => Msg::PromptProject,
+        };
+        lang.text(msg).to_string()
+    }
+}
+
+struct TextInput {
+    purpose: InputPurpose,
+    buffer: String,
+    error: Option<String>,
+}
+
+impl TextInput {
+    fn new(purpose: InputPurpose) -> Self {
+        Self {
+            purpose,
+            buffer: String::new(),
+            error: None,
+        }
+    }
+
+    // Starts from existing text, e.g. when editing a note
+    fn prefilled(purpose: InputPurpose, buffer: String) -> Self {
+        Self {
+            buffer,
+            ..Self::new(purpose)
+        }
+    }
+}
+
+struct Glyphs {
+    prestart: &'static str,
+    paused: &'static str,
+    running: &'static str,
+    delta: &'static str,
+    total: &'static str,
+    // In the title bar while the controls are locked
+    locked: &'static str,
+    // Marks a lap that beat the reference session's
+    gold: &'static str,
+    borders: border::Set,
+}
+
+const UNICODE_GLYPHS: Glyphs = Glyphs {
+    prestart: "🚦 ",
+    paused: "⏸️  ",
+    running: "⏱️  ",
+    delta: "Δ",
+    total: "Σ",
+    locked: "🔒 LOCKED",
+    gold: " ★",
+    borders: border::PLAIN,
+};
+
+// For terminals without UTF-8 (e.g. the basic Linux console)
+const ASCII_GLYPHS: Glyphs = Glyphs {
+    prestart: "[..] ",
+    paused: "[||] ",
+    running: "[>] ",
+    delta: "d",
+    total: "sum",
+    locked: "[LOCKED]",
+    gold: " *",
+    borders: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
+    },
 };
 
-struct Chronometer {
-    start_time: Option<Instant>,
-    lap_times: Vec<String>,
-    lap_durations: Vec<Duration>,
-    is_running: bool,
-    is_paused: bool,
-    paused_duration: Duration,
-    log_file: Option<File>,
-    start_timestamp: SystemTime,
+fn glyphs(ascii: bool) -> &'static Glyphs {
+    if ascii {
+        &ASCII_GLYPHS
+    } else {
+        &UNICODE_GLYPHS
+    }
+}
+
+fn beat_index(elapsed: Duration, bpm: u32) -> u128 {
+    elapsed.as_millis() * u128::from(bpm) / 60_000
+}
+
+fn locale_supports_utf8() -> bool {
+    // Windows consoles don't advertise the encoding through the locale
+    if cfg!(windows) {
+        return true;
+    }
+    // Same precedence as setlocale(): the first non-empty variable wins
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .map(|value| {
+            let value = value.to_lowercase();
+            value.contains("utf-8") || value.contains("utf8")
+        })
+        .unwrap_or(false)
+}
+
+#[derive(PartialEq)]
+enum LoopControl {
+    Continue,
+    Quit,
+}
+
+// Transient feedback for the status bar
+struct StatusMessage {
+    text: String,
+    // Failures stand out in the alert color
+    error: bool,
+    set_at: Instant,
+}
+
+const STATUS_TIMEOUT: Duration = Duration::from_secs(3);
+// Dimmed for the last stretch before it goes
+const STATUS_FADE: Duration = Duration::from_secs(1);
+// Older messages are dropped past this many
+const STATUS_QUEUE: usize = 5;
+
+impl StatusMessage {
+    fn is_live(&self) -> bool {
+        self.set_at.elapsed() < STATUS_TIMEOUT
+    }
+
+    fn is_fading(&self) -> bool {
+        self.set_at.elapsed() >= STATUS_TIMEOUT - STATUS_FADE
+    }
+}
+
+// A ringing alarm sounds this often until a key stops it, for at most
+// ALARM_RING_FOR
+const ALARM_REPEAT_SECS: u64 = 5;
+const ALARM_RING_FOR: Duration = Duration::from_secs(60);
+
+// The daemon `chronorust attach` follows, and when its session was last
+// fetched
+struct Attached {
+    socket: PathBuf,
+    synced: Instant,
+    // The daemon's answer to `quit`, once it has been told to stop
+    ended: Option<String>,
+}
+
+// How often an attached TUI asks the daemon for its session
+const ATTACH_SYNC: Duration = Duration::from_millis(250);
+
+struct AppState {
+    rate_unit: RateUnit,
+    // What the screen and the exit report are written in
+    lang: Lang,
+    ascii: bool,
+    use_color: bool,
+    theme: Theme,
+    project_target: Option<Duration>,
+    bpm: Option<u32>,
+    beep: bool,
+    notify: bool,
+    sound: Sound,
+    last_beat: Option<u128>,
+    last_prestart_second: Option<u128>,
+    input: Option<TextInput>,
+    // Named timer durations, and the Shift+P menu's selection while it is open
+    presets: Vec<(String, Duration)>,
+    preset_menu: Option<usize>,
+    // The key help popup's scroll offset while it is open
+    help: Option<u16>,
+    countdowns: Vec<Countdown>,
+    selected_countdown: usize,
+    selected_lap: Option<usize>,
+    lap_filter: Option<LapFilter>,
+    heatmap: bool,
+    // Every key but the unlock sequence is ignored; `unlock_armed` is when
+    // its first key came
+    locked: bool,
+    unlock_armed: Option<Instant>,
+    // Ask before a reset or quit would throw laps away; `confirming` is the
+    // action waiting for an answer
+    confirm: bool,
+    confirming: Option<Action>,
+    lap_colors: LapColors,
+    // A fraction of the average split, for LapColors::Pace
+    lap_tolerance: f64,
+    // Clickable buttons and wheel scrolling; off leaves the mouse to the
+    // terminal for selecting text
+    mouse: bool,
+    // Splits first in the lap list, cumulative times in parentheses
+    show_splits: bool,
+    // Newest last; each fades out STATUS_TIMEOUT after it was set
+    messages: VecDeque<StatusMessage>,
+    countdown_alerted: bool,
+    // Target of alarm mode, as shown beside the time
+    alarm: Option<String>,
+    // When the alarm went off and how often it has rung since
+    alarm_ringing: Option<(Instant, u64)>,
+    histogram_bucket: Duration,
+    show_histogram: bool,
+    show_lap_stats: bool,
+    big_digits: bool,
+    export_format: ExportFormat,
+    pomodoro: Option<Pomodoro>,
+    interval: Option<Interval>,
+    // Seconds left when the last pre-transition cue rang
+    interval_cue: Option<u128>,
+    plan: Option<Plan>,
+    sequence: Option<Sequence>,
+    command: Option<RunningCommand>,
+    history_dir: Option<PathBuf>,
+    history: Option<HistoryView>,
+    // Time tracked today by earlier runs, from the daily totals file, and
+    // with this run's session time added on
+    today_before: Option<Duration>,
+    today: Option<Duration>,
+    // Set while `chronorust attach` shows the daemon's session
+    attached: Option<Attached>,
+    keymap: KeyMap,
+    #[cfg(feature = "zones")]
+    zones: Vec<chrono_tz::Tz>,
+    // The world clock panel, toggled with C
+    #[cfg(feature = "zones")]
+    show_clock: bool,
 }
 
-impl Chronometer {
+impl AppState {
+    // Defaults match running without any flags, so ui() can be driven from
+    // any backend without going through main()
     fn new() -> Self {
         Self {
-            start_time: None,
-            lap_times: Vec::new(),
-            lap_durations: Vec::new(),
-            is_running: false,
-            is_paused: false,
-            paused_duration: Duration::new(0, 0),
-            log_file: None,
-            start_timestamp: SystemTime::now(),
-        }
-    }
-
-    fn start(&mut self) {
-        self.start_time = Some(Instant::now());
-        self.is_running = true;
-        self.is_paused = false;
-        self.paused_duration = Duration::new(0, 0);
-        self.start_timestamp = SystemTime::now();
-    }
-
-    fn enable_logging(&mut self) -> io::Result<()> {
-        let now = SystemTime::now();
-        let duration = now.duration_since(UNIX_EPOCH).unwrap();
-        let timestamp = duration.as_secs();
-
-        let datetime = chrono::DateTime::from_timestamp(timestamp as i64, 0).unwrap();
-        let filename = format!(
-            "ChronoRust-{}-log.txt",
-            datetime.format("%d-%m-%y-%H-%M-%S")
+            rate_unit: RateUnit::PerMinute,
+            lang: Lang::En,
+            ascii: false,
+            use_color: true,
+            theme: Theme::default(),
+            project_target: None,
+            bpm: None,
+            beep: false,
+            notify: false,
+            sound: Sound::default(),
+            last_beat: None,
+            last_prestart_second: None,
+            input: None,
+            presets: Vec::new(),
+            preset_menu: None,
+            help: None,
+            countdowns: Vec::new(),
+            selected_countdown: 0,
+            selected_lap: None,
+            lap_filter: None,
+            heatmap: true,
+            locked: false,
+            unlock_armed: None,
+            confirm: true,
+            confirming: None,
+            lap_colors: LapColors::Heat,
+            lap_tolerance: 0.05,
+            mouse: true,
+            show_splits: false,
+            messages: VecDeque::new(),
+            countdown_alerted: false,
+            alarm: None,
+            alarm_ringing: None,
+            histogram_bucket: Duration::from_secs(60),
+            show_histogram: false,
+            show_lap_stats: false,
+            big_digits: false,
+            export_format: ExportFormat::Csv,
+            pomodoro: None,
+            interval: None,
+            interval_cue: None,
+            plan: None,
+            sequence: None,
+            command: None,
+            history_dir: None,
+            history: None,
+            today_before: None,
+            today: None,
+            attached: None,
+            keymap: KeyMap::default(),
+            #[cfg(feature = "zones")]
+            zones: Vec::new(),
+            #[cfg(feature = "zones")]
+            show_clock: false,
+        }
+    }
+
+    fn tick(&mut self, chronometer: &mut Chronometer) {
+        let lang = self.lang;
+        // Egg timers run regardless of the stopwatch state
+        for countdown in &mut self.countdowns {
+            if countdown.tick() {
+                chronometer.log_line(&format!(
+                    "Timer '{}' finished at: {}",
+                    countdown.name,
+                    chronometer.wall_clock()
+                ));
+                self.sound.alarm();
+                if self.notify {
+                    let time = chronometer.format_duration(countdown.duration);
+                    notifications::notify(
+                        &lang.fill(Msg::TimerFinished, &[("name", &countdown.name)]),
+                        &lang.fill(Msg::TimeIsUp, &[("time", &time)]),
+                    );
+                }
+            }
+        }
+
+        // Countdown mode alerts once on reaching zero, re-armed by a reset
+        if let Some(remaining) = chronometer.countdown_remaining() {
+            if !remaining.is_zero() {
+                self.countdown_alerted = false;
+            } else if chronometer.is_started() && !self.countdown_alerted {
+                self.countdown_alerted = true;
+                if let Some(ref alarm) = self.alarm {
+                    let line = format!("Alarm went off at: {}", chronometer.wall_clock());
+                    chronometer.log_line(&line);
+                    self.notify(
+                        lang.text(Msg::AlarmTitle),
+                        &lang.fill(Msg::ItsTime, &[("time", alarm)]),
+                    );
+                    self.alarm_ringing = Some((Instant::now(), 0));
+                } else {
+                    let line = format!("Countdown finished at: {}", chronometer.wall_clock());
+                    chronometer.log_line(&line);
+                    self.sound.alarm();
+                    if let Some(from) = chronometer.countdown_from {
+                        let time = chronometer.format_duration(from);
+                        self.notify(
+                            lang.text(Msg::CountdownFinished),
+                            &lang.fill(Msg::TimeIsUp, &[("time", &time)]),
+                        );
+                    }
+                }
+                chronometer.run_hook("on_countdown_finished");
+            }
+        }
+
+        // A ringing alarm repeats until a key stops it or it gives up
+        if let Some((since, rung)) = self.alarm_ringing {
+            let elapsed = since.elapsed();
+            let due = elapsed.as_secs() / ALARM_REPEAT_SECS + 1;
+            if elapsed >= ALARM_RING_FOR {
+                self.alarm_ringing = None;
+            } else if due > rung {
+                self.alarm_ringing = Some((since, due));
+                self.sound.alarm();
+            }
+        }
+
+        // Prestart beeps once per second like a starting gun countdown
+        if let Some(remaining) = chronometer.prestart_remaining() {
+            let second = remaining.as_millis().div_ceil(1_000);
+            if self.last_prestart_second != Some(second) {
+                self.last_prestart_second = Some(second);
+                self.bell();
+            }
+            return;
+        }
+
+        // Beats follow active elapsed time, so they stop while paused
+        if let Some(bpm) = self.bpm {
+            if chronometer.is_counting() {
+                let beat = beat_index(chronometer.elapsed(), bpm);
+                if self.last_beat != Some(beat) {
+                    self.last_beat = Some(beat);
+                    self.bell();
+                }
+            }
+        }
+    }
+
+    // Half a second on, half off while the alarm rings
+    fn alarm_flash(&self) -> bool {
+        self.alarm_ringing
+            .is_some_and(|(since, _)| (since.elapsed().as_millis() / 500).is_multiple_of(2))
+    }
+
+    // The time flashes like a ringing alarm, with the alarm's rhythm or
+    // else half a second on, half off through a countdown's overtime
+    fn time_flash(&self, chronometer: &Chronometer) -> bool {
+        self.alarm_flash()
+            || chronometer
+                .countdown_overtime()
+                .is_some_and(|over| (over.as_millis() / 500).is_multiple_of(2))
+    }
+
+    fn beat_flash(&self, chronometer: &Chronometer) -> bool {
+        match self.bpm {
+            Some(bpm) if chronometer.is_counting() => {
+                let beat_ms = 60_000 / u128::from(bpm);
+                let phase = chronometer.elapsed().as_millis() % beat_ms.max(1);
+                // Flash for the first quarter of the beat, capped for slow tempos
+                phase < (beat_ms / 4).min(150)
+            }
+            _ => false,
+        }
+    }
+
+    fn set_status(&mut self, message: String) {
+        self.push_message(message, false);
+    }
+
+    fn set_error(&mut self, message: String) {
+        self.push_message(message, true);
+    }
+
+    // A repeat of the newest message just starts its time over
+    fn push_message(&mut self, text: String, error: bool) {
+        self.messages.retain(|message| message.is_live());
+        if self.messages.back().is_some_and(|last| last.text == text) {
+            self.messages.pop_back();
+        }
+        if self.messages.len() == STATUS_QUEUE {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(StatusMessage {
+            text,
+            error,
+            set_at: Instant::now(),
+        });
+    }
+
+    // What changes on screen by itself between inputs: the times, the
+    // timers' seconds, flashes and the status messages. The draw loop skips
+    // frames where this stays the same.
+    fn frame_key(&self, timers: &App) -> String {
+        let chronometer = timers.active();
+        let mut key = format!(
+            "{}|{}",
+            self.time_flash(chronometer),
+            self.beat_flash(chronometer)
+        );
+        for timer in &timers.timers {
+            key.push_str(&format!("|{}", timer.display()));
+        }
+        let seconds = self
+            .countdowns
+            .iter()
+            .map(Countdown::remaining)
+            .chain(self.pomodoro.as_ref().map(Pomodoro::remaining))
+            .chain(self.interval.as_ref().map(Interval::remaining))
+            .chain(self.plan.as_ref().map(Plan::remaining))
+            .chain(self.plan.as_ref().map(Plan::overtime))
+            .chain(self.sequence.as_ref().map(Sequence::remaining))
+            .chain(self.today);
+        for remaining in seconds {
+            key.push_str(&format!("|{}", remaining.as_secs()));
+        }
+        key.push('|');
+        key.extend(
+            self.live_messages()
+                .map(|message| if message.is_fading() { 'f' } else { 'm' }),
         );
+        key
+    }
+
+    // Messages still showing, newest first
+    fn live_messages(&self) -> impl Iterator<Item = &StatusMessage> {
+        self.messages
+            .iter()
+            .rev()
+            .filter(|message| message.is_live())
+    }
 
-        let file = File::create(&filename)?;
-        self.log_file = Some(file);
+    fn current_status(&self) -> Option<&str> {
+        self.live_messages()
+            .next()
+            .map(|message| message.text.as_str())
+    }
 
-        // Write initial log entry
-        if let Some(ref mut file) = self.log_file {
-            writeln!(
-                file,
-                "ChronoRust Session Started: {}",
-                datetime.format("%Y-%m-%d %H:%M:%S")
-            )?;
-            writeln!(file, "================================================")?;
+    fn toggle_clock(&mut self) {
+        #[cfg(feature = "zones")]
+        if self.zones.is_empty() {
+            self.set_status(self.lang.text(Msg::NoClockZones).to_string());
+        } else {
+            self.show_clock = !self.show_clock;
         }
+        #[cfg(not(feature = "zones"))]
+        self.set_error(self.lang.text(Msg::NoZonesFeature).to_string());
+    }
 
-        Ok(())
+    // The command's exit is the final lap; the clock stops there
+    fn tick_command(&mut self, chronometer: &mut Chronometer) {
+        let Some(status) = self.command.as_mut().and_then(RunningCommand::poll) else {
+            return;
+        };
+        chronometer.lap();
+        // Already paused by hand is as good
+        let _ = chronometer.pause();
+        let line = format!(
+            "Command finished ({}) at: {}",
+            process::exit_label(status),
+            chronometer.wall_clock()
+        );
+        chronometer.log_line(&line);
+        self.sound.alarm();
     }
 
-    fn reset(&mut self) {
-        self.start_time = Some(Instant::now());
-        self.lap_times.clear();
-        self.lap_durations.clear();
-        self.is_running = true;
-        self.is_paused = false;
-        self.paused_duration = Duration::new(0, 0);
-        self.start_timestamp = SystemTime::now();
+    // The session timer is a copy of the daemon's, fetched anew a few times
+    // a second and counting on by itself in between
+    #[cfg(unix)]
+    fn tick_attached(&mut self, chronometer: &mut Chronometer) {
+        let Some(ref mut attached) = self.attached else {
+            return;
+        };
+        if attached.synced.elapsed() < ATTACH_SYNC {
+            return;
+        }
+        attached.synced = Instant::now();
+        match daemon::fetch_state(&attached.socket) {
+            Ok(state) => chronometer.follow(state.as_ref()),
+            Err(err) => self.set_error(self.lang.fill(Msg::LostDaemon, &[("error", &err)])),
+        }
+    }
 
-        // Log reset event
-        if let Some(ref mut file) = self.log_file {
-            let now = SystemTime::now();
-            let datetime = chrono::DateTime::from_timestamp(
-                now.duration_since(UNIX_EPOCH).unwrap().as_secs() as i64,
-                0,
-            )
-            .unwrap();
-            let _ = writeln!(file, "Reset at: {}", datetime.format("%Y-%m-%d %H:%M:%S"));
+    // The day's total counts the session timer, which resets don't clear
+    fn tick_today(&mut self, chronometer: &Chronometer) {
+        self.today = self
+            .today_before
+            .map(|before| before + chronometer.tracked());
+    }
+
+    // Pomodoro phases follow the session timer whichever tab is shown
+    fn tick_pomodoro(&mut self, chronometer: &mut Chronometer) {
+        let Some(ref mut pomodoro) = self.pomodoro else {
+            return;
+        };
+        if pomodoro.tick(chronometer.elapsed()) {
+            let line = format!(
+                "Pomodoro {} {} started at: {}",
+                pomodoro.phase.label().to_lowercase(),
+                pomodoro_cycle(pomodoro),
+                chronometer.wall_clock()
+            );
+            chronometer.log_line(&line);
+            self.sound.alarm();
+            let summary = self.lang.fill(
+                Msg::PhaseStarted,
+                &[
+                    ("phase", &self.lang.text(pomodoro_phase(pomodoro.phase))),
+                    ("cycle", &pomodoro_cycle(pomodoro)),
+                ],
+            );
+            let time = chronometer.format_duration(pomodoro.phase_length());
+            let body = self.lang.fill(Msg::UntilNextPhase, &[("time", &time)]);
+            self.notify(&summary, &body);
         }
     }
 
-    fn pause(&mut self) {
-        if self.is_running && !self.is_paused {
-            self.paused_duration += self.start_time.unwrap().elapsed();
-            self.is_paused = true;
+    // Interval phases follow the session timer like pomodoro ones, with a
+    // click on each of the last three seconds before a change
+    fn tick_interval(&mut self, chronometer: &mut Chronometer) {
+        let Some(ref mut interval) = self.interval else {
+            return;
+        };
+        if interval.tick(chronometer.elapsed()) {
+            self.interval_cue = None;
+            let line = match interval.phase {
+                IntervalPhase::Done => {
+                    let _ = chronometer.pause();
+                    format!("Interval workout finished at: {}", chronometer.wall_clock())
+                }
+                phase => format!(
+                    "Interval {} round {} started at: {}",
+                    phase.label().to_lowercase(),
+                    interval.round,
+                    chronometer.wall_clock()
+                ),
+            };
+            chronometer.log_line(&line);
+            self.sound.alarm();
+            let lang = self.lang;
+            let summary = match interval.phase {
+                IntervalPhase::Done => lang.text(Msg::WorkoutDone).to_string(),
+                phase => lang.fill(
+                    Msg::PhaseRound,
+                    &[
+                        ("phase", &lang.text(interval_phase(phase))),
+                        ("round", &interval.round),
+                        ("rounds", &interval.rounds),
+                    ],
+                ),
+            };
+            let time = chronometer.format_duration(chronometer.elapsed());
+            let body = lang.fill(Msg::TimeInTotal, &[("time", &time)]);
+            self.notify(&summary, &body);
+            return;
+        }
+
+        let seconds_left = interval.remaining().as_millis().div_ceil(1_000);
+        if interval.phase != IntervalPhase::Done
+            && (1..=3).contains(&seconds_left)
+            && self.interval_cue != Some(seconds_left)
+        {
+            self.interval_cue = Some(seconds_left);
+            ring_bell();
         }
     }
 
-    fn resume(&mut self) {
-        if self.is_running && self.is_paused {
-            self.start_time = Some(Instant::now());
-            self.is_paused = false;
+    // A --session plan moves on by itself or waits for the next-segment
+    // key, logging each segment as it starts; the clock stops after the last
+    fn tick_plan(&mut self, chronometer: &mut Chronometer) {
+        let Some(ref mut plan) = self.plan else {
+            return;
+        };
+        if plan.tick(chronometer.elapsed()) {
+            self.plan_changed(chronometer);
         }
     }
 
-    fn add_lap(&mut self) {
-        if self.is_running {
-            let elapsed = self.get_elapsed();
-            let lap_time = self.format_duration(elapsed);
-            let lap_time_clone = lap_time.clone();
-            self.lap_times.push(lap_time);
-            self.lap_durations.push(elapsed);
+    fn advance_plan(&mut self, chronometer: &mut Chronometer) {
+        let Some(ref mut plan) = self.plan else {
+            self.set_error(self.lang.text(Msg::NoPlan).to_string());
+            return;
+        };
+        if !chronometer.is_started() {
+            return;
+        }
+        if plan.advance(chronometer.elapsed()) {
+            self.plan_changed(chronometer);
+        }
+    }
 
-            // Log lap event
-            if let Some(ref mut file) = self.log_file {
-                let now = SystemTime::now();
-                let datetime = chrono::DateTime::from_timestamp(
-                    now.duration_since(UNIX_EPOCH).unwrap().as_secs() as i64,
-                    0,
+    fn plan_changed(&mut self, chronometer: &mut Chronometer) {
+        let Some(ref plan) = self.plan else {
+            return;
+        };
+        let (line, summary, body) = match plan.current() {
+            Some((name, length)) => (
+                format!(
+                    "Segment {} '{}' started at: {}",
+                    plan.index + 1,
+                    name,
+                    chronometer.wall_clock()
+                ),
+                format!("{} ({}/{})", name, plan.index + 1, plan.segments.len()),
+                format!("{} planned", format_seconds(*length)),
+            ),
+            None => {
+                let _ = chronometer.pause();
+                (
+                    format!("Session plan finished at: {}", chronometer.wall_clock()),
+                    "Plan done".to_string(),
+                    format!(
+                        "{} in total",
+                        chronometer.format_duration(chronometer.elapsed())
+                    ),
                 )
-                .unwrap();
-                let _ = writeln!(
-                    file,
-                    "Lap {} at: {} - Time: {}",
-                    self.lap_times.len(),
-                    datetime.format("%Y-%m-%d %H:%M:%S"),
-                    lap_time_clone
-                );
             }
+        };
+        chronometer.log_line(&line);
+        self.sound.alarm();
+        self.notify(&summary, &body);
+    }
+
+    // A seq rings as each segment but the last ends; the end of the last
+    // is the countdown's own
+    fn tick_sequence(&mut self, chronometer: &mut Chronometer) {
+        let Some(ref mut sequence) = self.sequence else {
+            return;
+        };
+        if !sequence.tick(chronometer.elapsed()) {
+            return;
         }
+        // Back at the first segment after a reset, nothing has ended
+        let Some(length) = sequence.current().filter(|_| sequence.index > 0) else {
+            return;
+        };
+        let line = format!(
+            "Sequence segment {} started at: {}",
+            sequence.index + 1,
+            chronometer.wall_clock()
+        );
+        let summary = format!("Segment {}/{}", sequence.index + 1, sequence.segments.len());
+        let body = format!("{} to go", format_seconds(length));
+        chronometer.log_line(&line);
+        self.sound.alarm();
+        self.notify(&summary, &body);
     }
 
-    fn get_elapsed(&self) -> Duration {
-        if self.is_paused {
-            self.paused_duration
-        } else if let Some(start) = self.start_time {
-            self.paused_duration + start.elapsed()
+    // Text in the big font, box-drawing or ASCII as the terminal allows
+    fn big_text(&self, text: &str) -> Option<Vec<String>> {
+        if self.ascii {
+            format_big_time_ascii(text)
         } else {
-            Duration::new(0, 0)
+            format_big_time_unicode(text)
         }
     }
 
-    fn format_duration(&self, duration: Duration) -> String {
-        let total_ms = duration.as_millis();
-        let hours = total_ms / 3_600_000;
-        let minutes = (total_ms % 3_600_000) / 60_000;
-        let seconds = (total_ms % 60_000) / 1_000;
-        let milliseconds = total_ms % 1_000;
-
-        format!(
-            "{:02}:{:02}:{:02}.{:03}",
-            hours, minutes, seconds, milliseconds
-        )
+    fn phase_height(&self) -> u16 {
+        match (&self.pomodoro, &self.interval) {
+            (Some(_), _) => 3,
+            // Big phase name and a gauge line inside the border
+            (None, Some(_)) => BIG_HEIGHT as u16 + 3,
+            // The segment names above the gauge
+            (None, None) if self.plan.is_some() || self.sequence.is_some() => 4,
+            (None, None) => 0,
+        }
     }
 
-    fn display(&self) -> String {
-        if self.is_running {
-            self.format_duration(self.get_elapsed())
+    // The fraction done and the time left, in the modes that end: a
+    // countdown, an alarm (measured from the start of the session) and an
+    // interval workout or a session plan. Pomodoro phases have their own
+    // gauge and no end.
+    fn progress(&self, chronometer: &Chronometer) -> Option<(f64, Duration)> {
+        let (done, total) = if let Some(ref interval) = self.interval {
+            (chronometer.elapsed(), interval.total())
+        } else if let Some(ref plan) = self.plan {
+            (plan.planned_elapsed(), plan.total())
+        } else if let Some(at) = chronometer.alarm_at {
+            let total = at
+                .duration_since(chronometer.start_timestamp)
+                .unwrap_or_default();
+            (
+                total.saturating_sub(chronometer.countdown_remaining()?),
+                total,
+            )
         } else {
-            "00:00:00.000".to_string()
+            (chronometer.elapsed(), chronometer.countdown_from?)
+        };
+        if total.is_zero() {
+            return Some((1.0, Duration::ZERO));
+        }
+        let ratio = (done.as_secs_f64() / total.as_secs_f64()).min(1.0);
+        Some((ratio, total.saturating_sub(done)))
+    }
+
+    fn bell(&self) {
+        if self.beep {
+            ring_bell();
+        }
+    }
+
+    fn notify(&self, summary: &str, body: &str) {
+        if self.notify {
+            notifications::notify(summary, body);
+        }
+    }
+
+    // Read fresh each time, so sessions finished meanwhile show up
+    fn open_history(&mut self) {
+        let Some(ref dir) = self.history_dir else {
+            self.set_error(self.lang.text(Msg::NoHistoryDir).to_string());
+            return;
+        };
+        match history::load(dir) {
+            Ok(entries) => self.history = Some(HistoryView::new(entries)),
+            Err(err) => self.set_error(self.lang.fill(Msg::CannotReadHistory, &[("error", &err)])),
         }
     }
 
-    fn get_lap_differences(&self) -> Vec<String> {
-        let mut differences = Vec::new();
+    // Moves the lap cursor, starting from the newest lap; held as a lap id so
+    // it stays on the same lap while others are added or deleted. Moving
+    // past the newest lap drops the cursor so the list follows new laps.
+    fn move_lap_selection(&mut self, chronometer: &Chronometer, delta: isize) {
+        let ids = self.visible_lap_ids(chronometer);
+        let current = self
+            .selected_lap
+            .and_then(|id| ids.iter().position(|&lap_id| lap_id == id))
+            .unwrap_or(ids.len());
+        let index = current.saturating_add_signed(delta);
+        self.selected_lap = ids.get(index).copied();
+    }
+
+    // Ids of the laps shown in the lap list, in order
+    fn visible_lap_ids(&self, chronometer: &Chronometer) -> Vec<usize> {
+        let splits = chronometer.lap_splits();
+        chronometer
+            .lap_ids
+            .iter()
+            .zip(splits)
+            .filter(|&(_, split)| self.lap_filter.is_none_or(|filter| filter.matches(split)))
+            .map(|(&id, _)| id)
+            .collect()
+    }
+
+    fn start_preset(&mut self, index: usize) {
+        let Some((name, duration)) = self.presets.get(index).cloned() else {
+            return;
+        };
+        self.preset_menu = None;
+        self.countdowns.push(Countdown::new(name.clone(), duration));
+        self.selected_countdown = self.countdowns.len() - 1;
+        self.set_status(self.lang.fill(Msg::TimerStarted, &[("name", &name)]));
+    }
 
-        if self.lap_durations.len() <= 1 {
-            return differences;
+    fn remove_selected_countdown(&mut self) {
+        if self.selected_countdown < self.countdowns.len() {
+            self.countdowns.remove(self.selected_countdown);
         }
+        self.selected_countdown = self
+            .selected_countdown
+            .min(self.countdowns.len().saturating_sub(1));
+    }
 
-        for i in 1..self.lap_durations.len() {
-            let prev_lap = self.lap_durations[i - 1];
-            let current_lap = self.lap_durations[i];
-            let diff = current_lap - prev_lap;
-            differences.push(self.format_duration(diff));
+    // All foreground colors go through here so --no-color can drop them
+    fn fg(&self, color: Color) -> Style {
+        if self.use_color && self.theme.colored {
+            Style::default().fg(color)
+        } else {
+            Style::default()
         }
+    }
 
-        differences
+    fn block(&self) -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(glyphs(self.ascii).borders)
     }
 }
 
-fn main() -> io::Result<()> {
-    // Check for help flag
-    let args: Vec<String> = env::args().collect();
-    if args.contains(&"--help".to_string()) || args.contains(&"-h".to_string()) {
-        println!(
-            "ChronoRust v{} - High Precision Chronometer",
-            env!("CARGO_PKG_VERSION")
-        );
-        println!();
-        println!("A high-precision chronometer for Linux terminal built with Rust.");
-        println!("Provides millisecond-level timing accuracy and supports up to 99 hours of measurement.");
-        println!();
-        println!("USAGE:");
-        println!("    chronorust [OPTIONS]");
-        println!();
-        println!("OPTIONS:");
-        println!("    -C, --logging    Enable session logging");
-        println!("    -h, --help       Show this help message");
-        println!();
-        println!("CONTROLS:");
-        println!("    L               Record lap time");
-        println!("    R               Reset chronometer and restart");
-        println!("    S               Pause/Resume chronometer");
-        println!("    Q               Quit application");
-        println!();
-        println!("FEATURES:");
-        println!("    • High precision millisecond timing");
-        println!("    • Lap time recording with differences");
-        println!("    • Pause/Resume functionality");
-        println!("    • Optional session logging");
-        println!("    • Cross-platform support (Linux, Windows, macOS)");
-        println!();
-        println!("EXAMPLES:");
-        println!("    chronorust              # Start chronometer");
-        println!("    chronorust -C           # Start with logging enabled");
-        println!();
-        println!("For more information, visit: https://github.com/stulluk/chronorust");
-        return Ok(());
+// The stopwatches open in the TUI, one per tab. The first is the session
+// timer built from the command line: it's the one that logs, is saved with
+// --binary-state and is summarised on exit.
+struct App {
+    timers: Vec<Chronometer>,
+    active: usize,
+}
+
+impl App {
+    fn new(first: Chronometer) -> Self {
+        Self {
+            timers: vec![first],
+            active: 0,
+        }
     }
 
-    // Check for logging flag
-    let enable_logging = args.contains(&"-C".to_string());
+    fn active(&self) -> &Chronometer {
+        &self.timers[self.active]
+    }
 
-    if enable_logging {
-        println!("Logging enabled. Log file will be created in current directory.");
+    fn active_mut(&mut self) -> &mut Chronometer {
+        &mut self.timers[self.active]
     }
 
-    // Setup terminal
-    terminal::enable_raw_mode()?;
-    let mut stdout = stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    fn session(&self) -> &Chronometer {
+        &self.timers[0]
+    }
 
-    let mut chronometer = Chronometer::new();
+    // New timers start right away with the session timer's settings
+    fn add_timer(&mut self) {
+        let mut chronometer = self.timers[0].new_like();
+        chronometer.start().expect("a new chronometer is idle");
+        self.timers.push(chronometer);
+        self.active = self.timers.len() - 1;
+    }
 
-    // Enable logging if requested
-    if enable_logging {
-        chronometer.enable_logging()?;
+    fn select_next(&mut self, forward: bool) {
+        let count = self.timers.len();
+        self.active = if forward {
+            (self.active + 1) % count
+        } else {
+            (self.active + count - 1) % count
+        };
+    }
+
+    // Every timer keeps counting while another tab is shown
+    fn tick(&mut self) {
+        for chronometer in &mut self.timers {
+            chronometer.tick();
+        }
     }
+}
 
-    chronometer.start();
-    let mut running = true;
+// What the lap heatmap colors by
+#[derive(Clone, Copy, PartialEq)]
+enum LapColors {
+    // Green for the fastest split through red for the slowest
+    Heat,
+    // Green, yellow or red for faster than, close to or slower than the
+    // average
+    Pace,
+}
 
-    // Main loop
-    while running {
-        terminal.draw(|f| ui(f, &chronometer))?;
-
-        // Handle input
-        if event::poll(Duration::from_millis(50))? {
-            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
-                match code {
-                    KeyCode::Char('q') | KeyCode::Char('Q') => {
-                        running = false;
-                    }
-                    KeyCode::Char('r') | KeyCode::Char('R') => {
-                        chronometer.reset();
-                    }
-                    KeyCode::Char('l') | KeyCode::Char('L') => {
-                        if chronometer.is_running {
-                            chronometer.add_lap();
-                        }
-                    }
-                    KeyCode::Char('s') | KeyCode::Char('S') => {
-                        if chronometer.is_paused {
-                            chronometer.resume();
-                        } else {
-                            chronometer.pause();
-                        }
-                    }
-                    _ => {}
-                }
+#[derive(Clone, Copy, PartialEq)]
+enum SummaryFormat {
+    Json,
+    Kv,
+}
+
+// Read-only list of past sessions, newest first; Enter shows the laps of
+// the selected one
+struct HistoryView {
+    entries: Vec<HistoryEntry>,
+    selected: usize,
+    // Lap cursor while a session's laps are shown
+    viewing: Option<usize>,
+}
+
+impl HistoryView {
+    fn new(entries: Vec<HistoryEntry>) -> Self {
+        Self {
+            entries,
+            selected: 0,
+            viewing: None,
+        }
+    }
+
+    // Returns whether the key was used; Esc only steps back from laps here
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        let len = match self.viewing {
+            Some(_) => self.entries[self.selected].state.laps_ms.len(),
+            None => self.entries.len(),
+        };
+        let last = len.saturating_sub(1);
+        let page = LAP_PAGE as usize;
+        let cursor = match self.viewing {
+            Some(ref mut lap) => lap,
+            None => &mut self.selected,
+        };
+        match key {
+            KeyCode::Up => *cursor = cursor.saturating_sub(1),
+            KeyCode::Down => *cursor = (*cursor + 1).min(last),
+            KeyCode::PageUp => *cursor = cursor.saturating_sub(page),
+            KeyCode::PageDown => *cursor = (*cursor + page).min(last),
+            KeyCode::Home => *cursor = 0,
+            KeyCode::End => *cursor = last,
+            KeyCode::Enter if self.viewing.is_none() && !self.entries.is_empty() => {
+                self.viewing = Some(0)
             }
+            KeyCode::Esc if self.viewing.is_some() => self.viewing = None,
+            _ => return false,
         }
+        true
     }
+}
 
-    // Restore terminal
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal::disable_raw_mode()?;
-    println!("ChronoRust stopped. Goodbye!");
-    Ok(())
+// View-only filter over lap splits, typed as ">01:30" or "<00:45"
+#[derive(Clone, Copy)]
+struct LapFilter {
+    above: bool,
+    threshold: Duration,
 }
 
-fn ui(f: &mut Frame, chronometer: &Chronometer) {
-    let chunks = Layout::default()
-        .direction(Direction::Vertical)
-        .margin(2)
-        .constraints([
-            Constraint::Length(3), // Title
-            Constraint::Length(3), // Time display
-            Constraint::Min(5),    // Lap times
-            Constraint::Length(3), // Controls
-        ])
-        .split(f.size());
+impl LapFilter {
+    fn parse(input: &str, lang: Lang) -> Result<Self, String> {
+        let input = input.trim();
+        let (above, threshold) = if let Some(rest) = input.strip_prefix('>') {
+            (true, rest)
+        } else if let Some(rest) = input.strip_prefix('<') {
+            (false, rest)
+        } else {
+            return Err(lang.text(Msg::FilterNeedsSign).to_string());
+        };
+        let threshold = parse_duration(threshold)?;
+        Ok(Self { above, threshold })
+    }
 
-    // Title
-    let title = Paragraph::new(format!(
-        "ChronoRust v{} - High Precision Chronometer",
-        env!("CARGO_PKG_VERSION")
-    ))
-    .style(
-        Style::default()
-            .fg(Color::Cyan)
-            .add_modifier(Modifier::BOLD),
-    )
-    .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::ALL));
-    f.render_widget(title, chunks[0]);
+    fn matches(self, split: Duration) -> bool {
+        if self.above {
+            split > self.threshold
+        } else {
+            split < self.threshold
+        }
+    }
 
-    // Time display
-    let time_text = if chronometer.is_paused {
-        format!("⏸️  {}", chronometer.display())
+    fn label(self, chronometer: &Chronometer) -> String {
+        let comparator = if self.above { '>' } else { '<' };
+        format!(
+            "{}{}",
+            comparator,
+            chronometer.format_duration(self.threshold)
+        )
+    }
+}
+
+fn main() -> io::Result<()> {
+    let cli = Cli::parse();
+    let options = cli.options;
+    let history_dir = config::data_dir().map(|dir| dir.join("history"));
+
+    if let Some(Mode::History { number }) = cli.mode {
+        let mut chronometer = Chronometer::new();
+        chronometer.precision = options.precision;
+        chronometer.datetime_format = options.datetime_format;
+        chronometer.time_format = options.time_format;
+        return print_history(history_dir.as_deref(), number, &chronometer);
+    }
+
+    if let Some(Mode::Bench { duration }) = cli.mode {
+        return bench::run(duration, options.fps);
+    }
+
+    let socket = options.socket.clone().or_else(config::socket_path);
+    if let Some(Mode::Ctl { ref command }) = cli.mode {
+        return run_ctl(socket.as_deref(), command);
+    }
+    let daemon = matches!(cli.mode, Some(Mode::Daemon));
+    let attach = matches!(cli.mode, Some(Mode::Attach));
+    if (daemon || attach) && cfg!(not(unix)) {
+        let mode = if daemon { "daemon" } else { "attach" };
+        eprintln!(
+            "{} needs a Unix domain socket, which this system doesn't have",
+            mode
+        );
+        std::process::exit(1);
+    }
+
+    // A timed command is shown in the TUI, whatever stdin is
+    let run_command = match cli.mode {
+        Some(Mode::Run { ref command }) => Some(command.clone()),
+        _ => None,
+    };
+    if run_command.is_some() && (options.no_tui || options.line) {
+        Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "run needs the TUI; it can't be combined with --no-tui or --line",
+            )
+            .exit();
+    }
+
+    // Attached, the session and everything kept of it are the daemon's
+    if attach {
+        let flag = [
+            ("--no-tui", options.no_tui),
+            ("--line", options.line),
+            ("--resume", options.resume),
+            ("--binary-state", options.binary_state.is_some()),
+            ("--since", options.since.is_some()),
+            ("-C", options.logging),
+            ("--log-file", options.log_file.is_some()),
+        ]
+        .into_iter()
+        .find(|&(_, set)| set);
+        if let Some((flag, _)) = flag {
+            Cli::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    format!(
+                        "attach shows the daemon's session; it can't be combined with {}",
+                        flag
+                    ),
+                )
+                .exit();
+        }
+    }
+
+    // Check for logging flag; a log file of its own implies it
+    let enable_logging = options.logging || options.log_file.is_some();
+
+    let output_dir = options.output_dir.and_then(prepare_output_dir);
+
+    let prestart = options.prestart.map(Duration::from_secs);
+
+    let lang = options.lang.unwrap_or_else(Lang::from_env);
+
+    // Unicode glyphs need a UTF-8 locale unless forced either way
+    let ascii = if options.ascii {
+        true
+    } else if options.unicode {
+        false
     } else {
-        format!("⏱️  {}", chronometer.display())
+        !locale_supports_utf8()
     };
 
-    let time_paragraph = Paragraph::new(time_text)
-        .style(
-            Style::default()
-                .fg(Color::Green)
-                .add_modifier(Modifier::BOLD),
-        )
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL).title("Time"));
-    f.render_widget(time_paragraph, chunks[1]);
+    // A zero idle threshold or pace turns the feature off
+    let idle_pause = options.idle_pause.filter(|threshold| !threshold.is_zero());
+    let target_pace = options.pace.filter(|pace| !pace.is_zero());
 
-    // Lap times with differences
-    let mut lap_items: Vec<ListItem> = Vec::new();
-    let differences = chronometer.get_lap_differences();
+    // Lap names from a speedrun's splits
+    let segment_names = match options.segments {
+        Some(ref path) => match Chronometer::import_lss_segments(path) {
+            Ok(names) => names,
+            Err(err) => {
+                eprintln!("Cannot import --segments '{}': {}", path.display(), err);
+                std::process::exit(1);
+            }
+        },
+        None => Vec::new(),
+    };
 
-    for (i, lap_time) in chronometer.lap_times.iter().enumerate() {
-        let mut lap_text = format!("Lap {}: {}", i + 1, lap_time);
+    let reference_laps = match options.compare {
+        Some(ref path) => match Chronometer::import_reference(path) {
+            Ok(laps) => laps,
+            Err(err) => {
+                eprintln!("Cannot import --compare '{}': {}", path.display(), err);
+                std::process::exit(1);
+            }
+        },
+        None => Vec::new(),
+    };
 
-        // Add difference if available
-        if i > 0 && i - 1 < differences.len() {
-            lap_text.push_str(&format!(" (Δ: {})", differences[i - 1]));
+    let alarm_at = match cli.mode {
+        Some(Mode::Alarm { ref time, ref at }) => {
+            let target = match (time, at) {
+                (Some(time), _) => next_time_of_day(time),
+                (None, Some(at)) => future_datetime(at),
+                (None, None) => unreachable!("clap requires TIME or --at"),
+            };
+            match target {
+                Ok(target) => Some(target),
+                Err(err) => {
+                    eprintln!("Invalid alarm time: {}", err);
+                    std::process::exit(1);
+                }
+            }
         }
+        _ => None,
+    };
+    let pomodoro = match cli.mode {
+        Some(Mode::Pomodoro { work, rest }) => Some(Pomodoro::new(work, rest)),
+        _ => None,
+    };
+    let interval = match cli.mode {
+        Some(Mode::Interval { work, rest, rounds }) => match Interval::new(work, rest, rounds) {
+            Some(interval) => Some(interval),
+            None => {
+                eprintln!(
+                    "Invalid interval: {} rounds of that length are too long",
+                    rounds
+                );
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
 
-        lap_items.push(ListItem::new(lap_text).style(Style::default().fg(Color::Yellow)));
-    }
+    // A plan file is read with the config file's TOML subset; it brings its
+    // own phases, so it doesn't go with the other timer modes
+    let plan = match options.session {
+        Some(ref path) => {
+            if matches!(
+                cli.mode,
+                Some(
+                    Mode::Countdown { .. }
+                        | Mode::Alarm { .. }
+                        | Mode::Pomodoro { .. }
+                        | Mode::Interval { .. }
+                        | Mode::Seq { .. }
+                )
+            ) {
+                eprintln!("--session can only be used with the stopwatch");
+                std::process::exit(1);
+            }
+            let plan = Config::load_existing(path).and_then(|file| {
+                let segments = file.array("", "segments")?.unwrap_or_default();
+                Plan::new(
+                    file.string("", "title")?,
+                    file.string("", "advance")?,
+                    &segments,
+                )
+            });
+            match plan {
+                Ok(plan) => Some(plan),
+                Err(err) => {
+                    eprintln!("Invalid --session '{}': {}", path.display(), err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => None,
+    };
 
-    let lap_list = List::new(lap_items)
-        .block(Block::default().borders(Borders::ALL).title("Lap Times"))
-        .highlight_style(Style::default().add_modifier(Modifier::BOLD));
-    f.render_widget(lap_list, chunks[2]);
+    // NO_COLOR (https://no-color.org) counts when set to any non-empty value
+    let use_color =
+        !options.no_color && env::var("NO_COLOR").map_or(true, |value| value.is_empty());
 
-    // Controls
-    let controls_text = "Controls: R - Reset | L - Lap | S - Pause/Resume | Q - Quit";
-    let controls_paragraph = Paragraph::new(controls_text)
-        .style(Style::default().fg(Color::Gray))
-        .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::ALL).title("Controls"));
-    f.render_widget(controls_paragraph, chunks[3]);
+    // Key remapping from the [keys] table of the config file
+    let config = match config::default_path() {
+        Some(path) => match Config::load(&path) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("Invalid config file {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        },
+        None => Config::default(),
+    };
+    let keymap = match config
+        .table("keys")
+        .and_then(|entries| KeyMap::from_entries(&entries))
+    {
+        Ok(keymap) => keymap,
+        Err(err) => {
+            eprintln!("Invalid [keys] in config file: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    // --theme picks a built-in theme over the config file's [theme] table
+    let theme = match options.theme {
+        Some(theme) => theme,
+        None => match config
+            .table("theme")
+            .and_then(|entries| Theme::from_entries(&entries))
+        {
+            Ok(theme) => theme,
+            Err(err) => {
+                eprintln!("Invalid [theme] in config file: {}", err);
+                std::process::exit(1);
+            }
+        },
+    };
+
+    // Named durations for `countdown NAME` and the Shift+P menu
+    let presets = match config
+        .table("presets")
+        .and_then(|entries| parse_presets(&entries))
+    {
+        Ok(presets) => presets,
+        Err(err) => {
+            eprintln!("Invalid [presets] in config file: {}", err);
+            std::process::exit(1);
+        }
+    };
+    let preset_or_duration =
+        |duration: &str| match presets.iter().find(|(name, _)| name == duration) {
+            Some(&(_, preset)) => Ok(preset),
+            None => cli::positive_duration(duration)
+                .map_err(|err| format!("{}, and no preset is named '{}'", err, duration)),
+        };
+    let sequence = match cli.mode {
+        Some(Mode::Seq { ref durations }) => {
+            let segments = durations
+                .iter()
+                .enumerate()
+                .map(|(index, segment)| {
+                    preset_or_duration(segment)
+                        .map_err(|err| format!("segment {}: {}", index + 1, err))
+                })
+                .collect::<Result<Vec<_>, _>>();
+            match segments {
+                Ok(segments) => Some(Sequence::new(segments)),
+                Err(err) => {
+                    eprintln!("Invalid seq {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        _ => None,
+    };
+    // A sequence counts down as a whole, segment by segment
+    let countdown_from = match cli.mode {
+        Some(Mode::Countdown { ref duration, .. }) => match preset_or_duration(duration) {
+            Ok(duration) => Some(duration),
+            Err(err) => {
+                eprintln!("Invalid countdown: {}", err);
+                std::process::exit(1);
+            }
+        },
+        _ => sequence.as_ref().map(Sequence::total),
+    };
+
+    // --target over the [target] table's total; lap targets only come from
+    // the config file
+    let target = config.string("target", "total").and_then(|total| {
+        let laps = config.array("target", "laps")?.unwrap_or_default();
+        Target::parse(total, &laps)
+    });
+    let mut target = match target {
+        Ok(target) => target,
+        Err(err) => {
+            eprintln!("Invalid [target] in config file: {}", err);
+            std::process::exit(1);
+        }
+    };
+    target.total = options.target.or(target.total);
+
+    let mut app = AppState::new();
+    app.keymap = keymap;
+    app.presets = presets;
+    app.rate_unit = options.rate_unit;
+    app.lang = lang;
+    app.ascii = ascii;
+    app.use_color = use_color;
+    app.theme = theme;
+    app.project_target = options.project;
+    app.bpm = options.bpm;
+    app.beep = options.beep;
+    app.notify = options.notify;
+    app.sound.file = options.sound;
+    app.history_dir = history_dir.clone();
+    app.histogram_bucket = options.bucket;
+    app.heatmap = !options.no_heatmap;
+    app.locked = options.locked;
+    app.confirm = !options.no_confirm;
+    app.lap_colors = options.lap_colors;
+    app.lap_tolerance = options.lap_tolerance / 100.0;
+    app.mouse = !options.no_mouse;
+    app.show_splits = options.splits;
+    app.big_digits = options.big;
+    app.export_format = if options.export_lss {
+        ExportFormat::Lss
+    } else {
+        options.export_format
+    };
+    app.pomodoro = pomodoro;
+    // The date is left out while the alarm is due today
+    app.alarm = alarm_at.map(|at| {
+        let today = at.date_naive() == chrono::Local::now().date_naive();
+        if today {
+            options.time_format.time_of_day(&at)
+        } else {
+            options.time_format.datetime(&at)
+        }
+    });
+    app.interval = interval;
+    app.plan = plan;
+    app.sequence = sequence;
+
+    let mut chronometer = Chronometer::new();
+    chronometer.idle_pause = idle_pause;
+    chronometer.target_pace = target_pace;
+    chronometer.precision = options.precision;
+    chronometer.rounding = options.rounding;
+    chronometer.target_laps = options.project_laps;
+    chronometer.skip_laps = options.skip_laps;
+    if options.tag.is_some() {
+        chronometer.set_project(options.tag);
+    }
+    chronometer.datetime_format = options.datetime_format;
+    chronometer.time_format = options.time_format;
+    chronometer.lap_distance = options.lap_distance;
+    chronometer.pause_budget = options.pause_budget;
+    chronometer.output_dir = output_dir;
+    chronometer.reference_laps = reference_laps;
+    chronometer.target = target;
+    chronometer.segment_names = segment_names;
+    chronometer.countdown_from = countdown_from;
+    chronometer.overtime = matches!(cli.mode, Some(Mode::Countdown { overtime: true, .. }));
+    chronometer.alarm_at = alarm_at.map(SystemTime::from);
+    chronometer.auto_lap = options.auto_lap;
+    if !options.no_hooks {
+        let dir = options.hooks_dir.clone().or_else(config::hooks_dir);
+        match config
+            .table("hooks")
+            .and_then(|entries| Hooks::new(dir, &entries))
+        {
+            Ok(hooks) => chronometer.hooks = Some(hooks),
+            Err(err) => {
+                eprintln!("Invalid [hooks] in config file: {}", err);
+                std::process::exit(1);
+            }
+        }
+        #[cfg(feature = "scripting")]
+        if let (Some(hooks), Some(path)) = (chronometer.hooks.as_mut(), config::hooks_script()) {
+            if let Err(err) = hooks.load_script(&path) {
+                eprintln!("Invalid hook script {}: {}", path.display(), err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Enable logging if requested
+    if enable_logging {
+        let path = options.log_file.as_deref();
+        match chronometer.enable_logging(path, options.log_format) {
+            Ok(filename) => println!("Logging enabled. Log file: {}", filename.display()),
+            Err(err) => {
+                let filename = path.map_or("the log file".into(), |path| {
+                    format!("'{}'", path.display())
+                });
+                eprintln!("Cannot create {}: {}", filename, err);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // Laps of an earlier session, shown but not timed
+    let imported = match options.import {
+        Some(ref path) => {
+            if cli
+                .mode
+                .as_ref()
+                .is_some_and(|mode| !matches!(mode, Mode::Stopwatch))
+            {
+                eprintln!("--import can only be used with the stopwatch");
+                std::process::exit(1);
+            }
+            match Chronometer::import_laps(path) {
+                Ok(laps) => Some(laps),
+                Err(err) => {
+                    eprintln!("Cannot import --import '{}': {}", path.display(), err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        None => None,
+    };
+
+    // A binary state file resumes the saved session when it already exists
+    let binary_state = options.binary_state.as_deref();
+    let resumed = match binary_state {
+        Some(path) if path.exists() => match load_state_binary(path) {
+            Ok(state) => Some(state),
+            Err(err) => {
+                eprintln!("Cannot load --binary-state '{}': {}", path.display(), err);
+                std::process::exit(1);
+            }
+        },
+        _ => None,
+    };
+
+    // --resume keeps the session in the data directory between runs
+    let resume_path = match (options.resume, config::data_dir()) {
+        (false, _) => None,
+        (true, Some(dir)) => Some(dir.join("session.json")),
+        (true, None) => {
+            eprintln!("--resume needs HOME or XDG_DATA_HOME to find its session file");
+            std::process::exit(1);
+        }
+    };
+    let resumed = resumed.or_else(|| offer_resume(&chronometer, resume_path.as_deref()));
+    // A reviewed session isn't a new one to keep
+    let recorded_history = history_dir
+        .as_deref()
+        .filter(|_| !options.no_history && imported.is_none() && !attach);
+    // And its time goes on the day's total, shown in the stats box
+    let daily_path = config::data_dir()
+        .map(|dir| dir.join("daily.tsv"))
+        .filter(|_| recorded_history.is_some());
+    if let Some(ref path) = daily_path {
+        match daily::total(path, &daily::today()) {
+            Ok(total) => app.today_before = Some(total),
+            Err(err) => eprintln!("Cannot read '{}': {}", path.display(), err),
+        }
+    }
+
+    // `attach` starts from the daemon's session as it stands
+    let attached = attach.then(|| attach_to(socket.as_deref()));
+
+    let started = match (imported, resumed, options.since, prestart) {
+        _ if attach => Ok(()),
+        (Some(laps), ..) => chronometer.review(&laps),
+        (None, Some(state), _, _) => chronometer.restore(&state),
+        (None, None, Some(event), _) => match elapsed_since(&event) {
+            Ok(elapsed) => chronometer.start_since(elapsed),
+            Err(err) => {
+                eprintln!("Invalid --since value: {}", err);
+                std::process::exit(1);
+            }
+        },
+        // The daemon waits for `start`
+        (None, None, None, _) if daemon => Ok(()),
+        (None, None, None, Some(countdown)) if !countdown.is_zero() => {
+            chronometer.start_with_prestart(countdown)
+        }
+        _ => chronometer.start(),
+    };
+    started.expect("the session chronometer starts out idle");
+    if let Some((socket, state)) = attached {
+        chronometer.follow(state.as_ref());
+        // The daemon's own settings pace its session
+        chronometer.idle_pause = None;
+        chronometer.pause_budget = None;
+        chronometer.auto_lap = None;
+        app.attached = Some(Attached {
+            socket,
+            synced: Instant::now(),
+            ended: None,
+        });
+    }
+
+    // Optional status API for polling from another device
+    let http_addr = options.http;
+    if http_addr.is_some() && !cfg!(feature = "http") {
+        eprintln!("--http needs a build with the 'http' feature (cargo build --features http)");
+        std::process::exit(1);
+    }
+
+    // Optional world clock beside the laps. --zones shows it from the
+    // start; zones from the config file wait for C.
+    let zones_spec = options.zones;
+    if zones_spec.is_some() && !cfg!(feature = "zones") {
+        eprintln!("--zones needs a build with the 'zones' feature (cargo build --features zones)");
+        std::process::exit(1);
+    }
+    let clock_zones = match config.array("", "clock_zones") {
+        Ok(names) => names,
+        Err(err) => {
+            eprintln!("Invalid config file: {}", err);
+            std::process::exit(1);
+        }
+    };
+    #[cfg(feature = "zones")]
+    {
+        let (names, source) = match zones_spec {
+            Some(ref spec) => (spec.split(',').collect(), "--zones"),
+            None => (clock_zones.unwrap_or_default(), "clock_zones"),
+        };
+        let (zones, unknown) = zones::parse_zones(names);
+        for name in unknown {
+            eprintln!("Skipping unknown {} entry '{}'", source, name);
+        }
+        app.zones = zones;
+        app.show_clock = zones_spec.is_some();
+    }
+    #[cfg(not(feature = "zones"))]
+    let _ = clock_zones;
+
+    #[cfg(unix)]
+    if daemon {
+        let Some(ref socket) = socket else {
+            eprintln!("daemon needs --socket, XDG_RUNTIME_DIR or HOME to place its socket");
+            std::process::exit(1);
+        };
+        if let Err(err) = daemon::run(&mut chronometer, socket) {
+            eprintln!("Cannot run the daemon on '{}': {}", socket.display(), err);
+            std::process::exit(1);
+        }
+        let format = options.summary_format.unwrap_or(SummaryFormat::Kv);
+        print_summary(&chronometer, Some(format), lang);
+        return finish_session(
+            &mut chronometer,
+            binary_state,
+            resume_path.as_deref(),
+            recorded_history,
+            daily_path.as_deref(),
+        );
+    }
+
+    // Headless mode for scripts: signals in, plain lines out
+    if options.no_tui {
+        run_headless(&mut chronometer, options.interval)?;
+        let format = options.summary_format.unwrap_or(SummaryFormat::Kv);
+        print_summary(&chronometer, Some(format), lang);
+        return finish_session(
+            &mut chronometer,
+            binary_state,
+            resume_path.as_deref(),
+            recorded_history,
+            daily_path.as_deref(),
+        );
+    }
+
+    // Line mode free-runs without the TUI
+    if options.line {
+        run_line_mode(&mut chronometer, app.ascii, options.fps)?;
+        print_summary(&chronometer, options.summary_format, lang);
+        return finish_session(
+            &mut chronometer,
+            binary_state,
+            resume_path.as_deref(),
+            recorded_history,
+            daily_path.as_deref(),
+        );
+    }
+
+    // Piped input drives the chronometer with text commands instead of keys
+    if run_command.is_none() && !io::stdin().is_terminal() {
+        let flag = [
+            ("--write-current", options.write_current.is_some()),
+            ("--ws-port", options.ws_port.is_some()),
+            ("--import", options.import.is_some()),
+            ("attach", attach),
+        ]
+        .into_iter()
+        .find(|&(_, set)| set);
+        if let Some((flag, _)) = flag {
+            eprintln!("{} needs the TUI, and stdin is not a terminal", flag);
+            std::process::exit(1);
+        }
+        run_stdin_mode(&mut chronometer)?;
+        // Its replies already end with the state, so the summary is opt-in
+        if options.summary_format.is_some() {
+            print_summary(&chronometer, options.summary_format, lang);
+        }
+        return finish_session(
+            &mut chronometer,
+            binary_state,
+            resume_path.as_deref(),
+            recorded_history,
+            daily_path.as_deref(),
+        );
+    }
+
+    // Spawned before the TUI starts so a bad command fails on a plain terminal
+    if let Some(ref command) = run_command {
+        let output = chronometer.output_path(&format!(
+            "ChronoRust-{}-output.txt",
+            chronometer.file_stamp(chrono::Utc::now())
+        ));
+        match RunningCommand::spawn(command, &output) {
+            Ok(running) => app.command = Some(running),
+            Err(err) => {
+                eprintln!("Cannot run '{}': {}", command.join(" "), err);
+                std::process::exit(1);
+            }
+        }
+        let line = format!(
+            "Command '{}' started at: {} - Output: {}",
+            command.join(" "),
+            chronometer.wall_clock(),
+            output.display()
+        );
+        chronometer.log_line(&line);
+    }
+
+    // The status API shares the timers with the draw loop
+    let shared = Arc::new(Mutex::new(App::new(chronometer)));
+    #[cfg(feature = "http")]
+    if let Some(addr) = http_addr {
+        let addr = http::bind_address(&addr);
+        if let Err(err) = http::serve(&addr, Arc::clone(&shared)) {
+            eprintln!("Cannot serve --http on {}: {}", addr, err);
+            std::process::exit(1);
+        }
+    }
+
+    if let Some(port) = options.ws_port {
+        match ws::serve(port, Arc::clone(&shared)) {
+            Ok(sender) => shared.lock().unwrap().timers[0].broadcast = Some(sender),
+            Err(err) => {
+                eprintln!("Cannot broadcast on --ws-port {}: {}", port, err);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(ref path) = options.write_current {
+        let interval = Duration::from_millis(options.write_interval);
+        if let Err(err) = current_file::spawn(path, interval, Arc::clone(&shared)) {
+            eprintln!("Cannot write --write-current '{}': {}", path.display(), err);
+            std::process::exit(1);
+        }
+    }
+
+    // Setup terminal
+    let mut tui = Tui::enter(app.mouse)?;
+    // Raw mode turns Ctrl+Z into a key, which sets the same flag as a
+    // SIGTSTP sent from outside
+    #[cfg(unix)]
+    let suspend = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    let continued = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    {
+        signal_hook::flag::register(signal_hook::consts::SIGTSTP, Arc::clone(&suspend))?;
+        signal_hook::flag::register(signal_hook::consts::SIGCONT, Arc::clone(&continued))?;
+    }
+
+    // Fatal errors end the loop but still go through terminal restore
+    let mut fatal = None;
+    let mut input_errors = 0;
+    let mut hits = HitAreas::default();
+    // A frame is drawn after every input and whenever what's shown changed,
+    // at most --fps times a second
+    let frame = Duration::from_secs(1) / options.fps;
+    let mut redraw = true;
+    let mut last_frame: Option<(String, Instant)> = None;
+
+    // Main loop
+    loop {
+        #[cfg(unix)]
+        {
+            let resumed = if suspend.swap(false, Ordering::Relaxed) {
+                tui.suspend()
+            } else if continued.load(Ordering::Relaxed) {
+                tui.resume()
+            } else {
+                Ok(())
+            };
+            continued.store(false, Ordering::Relaxed);
+            if let Err(err) = resumed {
+                fatal = Some(err);
+                break;
+            }
+        }
+
+        // The lock is released before waiting on input
+        {
+            let mut timers = shared.lock().unwrap();
+            timers.tick();
+            #[cfg(unix)]
+            app.tick_attached(&mut timers.timers[0]);
+            app.tick(timers.active_mut());
+            app.tick_pomodoro(&mut timers.timers[0]);
+            app.tick_interval(&mut timers.timers[0]);
+            app.tick_plan(&mut timers.timers[0]);
+            app.tick_sequence(&mut timers.timers[0]);
+            app.tick_command(&mut timers.timers[0]);
+            app.tick_today(&timers.timers[0]);
+            // Only the session timer logs
+            if let Some(err) = timers.timers[0].take_log_error() {
+                app.set_error(app.lang.fill(Msg::LogWriteFailed, &[("error", &err)]));
+            }
+            let key = app.frame_key(&timers);
+            let stale = last_frame
+                .as_ref()
+                .is_none_or(|(last, drawn)| *last != key || drawn.elapsed() >= IDLE_REDRAW);
+            if redraw || stale {
+                if let Err(err) = tui.terminal.draw(|f| hits = ui(f, &timers, &app)) {
+                    fatal = Some(err);
+                    break;
+                }
+                redraw = false;
+                last_frame = Some((key, Instant::now()));
+            }
+        }
+
+        // Handle input; transient read errors are logged and skipped
+        let input = match read_input(frame) {
+            Ok(input) => {
+                input_errors = 0;
+                redraw |= input.is_some();
+                input
+            }
+            Err(err) => {
+                let mut timers = shared.lock().unwrap();
+                match skip_input_error(&mut timers, err, &mut input_errors) {
+                    Ok(()) => None,
+                    Err(err) => {
+                        fatal = Some(err);
+                        break;
+                    }
+                }
+            }
+        };
+        let code = match input {
+            Some(Input::Key(code)) => code,
+            #[cfg(unix)]
+            Some(Input::Suspend) => {
+                suspend.store(true, Ordering::Relaxed);
+                continue;
+            }
+            Some(Input::Detach) if app.attached.is_some() => break,
+            Some(Input::Detach) => KeyCode::Char('d'),
+            Some(Input::Mouse(mouse)) => {
+                let control = handle_mouse(&mut shared.lock().unwrap(), &mut app, mouse, &hits);
+                if control == LoopControl::Quit {
+                    break;
+                }
+                continue;
+            }
+            // Takes the new size and clears the screen straight away, so the
+            // next frame is drawn whole rather than diffed against cells
+            // the terminal may have reflowed
+            Some(Input::Resize) => {
+                if let Err(err) = tui.terminal.autoresize() {
+                    fatal = Some(err);
+                    break;
+                }
+                continue;
+            }
+            None => continue,
+        };
+
+        if handle_key(&mut shared.lock().unwrap(), &mut app, code) == LoopControl::Quit {
+            break;
+        }
+    }
+
+    // Restore terminal
+    tui.leave()?;
+    // Attached, the session is the daemon's to finish
+    if let Some(attached) = app.attached.take() {
+        if let Some(err) = fatal {
+            return Err(err);
+        }
+        match attached.ended {
+            Some(reply) => println!("{}", reply),
+            None => println!(
+                "{}",
+                app.lang
+                    .fill(Msg::Detached, &[("socket", &attached.socket.display())])
+            ),
+        }
+        return Ok(());
+    }
+    let mut timers = shared.lock().unwrap();
+    let chronometer = &mut timers.timers[0];
+    let saved = finish_session(
+        chronometer,
+        binary_state,
+        resume_path.as_deref(),
+        recorded_history,
+        daily_path.as_deref(),
+    );
+    // The summary is printed even when saving failed, for scripts reading it
+    print_summary(chronometer, options.summary_format, app.lang);
+    // A dead terminal still gets the session logged and saved first
+    if let Some(err) = fatal {
+        return Err(err);
+    }
+    saved?;
+    if options.summary_format.is_none() {
+        println!("{}", app.lang.text(Msg::Goodbye));
+    }
+
+    // Like time(1), pass the command's exit code on
+    if let Some(mut command) = app.command {
+        command.kill();
+        let time = chronometer.format_duration(chronometer.elapsed());
+        println!(
+            "{}",
+            app.lang.fill(
+                Msg::CommandEnded,
+                &[
+                    ("command", &command.command_line),
+                    ("state", &command_state(&command, app.lang)),
+                    ("time", &time),
+                ],
+            )
+        );
+        std::process::exit(command.status.and_then(|status| status.code()).unwrap_or(1));
+    }
+    Ok(())
+}
+
+// Redraws happen at least this often, for what frame_key() doesn't cover
+// such as the world clock
+const IDLE_REDRAW: Duration = Duration::from_secs(1);
+
+// Laps moved by PageUp/PageDown
+const LAP_PAGE: isize = 10;
+
+// How long after the lock key Enter still unlocks
+const UNLOCK_WINDOW: Duration = Duration::from_secs(2);
+
+fn handle_key(timers: &mut App, app: &mut AppState, key: KeyCode) -> LoopControl {
+    // The key that stops a ringing alarm does nothing else
+    if app.alarm_ringing.take().is_some() {
+        return LoopControl::Continue;
+    }
+
+    // The key that wakes an auto-paused session only resumes it
+    if timers.active_mut().record_activity() {
+        return LoopControl::Continue;
+    }
+
+    // Locked, only the lock key followed soon after by Enter gets through
+    if app.locked {
+        let lock_key =
+            matches!(key, KeyCode::Char(c) if app.keymap.action(c) == Some(Action::Lock));
+        let armed = app
+            .unlock_armed
+            .take()
+            .is_some_and(|at| at.elapsed() < UNLOCK_WINDOW);
+        if armed && key == KeyCode::Enter {
+            app.locked = false;
+            app.set_status(app.lang.text(Msg::ControlsUnlocked).to_string());
+        } else if lock_key {
+            app.unlock_armed = Some(Instant::now());
+            app.set_status(app.lang.text(Msg::PressEnterToUnlock).to_string());
+        } else {
+            app.set_error(
+                app.lang
+                    .fill(Msg::LockedHint, &[("key", &app.keymap.label(Action::Lock))]),
+            );
+        }
+        return LoopControl::Continue;
+    }
+
+    // Only yes or no answers a confirmation, so a stray key changes nothing
+    if let Some(action) = app.confirming {
+        match key {
+            KeyCode::Char('y' | 'Y') | KeyCode::Enter => {
+                app.confirming = None;
+                return perform_action(timers, app, action);
+            }
+            KeyCode::Char('n' | 'N') | KeyCode::Esc => app.confirming = None,
+            _ => {}
+        }
+        return LoopControl::Continue;
+    }
+
+    let chronometer = timers.active_mut();
+
+    if let Some(input) = app.input.as_mut() {
+        match key {
+            KeyCode::Esc => app.input = None,
+            KeyCode::Enter => {
+                let result = match input.purpose {
+                    InputPurpose::ResetOffset => parse_duration(&input.buffer).map(|offset| {
+                        chronometer.reset_to(offset);
+                        app.selected_lap = None;
+                    }),
+                    InputPurpose::AddCountdown => {
+                        let fallback = format!("Timer {}", app.countdowns.len() + 1);
+                        Countdown::parse(&input.buffer, fallback).map(|countdown| {
+                            app.countdowns.push(countdown);
+                            app.selected_countdown = app.countdowns.len() - 1;
+                        })
+                    }
+                    InputPurpose::LapNote(lap_id) => {
+                        chronometer.set_lap_note(lap_id, &input.buffer);
+                        Ok(())
+                    }
+                    InputPurpose::LapFilter => {
+                        LapFilter::parse(&input.buffer, app.lang).map(|filter| {
+                            app.lap_filter = Some(filter);
+                        })
+                    }
+                    // The time title shows the tag in force
+                    InputPurpose::Project if input.buffer.trim().is_empty() => {
+                        chronometer.set_project(None);
+                        Ok(())
+                    }
+                    InputPurpose::Project => parse_project(&input.buffer)
+                        .map(|project| chronometer.set_project(Some(project))),
+                };
+                match result {
+                    Ok(()) => app.input = None,
+                    Err(err) => input.error = Some(err),
+                }
+            }
+            KeyCode::Backspace => {
+                input.buffer.pop();
+            }
+            KeyCode::Char(c) => input.buffer.push(c),
+            _ => {}
+        }
+        return LoopControl::Continue;
+    }
+
+    // The preset menu takes the keys while it is open: Up/Down and Enter,
+    // or a preset's number to start it straight away
+    if let Some(selected) = app.preset_menu {
+        match key {
+            KeyCode::Esc => app.preset_menu = None,
+            KeyCode::Up => app.preset_menu = Some(selected.saturating_sub(1)),
+            KeyCode::Down => app.preset_menu = Some((selected + 1).min(app.presets.len() - 1)),
+            KeyCode::Enter => app.start_preset(selected),
+            KeyCode::Char(c) => {
+                if let Some(index) = c.to_digit(10).and_then(|digit| digit.checked_sub(1)) {
+                    app.start_preset(index as usize);
+                }
+            }
+            _ => {}
+        }
+        return LoopControl::Continue;
+    }
+
+    // So does the key help, which only scrolls
+    if let Some(offset) = app.help {
+        match key {
+            KeyCode::Up => app.help = Some(offset.saturating_sub(1)),
+            KeyCode::Down => app.help = Some((offset + 1).min(help_lines(app).len() as u16)),
+            KeyCode::Char(c) if app.keymap.action(c) == Some(Action::Help) => app.help = None,
+            KeyCode::Esc => app.help = None,
+            _ => {}
+        }
+        return LoopControl::Continue;
+    }
+
+    // The history screen takes the navigation keys while it is open
+    if let Some(view) = app.history.as_mut() {
+        if key == KeyCode::Esc && view.viewing.is_none() {
+            app.history = None;
+            return LoopControl::Continue;
+        }
+        if view.handle_key(key) {
+            return LoopControl::Continue;
+        }
+    }
+
+    let action = match key {
+        KeyCode::Up | KeyCode::Down | KeyCode::PageUp | KeyCode::PageDown => {
+            let delta = match key {
+                KeyCode::Up => -1,
+                KeyCode::Down => 1,
+                KeyCode::PageUp => -LAP_PAGE,
+                _ => LAP_PAGE,
+            };
+            app.move_lap_selection(chronometer, delta);
+            return LoopControl::Continue;
+        }
+        KeyCode::Home => {
+            app.selected_lap = app.visible_lap_ids(chronometer).first().copied();
+            return LoopControl::Continue;
+        }
+        KeyCode::End => {
+            app.selected_lap = None;
+            return LoopControl::Continue;
+        }
+        KeyCode::Esc => {
+            app.lap_filter = None;
+            return LoopControl::Continue;
+        }
+        // The lap cursor and filter belong to the timer being left
+        KeyCode::Tab | KeyCode::BackTab => {
+            timers.select_next(key == KeyCode::Tab);
+            app.selected_lap = None;
+            app.lap_filter = None;
+            return LoopControl::Continue;
+        }
+        KeyCode::Delete => Some(Action::DeleteLap),
+        KeyCode::Char(c) if c.is_uppercase() && app.keymap.action(c) == Some(Action::Copy) => {
+            Some(Action::CopyLaps)
+        }
+        KeyCode::Char(c) if c.is_uppercase() && app.keymap.action(c) == Some(Action::Precision) => {
+            Some(Action::Presets)
+        }
+        KeyCode::Char(c) => app.keymap.action(c),
+        _ => None,
+    };
+    match action {
+        Some(action) => run_action(timers, app, action),
+        None => LoopControl::Continue,
+    }
+}
+
+// Clicks on the controls bar buttons and the wheel over the lap list. The
+// prompt, the preset menu and the key help only take keys, so the mouse does nothing
+// while one is open, or while the controls are locked or a confirmation waits.
+fn handle_mouse(
+    timers: &mut App,
+    app: &mut AppState,
+    mouse: MouseEvent,
+    hits: &HitAreas,
+) -> LoopControl {
+    if app.locked
+        || app.confirming.is_some()
+        || app.input.is_some()
+        || app.preset_menu.is_some()
+        || app.help.is_some()
+    {
+        return LoopControl::Continue;
+    }
+    let inside = |rect: &Rect| {
+        (rect.left()..rect.right()).contains(&mouse.column)
+            && (rect.top()..rect.bottom()).contains(&mouse.row)
+    };
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => {
+            let Some(&(action, _)) = hits.buttons.iter().find(|(_, rect)| inside(rect)) else {
+                return LoopControl::Continue;
+            };
+            // Like a key, the click that stops the alarm or wakes an
+            // auto-paused session does nothing else
+            if app.alarm_ringing.take().is_some() || timers.active_mut().record_activity() {
+                return LoopControl::Continue;
+            }
+            run_action(timers, app, action)
+        }
+        // Scrolls like Up/Down, in the history screen too
+        MouseEventKind::ScrollUp if hits.laps.as_ref().is_some_and(inside) => {
+            handle_key(timers, app, KeyCode::Up)
+        }
+        MouseEventKind::ScrollDown if hits.laps.as_ref().is_some_and(inside) => {
+            handle_key(timers, app, KeyCode::Down)
+        }
+        _ => LoopControl::Continue,
+    }
+}
+
+fn run_action(timers: &mut App, app: &mut AppState, action: Action) -> LoopControl {
+    let chronometer = timers.active_mut();
+    // Looking and exporting are fine, changing the laps or the clock isn't
+    let changes = matches!(
+        action,
+        Action::Lap
+            | Action::Pause
+            | Action::Reset
+            | Action::ResetTo
+            | Action::Undo
+            | Action::DeleteLap
+            | Action::RedoLap
+            | Action::LapNote
+            | Action::Project
+    );
+    if chronometer.read_only && changes {
+        app.set_error(app.lang.text(Msg::ImportedReadOnly).to_string());
+        return LoopControl::Continue;
+    }
+    if app.confirm && at_risk(timers, action) > 0 {
+        app.confirming = Some(action);
+        return LoopControl::Continue;
+    }
+    perform_action(timers, app, action)
+}
+
+// The laps a reset of the active timer or a quit would throw away, counting
+// only timers that are running or paused
+fn at_risk(timers: &App, action: Action) -> usize {
+    let laps = |chronometer: &Chronometer| {
+        if chronometer.is_started() {
+            chronometer.lap_durations.len()
+        } else {
+            0
+        }
+    };
+    match action {
+        Action::Reset => laps(timers.active()),
+        Action::Quit => timers.timers.iter().map(laps).sum(),
+        _ => 0,
+    }
+}
+
+// Attached, what changes the session timer is asked of the daemon rather
+// than done here; None leaves the action to perform_action
+#[cfg(unix)]
+fn forward_action(timers: &mut App, app: &mut AppState, action: Action) -> Option<LoopControl> {
+    let socket = app.attached.as_ref()?.socket.clone();
+    let session = timers.active == 0;
+    let counting = timers.session().is_counting();
+    let command = match action {
+        Action::Quit => "quit",
+        Action::Lap if session => "lap",
+        Action::Pause if session && counting => "stop",
+        Action::Pause if session => "start",
+        Action::Reset if session => "reset",
+        Action::ResetTo
+        | Action::Undo
+        | Action::DeleteLap
+        | Action::RedoLap
+        | Action::LapNote
+        | Action::Project
+            if session =>
+        {
+            app.set_error(app.lang.text(Msg::NotWhileAttached).to_string());
+            return Some(LoopControl::Continue);
+        }
+        _ => return None,
+    };
+    match daemon::send(&socket, command) {
+        Ok(reply) if reply.starts_with("error:") => app.set_error(reply),
+        Ok(reply) if action == Action::Quit => {
+            if let Some(ref mut attached) = app.attached {
+                attached.ended = Some(reply);
+            }
+            return Some(LoopControl::Quit);
+        }
+        Ok(_) => {
+            match action {
+                Action::Pause if counting => {
+                    app.set_status(app.lang.text(Msg::StatusPaused).to_string())
+                }
+                Action::Pause => app.set_status(app.lang.text(Msg::StatusResumed).to_string()),
+                Action::Reset => app.selected_lap = None,
+                _ => {}
+            }
+            // Shown straight away rather than at the next sync
+            if let Ok(state) = daemon::fetch_state(&socket) {
+                timers.timers[0].follow(state.as_ref());
+            }
+        }
+        // With the daemon gone there is nothing left to stop
+        Err(_) if action == Action::Quit => return Some(LoopControl::Quit),
+        Err(err) => app.set_error(app.lang.fill(Msg::LostDaemon, &[("error", &err)])),
+    }
+    Some(LoopControl::Continue)
+}
+
+fn perform_action(timers: &mut App, app: &mut AppState, action: Action) -> LoopControl {
+    #[cfg(unix)]
+    if let Some(control) = forward_action(timers, app, action) {
+        return control;
+    }
+    let chronometer = timers.active_mut();
+    match action {
+        Action::NewTimer => {
+            timers.add_timer();
+            app.selected_lap = None;
+            app.lap_filter = None;
+        }
+        Action::Quit => {
+            return LoopControl::Quit;
+        }
+        Action::Reset => {
+            chronometer.reset();
+            // Lap ids restart with the session, so drop the lap cursor
+            app.selected_lap = None;
+        }
+        Action::ResetTo => {
+            app.input = Some(TextInput::new(InputPurpose::ResetOffset));
+        }
+        Action::Undo => match chronometer.undo() {
+            Some(Undone::Reset) => {
+                app.selected_lap = None;
+                app.set_status(app.lang.text(Msg::ResetUndone).to_string());
+            }
+            Some(Undone::DeleteLap(lap_id)) => {
+                app.selected_lap = Some(lap_id);
+                app.set_status(app.lang.fill(Msg::LapRestored, &[("lap", &lap_id)]));
+            }
+            None => app.set_status(app.lang.text(Msg::NothingToUndo).to_string()),
+        },
+        Action::AddTimer => {
+            app.input = Some(TextInput::new(InputPurpose::AddCountdown));
+        }
+        Action::Presets if app.presets.is_empty() => {
+            app.set_error(app.lang.text(Msg::NoPresets).to_string());
+        }
+        Action::Presets => {
+            app.preset_menu = Some(0);
+        }
+        Action::Help => {
+            app.help = Some(0);
+        }
+        Action::Freeze => {
+            if chronometer.toggle_freeze() {
+                app.set_status(app.lang.fill(
+                    Msg::TimeFrozen,
+                    &[("key", &app.keymap.label(Action::Freeze))],
+                ));
+            } else if chronometer.is_started() {
+                app.set_status(app.lang.text(Msg::ShowingLive).to_string());
+            }
+        }
+        Action::Project => {
+            let project = chronometer.current_project().unwrap_or_default();
+            app.input = Some(TextInput::prefilled(
+                InputPurpose::Project,
+                project.to_string(),
+            ));
+        }
+        Action::Lock => {
+            app.locked = true;
+            app.set_status(app.lang.fill(
+                Msg::ControlsLocked,
+                &[("key", &app.keymap.label(Action::Lock))],
+            ));
+        }
+        Action::RemoveTimer => {
+            app.remove_selected_countdown();
+        }
+        Action::NextSegment => {
+            app.advance_plan(&mut timers.timers[0]);
+        }
+        Action::PrevTimer => {
+            app.selected_countdown = app.selected_countdown.saturating_sub(1);
+        }
+        Action::NextTimer if app.selected_countdown + 1 < app.countdowns.len() => {
+            app.selected_countdown += 1;
+        }
+        Action::Lap if chronometer.is_started() => {
+            chronometer.lap();
+            app.sound.lap();
+            app.set_status(app.lang.fill(
+                Msg::LapRecorded,
+                &[("lap", &chronometer.lap_durations.len())],
+            ));
+        }
+        Action::Filter => {
+            app.input = Some(TextInput::new(InputPurpose::LapFilter));
+        }
+        Action::LapNote => {
+            if let Some(lap_id) = app.selected_lap {
+                let note = chronometer.lap_notes.get(&lap_id).cloned();
+                app.input = Some(TextInput::prefilled(
+                    InputPurpose::LapNote(lap_id),
+                    note.unwrap_or_default(),
+                ));
+            }
+        }
+        // The selected lap, or the newest one while the list follows it
+        Action::DeleteLap => {
+            let Some(lap_id) = app.selected_lap.or(chronometer.lap_ids.last().copied()) else {
+                return LoopControl::Continue;
+            };
+            // The cursor moves on to the next lap shown, if there is one
+            let ids = app.visible_lap_ids(chronometer);
+            let next = ids
+                .iter()
+                .position(|&id| id == lap_id)
+                .and_then(|index| ids.get(index + 1).copied());
+            if chronometer.delete_lap(lap_id) {
+                app.selected_lap = app.selected_lap.and(next);
+                app.set_status(app.lang.fill(
+                    Msg::LapDeleted,
+                    &[("lap", &lap_id), ("key", &app.keymap.label(Action::Undo))],
+                ));
+            }
+        }
+        Action::RedoLap => {
+            chronometer.restart_from_lap();
+        }
+        Action::Precision => {
+            chronometer.cycle_precision();
+            app.set_status(app.lang.fill(
+                Msg::PrecisionSet,
+                &[(
+                    "precision",
+                    &app.lang.text(precision_name(chronometer.precision)),
+                )],
+            ));
+        }
+        Action::Chart => {
+            app.show_histogram = !app.show_histogram;
+        }
+        Action::LapStats => {
+            app.show_lap_stats = !app.show_lap_stats;
+        }
+        Action::History => match app.history {
+            Some(_) => app.history = None,
+            None => app.open_history(),
+        },
+        Action::Heatmap => {
+            app.heatmap = !app.heatmap;
+        }
+        Action::WorldClock => app.toggle_clock(),
+        Action::SplitView => {
+            app.show_splits = !app.show_splits;
+        }
+        Action::BigDigits => {
+            app.big_digits = !app.big_digits;
+        }
+        Action::Copy => {
+            let time = chronometer.display();
+            copy_to_clipboard(app, &time, &time);
+        }
+        Action::CopyLaps => match chronometer.lap_durations.len() {
+            0 => app.set_status(app.lang.text(Msg::NoLapsToCopy).to_string()),
+            laps => copy_to_clipboard(app, &export::laps_tsv(chronometer), &app.lang.laps(laps)),
+        },
+        Action::Snapshot => {
+            let line = match write_snapshot(chronometer, app) {
+                Ok(filename) => {
+                    app.set_status(
+                        app.lang
+                            .fill(Msg::SnapshotWritten, &[("path", &filename.display())]),
+                    );
+                    format!(
+                        "Snapshot {} written at: {}",
+                        filename.display(),
+                        chronometer.wall_clock()
+                    )
+                }
+                Err(err) => {
+                    app.set_error(app.lang.fill(Msg::SnapshotFailed, &[("error", &err)]));
+                    format!("Snapshot failed at: {} - {}", chronometer.wall_clock(), err)
+                }
+            };
+            chronometer.log_line(&line);
+        }
+        Action::Export => {
+            let line = match chronometer.export_laps(app.export_format) {
+                Ok(filename) => {
+                    app.set_status(
+                        app.lang
+                            .fill(Msg::Exported, &[("path", &filename.display())]),
+                    );
+                    format!(
+                        "Laps exported to {} at: {}",
+                        filename.display(),
+                        chronometer.wall_clock()
+                    )
+                }
+                Err(err) => {
+                    app.set_error(app.lang.fill(Msg::ExportFailed, &[("error", &err)]));
+                    format!("Export failed at: {} - {}", chronometer.wall_clock(), err)
+                }
+            };
+            chronometer.log_line(&line);
+        }
+        Action::Pause => match chronometer.toggle_pause() {
+            Ok(()) if chronometer.is_paused() => {
+                app.set_status(app.lang.text(Msg::StatusPaused).to_string())
+            }
+            Ok(()) => app.set_status(app.lang.text(Msg::StatusResumed).to_string()),
+            Err(err) => app.set_error(app.lang.fill(Msg::CantPause, &[("error", &err)])),
+        },
+        _ => {}
+    }
+
+    LoopControl::Continue
+}
+
+// Input error policy: interruptions and timeouts are transient (e.g. a
+// signal arriving mid-read) and the loop keeps going, up to a run of
+// MAX_INPUT_ERRORS in a row. Anything else means the terminal is gone and
+// the session ends after restoring the terminal.
+const MAX_INPUT_ERRORS: u32 = 100;
+
+fn is_recoverable(err: &io::Error) -> bool {
+    matches!(
+        err.kind(),
+        io::ErrorKind::Interrupted | io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+// Logs a transient read error and counts it against the run in a row;
+// gives back the error if it should end the session instead
+fn skip_input_error(timers: &mut App, err: io::Error, input_errors: &mut u32) -> io::Result<()> {
+    if !is_recoverable(&err) || *input_errors >= MAX_INPUT_ERRORS {
+        return Err(err);
+    }
+    *input_errors += 1;
+    let line = format!(
+        "Input error at: {} - {}",
+        timers.session().wall_clock(),
+        err
+    );
+    timers.timers[0].log_line(&line);
+    Ok(())
+}
+
+enum Input {
+    Key(KeyCode),
+    Mouse(MouseEvent),
+    Resize,
+    // Ctrl+Z
+    #[cfg(unix)]
+    Suspend,
+    // Ctrl+D, which detaches from the daemon
+    Detach,
+}
+
+fn read_input(timeout: Duration) -> io::Result<Option<Input>> {
+    if !event::poll(timeout)? {
+        return Ok(None);
+    }
+    match event::read()? {
+        #[cfg(unix)]
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('z'),
+            modifiers,
+            ..
+        }) if modifiers.contains(KeyModifiers::CONTROL) => Ok(Some(Input::Suspend)),
+        Event::Key(KeyEvent {
+            code: KeyCode::Char('d'),
+            modifiers,
+            ..
+        }) if modifiers.contains(KeyModifiers::CONTROL) => Ok(Some(Input::Detach)),
+        Event::Key(KeyEvent { code, .. }) => Ok(Some(Input::Key(code))),
+        Event::Mouse(mouse) => Ok(Some(Input::Mouse(mouse))),
+        Event::Resize(..) => Ok(Some(Input::Resize)),
+        _ => Ok(None),
+    }
+}
+
+fn line_text(line: &Line) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
+// `what` names the copied text in the status line
+fn copy_to_clipboard(app: &mut AppState, text: &str, what: &str) {
+    match clipboard::copy(text) {
+        Ok(Copied::Tool) => app.set_status(app.lang.fill(Msg::Copied, &[("what", &what)])),
+        Ok(Copied::Terminal) => {
+            app.set_status(app.lang.fill(Msg::CopiedTerminal, &[("what", &what)]))
+        }
+        Err(err) => app.set_error(app.lang.fill(Msg::CopyFailed, &[("error", &err)])),
+    }
+}
+
+// Writes what the screen shows as a plain-text report, built from the same
+// formatting helpers as the UI rather than the rendered buffer
+fn write_snapshot(chronometer: &Chronometer, app: &AppState) -> io::Result<PathBuf> {
+    let now = chrono::Utc::now();
+    let filename = chronometer.output_path(&format!(
+        "ChronoRust-{}-snapshot.txt",
+        chronometer.file_stamp(now)
+    ));
+    let started = chrono::DateTime::<chrono::Utc>::from(chronometer.start_timestamp);
+
+    let mut report = vec![
+        format!("ChronoRust v{} Snapshot", env!("CARGO_PKG_VERSION")),
+        format!("Session started: {}", chronometer.format_datetime(&started)),
+        format!(
+            "Total elapsed: {}",
+            chronometer.format_duration(chronometer.elapsed())
+        ),
+        "================================================".to_string(),
+        format!(
+            "Time: {} ({})",
+            chronometer.display(),
+            chronometer.state_label()
+        ),
+        String::new(),
+        "Stats".to_string(),
+    ];
+    for line in stats_lines(chronometer, app) {
+        report.push(format!("  {}", line_text(&line)));
+    }
+
+    report.push(String::new());
+    report.push("Lap Times".to_string());
+    let differences = chronometer.get_lap_differences();
+    for i in 0..chronometer.lap_durations.len() {
+        let spans = lap_spans(chronometer, app, &differences, i);
+        report.push(format!("  {}", line_text(&Line::from(spans))));
+        if let Some(note) = chronometer.lap_notes.get(&chronometer.lap_ids[i]) {
+            report.push(format!("      note: {}", note));
+        }
+    }
+    if chronometer.lap_durations.is_empty() {
+        report.push("  (no laps)".to_string());
+    }
+
+    let histogram = chronometer.lap_histogram(app.histogram_bucket);
+    if !histogram.is_empty() {
+        report.push(String::new());
+        report.push(format!(
+            "Laps per {}",
+            chronometer.format_duration(app.histogram_bucket)
+        ));
+        for (index, count) in histogram {
+            let from = app.histogram_bucket * index as u32;
+            report.push(format!(
+                "  {}  {:>3}  {}",
+                chronometer.format_duration(from),
+                count,
+                "#".repeat(count)
+            ));
+        }
+    }
+
+    let mut file = File::create(&filename)?;
+    for line in report {
+        writeln!(file, "{}", line)?;
+    }
+    Ok(filename)
+}
+
+// Creates the --output-dir for generated files; None, after a warning, has
+// them go to the current directory instead
+fn prepare_output_dir(dir: PathBuf) -> Option<PathBuf> {
+    match std::fs::create_dir_all(&dir) {
+        Ok(()) => Some(dir),
+        Err(err) => {
+            eprintln!(
+                "Cannot create --output-dir '{}' ({}), using the current directory",
+                dir.display(),
+                err
+            );
+            None
+        }
+    }
+}
+
+// Logs the end of the session and saves it wherever it's kept
+fn finish_session(
+    chronometer: &mut Chronometer,
+    binary_state: Option<&Path>,
+    resume_path: Option<&Path>,
+    history_dir: Option<&Path>,
+    daily_path: Option<&Path>,
+) -> io::Result<()> {
+    chronometer.log_quit();
+    let state = chronometer.session_state();
+    let failed =
+        |path: &Path, err| io::Error::other(format!("cannot save '{}': {}", path.display(), err));
+    if let Some(path) = binary_state {
+        save_state_binary(path, &state).map_err(|err| failed(path, err))?;
+    }
+    if let Some(path) = resume_path {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        save_state_json(path, &state).map_err(|err| failed(path, err))?;
+    }
+    // A session that never started isn't worth keeping
+    if let Some(dir) = history_dir.filter(|_| state.elapsed_ms > 0) {
+        history::record(dir, &state).map_err(|err| failed(dir, err))?;
+    }
+    if let Some(path) = daily_path {
+        daily::add(path, &daily::today(), chronometer.tracked())
+            .map_err(|err| failed(path, err.into()))?;
+    }
+    Ok(())
+}
+
+// `chronorust history`: the session list, or the laps of session N
+fn print_history(
+    dir: Option<&Path>,
+    session: Option<usize>,
+    chronometer: &Chronometer,
+) -> io::Result<()> {
+    let Some(dir) = dir else {
+        eprintln!("history needs HOME or XDG_DATA_HOME to find its directory");
+        std::process::exit(1);
+    };
+    let entries = history::load(dir)?;
+    match session {
+        None if entries.is_empty() => println!("No sessions in {} yet", dir.display()),
+        None => {
+            for (i, entry) in entries.iter().enumerate() {
+                println!("#{:<3} {}", i + 1, history_row(entry, chronometer));
+            }
+            // Every session's tagged time, added up per project
+            let mut totals = Vec::new();
+            for (name, time) in entries.iter().flat_map(HistoryEntry::projects) {
+                project::add(&mut totals, &name, time);
+            }
+            if !totals.is_empty() {
+                println!();
+                println!("Time per project");
+                for (name, time) in totals {
+                    println!("  {}  {}", chronometer.format_duration(time), name);
+                }
+            }
+        }
+        Some(number) => {
+            let Some(entry) = number.checked_sub(1).and_then(|i| entries.get(i)) else {
+                eprintln!("No session #{} ({} in history)", number, entries.len());
+                std::process::exit(1);
+            };
+            println!("Session {}", history_row(entry, chronometer));
+            for line in history_laps(entry, chronometer) {
+                println!("  {}", line);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn history_row(entry: &HistoryEntry, chronometer: &Chronometer) -> String {
+    let started = chrono::DateTime::<chrono::Local>::from(entry.started());
+    let mut row = format!(
+        "{}  {}  {} laps",
+        chronometer.format_datetime(&started),
+        chronometer.format_duration(entry.total()),
+        entry.state.laps_ms.len()
+    );
+    let projects = entry.projects();
+    if !projects.is_empty() {
+        row.push_str(&format!("  ({})", project_list(chronometer, &projects)));
+    }
+    row
+}
+
+fn history_laps(entry: &HistoryEntry, chronometer: &Chronometer) -> Vec<String> {
+    let mut previous = Duration::ZERO;
+    entry
+        .laps()
+        .into_iter()
+        .enumerate()
+        .map(|(i, lap)| {
+            let split = lap.saturating_sub(previous);
+            previous = lap;
+            format!(
+                "Lap {}: {} (+{})",
+                i + 1,
+                chronometer.format_duration(lap),
+                chronometer.format_duration(split)
+            )
+        })
+        .collect()
+}
+
+// Asks before picking up the saved --resume session; piped input can't
+// answer, so it resumes without asking
+fn offer_resume(chronometer: &Chronometer, path: Option<&Path>) -> Option<SessionState> {
+    let path = path.filter(|path| path.exists())?;
+    let state = match load_state_json(path) {
+        Ok(state) => state,
+        Err(err) => {
+            eprintln!("Ignoring --resume session '{}': {}", path.display(), err);
+            return None;
+        }
+    };
+    if !io::stdin().is_terminal() {
+        return Some(state);
+    }
+
+    let started = chrono::DateTime::from_timestamp_millis(state.start_timestamp_ms as i64)
+        .map_or("-".to_string(), |started| {
+            chronometer.format_datetime(&started)
+        });
+    print!(
+        "Resume the session started {} ({}{}, {} laps)? [Y/n] ",
+        started,
+        chronometer.format_duration(Duration::from_millis(state.elapsed_ms)),
+        if state.paused { ", paused" } else { "" },
+        state.laps_ms.len()
+    );
+    let _ = io::stdout().flush();
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).ok()?;
+    match answer.trim().to_lowercase().as_str() {
+        "" | "y" | "yes" => Some(state),
+        _ => None,
+    }
+}
+
+// The wall-clock account of the session printed on exit
+fn print_report(chronometer: &Chronometer, lang: Lang) {
+    let report = chronometer.report();
+    let format = |duration| chronometer.format_duration(duration);
+    // Values line up after the longest label in the language
+    let labels = [
+        Msg::ReportStarted,
+        Msg::ReportEnded,
+        Msg::ReportElapsed,
+        Msg::ReportPaused,
+        Msg::ReportLaps,
+        Msg::ReportTarget,
+        Msg::OverBy,
+        Msg::ReportProject,
+    ];
+    let width = labels
+        .iter()
+        .map(|&label| lang.text(label).chars().count() + 1)
+        .max()
+        .unwrap_or(0);
+    let line = |label: Msg, value: String| {
+        let label = format!("{}:", lang.text(label));
+        println!("  {:<width$} {}", label, value);
+    };
+    println!("{}", lang.text(Msg::Summary));
+    line(Msg::ReportStarted, chronometer.format_time(report.started));
+    line(Msg::ReportEnded, chronometer.format_time(report.ended));
+    line(Msg::ReportElapsed, format(report.elapsed));
+    let pauses = match report.pauses.len() {
+        1 => lang.text(Msg::OnePause).to_string(),
+        count => lang.fill(Msg::PauseCount, &[("count", &count)]),
+    };
+    line(
+        Msg::ReportPaused,
+        format!("{} ({})", format(report.paused), pauses),
+    );
+    for &(start, end) in &report.pauses {
+        println!(
+            "  {:width$} {} - {}",
+            "",
+            chronometer.format_time(start),
+            chronometer.format_time(end)
+        );
+    }
+    match report.stats {
+        Some(stats) => line(
+            Msg::ReportLaps,
+            lang.fill(
+                Msg::BestWorst,
+                &[
+                    ("laps", &report.laps),
+                    ("best", &format(stats.best)),
+                    ("worst", &format(stats.worst)),
+                ],
+            ),
+        ),
+        None => line(Msg::ReportLaps, report.laps.to_string()),
+    }
+    if let Some(delta) = chronometer.target_delta() {
+        let versus = lang.text(if delta.1 { Msg::Ahead } else { Msg::Behind });
+        line(
+            Msg::ReportTarget,
+            format!("{} ({})", chronometer.format_pace_delta(delta), versus),
+        );
+    }
+    if let Some(over) = chronometer.countdown_overtime() {
+        line(Msg::OverBy, format(over));
+    }
+    for (project, time) in chronometer.project_totals() {
+        line(Msg::ReportProject, format!("{} {}", project, format(time)));
+    }
+}
+
+// "client-a 01:10:00 | admin 00:20:00"
+fn project_list(chronometer: &Chronometer, totals: &[(String, Duration)]) -> String {
+    totals
+        .iter()
+        .map(|(project, time)| format!("{} {}", project, chronometer.format_duration(*time)))
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+// How every way of running ends: the --summary-format line when there is
+// one, else the report
+fn print_summary(chronometer: &Chronometer, format: Option<SummaryFormat>, lang: Lang) {
+    match format {
+        Some(format) => println!("{}", summary_line(chronometer, format)),
+        None => print_report(chronometer, lang),
+    }
+}
+
+fn summary_line(chronometer: &Chronometer, format: SummaryFormat) -> String {
+    let total = chronometer.format_duration(chronometer.elapsed());
+    let laps = chronometer.lap_durations.len();
+    let stats = chronometer.lap_stats();
+    let stat = |pick: fn(&LapStats) -> Duration| {
+        stats
+            .as_ref()
+            .map(|stats| chronometer.format_duration(pick(stats)))
+    };
+    let (best, worst, avg) = (stat(|s| s.best), stat(|s| s.worst), stat(|s| s.average));
+    let pace = chronometer
+        .running_pace_delta()
+        .map(|delta| chronometer.format_pace_delta(delta));
+    let distance = chronometer.distance_stats();
+    let pace_per_km = distance
+        .as_ref()
+        .and_then(|stats| stats.pace_per_km)
+        .map(|pace| chronometer.format_duration(pace));
+
+    match format {
+        SummaryFormat::Kv => {
+            let value = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+            let mut line = format!(
+                "total={} laps={} best={} worst={} avg={}",
+                total,
+                laps,
+                value(best),
+                value(worst),
+                value(avg)
+            );
+            if chronometer.target_pace.is_some() {
+                line.push_str(&format!(" pace={}", value(pace)));
+            }
+            if chronometer.skipped_laps() > 0 {
+                line.push_str(&format!(" excluded={}", chronometer.skipped_laps()));
+            }
+            if let Some(ref stats) = distance {
+                line.push_str(&format!(
+                    " distance_m={:.0} pace_km={}",
+                    stats.total_meters,
+                    value(pace_per_km.clone())
+                ));
+            }
+            line
+        }
+        SummaryFormat::Json => {
+            let value = |v: Option<String>| match v {
+                Some(v) => format!("\"{}\"", v),
+                None => "null".to_string(),
+            };
+            let mut extra = String::new();
+            if chronometer.target_pace.is_some() {
+                extra.push_str(&format!(",\"pace\":{}", value(pace)));
+            }
+            if chronometer.skipped_laps() > 0 {
+                extra.push_str(&format!(",\"excluded\":{}", chronometer.skipped_laps()));
+            }
+            if let Some(ref stats) = distance {
+                extra.push_str(&format!(
+                    ",\"distance_m\":{:.0},\"pace_km\":{}",
+                    stats.total_meters,
+                    value(pace_per_km.clone())
+                ));
+            }
+            format!(
+                "{{\"total\":\"{}\",\"laps\":{},\"best\":{},\"worst\":{},\"avg\":{}{}}}",
+                total,
+                laps,
+                value(best),
+                value(worst),
+                value(avg),
+                extra
+            )
+        }
+    }
+}
+
+// The line is only rewritten when it changes, checked --fps times a second
+fn run_line_mode(chronometer: &mut Chronometer, ascii: bool, fps: u32) -> io::Result<()> {
+    // Without raw mode Ctrl+C arrives as SIGINT, so catch it to restore the cursor
+    let stop = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&stop))?;
+    signal_hook::flag::register(signal_hook::consts::SIGTERM, Arc::clone(&stop))?;
+
+    let mut stdout = stdout();
+    execute!(stdout, cursor::Hide)?;
+
+    let mut last_line = String::new();
+    while !stop.load(Ordering::Relaxed) {
+        chronometer.tick();
+        let glyph = match (
+            ascii,
+            chronometer.prestart_until.is_some(),
+            chronometer.is_paused(),
+        ) {
+            (false, true, _) => "…",
+            (false, false, true) => "⏸",
+            (false, false, false) => "▶",
+            (true, true, _) => "..",
+            (true, false, true) => "||",
+            (true, false, false) => ">",
+        };
+        // Pad to overwrite leftovers from a longer previous line
+        let line = format!("\r{} {:<16}", glyph, chronometer.display());
+        if line != last_line {
+            write!(stdout, "{}", line)?;
+            stdout.flush()?;
+            last_line = line;
+        }
+        thread::sleep(Duration::from_secs(1) / fps);
+    }
+
+    execute!(stdout, cursor::Show)?;
+    writeln!(stdout)?;
+    Ok(())
+}
+
+// Runs until SIGINT/SIGTERM, taking a lap on every SIGUSR1. With an
+// interval the status is printed that often, otherwise only laps are.
+fn run_headless(chronometer: &mut Chronometer, interval: Option<Duration>) -> io::Result<()> {
+    let stop = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&stop))?;
+    signal_hook::flag::register(signal_hook::consts::SIGTERM, Arc::clone(&stop))?;
+    let lap_requested = Arc::new(AtomicBool::new(false));
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGUSR1, Arc::clone(&lap_requested))?;
+
+    let mut stdout = stdout();
+    let mut last_print = Instant::now();
+    while !stop.load(Ordering::Relaxed) {
+        thread::sleep(Duration::from_millis(50));
+        chronometer.tick();
+
+        if lap_requested.swap(false, Ordering::Relaxed) && chronometer.is_started() {
+            chronometer.lap();
+            writeln!(
+                stdout,
+                "lap {} {}",
+                chronometer.lap_ids.last().copied().unwrap_or_default(),
+                chronometer
+                    .lap_durations
+                    .last()
+                    .map_or(String::new(), |&lap| chronometer.format_duration(lap))
+            )?;
+            stdout.flush()?;
+        }
+
+        if interval.is_some_and(|interval| last_print.elapsed() >= interval) {
+            last_print = Instant::now();
+            writeln!(
+                stdout,
+                "{} {} laps={}",
+                chronometer.state_label(),
+                chronometer.display(),
+                chronometer.lap_durations.len()
+            )?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+// `chronorust attach`: the daemon's socket and its session as it stands
+fn attach_to(socket: Option<&Path>) -> (PathBuf, Option<SessionState>) {
+    let Some(socket) = socket else {
+        eprintln!("attach needs --socket, XDG_RUNTIME_DIR or HOME to find the daemon");
+        std::process::exit(1);
+    };
+    #[cfg(unix)]
+    match daemon::fetch_state(socket) {
+        Ok(state) => (socket.to_path_buf(), state),
+        Err(err)
+            if matches!(
+                err.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            eprintln!(
+                "No daemon on '{}'; start one with chronorust daemon",
+                socket.display()
+            );
+            std::process::exit(1);
+        }
+        Err(err) => {
+            eprintln!("Cannot attach to '{}': {}", socket.display(), err);
+            std::process::exit(1);
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        eprintln!("attach needs a Unix domain socket, which this system doesn't have");
+        std::process::exit(1);
+    }
+}
+
+// `chronorust ctl`: the daemon's answer on stdout, failing on its errors
+fn run_ctl(socket: Option<&Path>, command: &str) -> io::Result<()> {
+    let Some(socket) = socket else {
+        eprintln!("ctl needs --socket, XDG_RUNTIME_DIR or HOME to find the daemon");
+        std::process::exit(1);
+    };
+    #[cfg(unix)]
+    match daemon::send(socket, command) {
+        Ok(reply) => {
+            println!("{}", reply);
+            if reply.starts_with("error:") {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Err(err)
+            if matches!(
+                err.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            eprintln!(
+                "No daemon on '{}'; start one with chronorust daemon",
+                socket.display()
+            );
+            std::process::exit(1);
+        }
+        Err(err) => Err(err),
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = command;
+        eprintln!("ctl needs a Unix domain socket, which this system doesn't have");
+        std::process::exit(1);
+    }
+}
+
+fn run_stdin_mode(chronometer: &mut Chronometer) -> io::Result<()> {
+    let mut stdout = stdout();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let command = line.trim();
+        if command.is_empty() {
+            continue;
+        }
+
+        chronometer.tick();
+        match command {
+            "lap" => chronometer.lap(),
+            "pause" | "resume" => {
+                let result = match command {
+                    "pause" => chronometer.pause(),
+                    _ => chronometer.resume(),
+                };
+                if let Err(err) = result {
+                    eprintln!("Cannot {}: {}", command, err);
+                    continue;
+                }
+            }
+            "reset" => chronometer.reset(),
+            "quit" => break,
+            _ => {
+                eprintln!("Unknown command '{}'", command);
+                continue;
+            }
+        }
+
+        writeln!(
+            stdout,
+            "{} {} laps={}",
+            chronometer.state_label(),
+            chronometer.display(),
+            chronometer.lap_durations.len()
+        )?;
+        stdout.flush()?;
+    }
+
+    // The state the session ended in, as the daemon reports it
+    chronometer.tick();
+    writeln!(
+        stdout,
+        "{} {} laps={}",
+        chronometer.state_label(),
+        chronometer.display(),
+        chronometer.lap_durations.len()
+    )?;
+    Ok(())
+}
+
+struct UiLayout {
+    title: Option<Rect>,
+    time: Rect,
+    // The pomodoro or interval panel
+    phase: Option<Rect>,
+    // How far a countdown, alarm or interval workout has got
+    progress: Option<Rect>,
+    stats: Option<Rect>,
+    timers: Option<Rect>,
+    laps: Option<Rect>,
+    // One row of transient messages above the controls
+    status: Option<Rect>,
+    controls: Option<Rect>,
+}
+
+// Below this size ui() draws the compact one-line view instead
+const COMPACT_WIDTH: u16 = 40;
+const COMPACT_HEIGHT: u16 = 6;
+// From this width the laps get a column of their own on the right
+const WIDE_WIDTH: u16 = 120;
+
+// Fits the sections into the available height. The full layout needs 21
+// rows; below that the outer margin goes first, then sections are kept in
+// priority order: time display (never dropped), lap times, phase panel,
+// progress gauge, stats, timers, title, controls. The timers box only
+// exists while there are timers. On a wide terminal the laps take the full
+// height of the right half and the other sections stack in the left one.
+fn compute_layout(
+    area: Rect,
+    time_rows: u16,
+    phase_height: u16,
+    show_progress: bool,
+    stats_rows: usize,
+    timer_count: usize,
+) -> UiLayout {
+    const SECTION: u16 = 3;
+    const FULL_LAPS: u16 = 5;
+    const FULL_HEIGHT: u16 = 4 * SECTION + FULL_LAPS + 4;
+
+    let full = area.height >= FULL_HEIGHT;
+    let margin = if full { 2 } else { 0 };
+    let inner = area.inner(&Margin::new(2, margin));
+    let (column, laps_column) = if area.width >= WIDE_WIDTH {
+        let left = inner.width / 2 - 1;
+        (
+            Rect {
+                width: left,
+                ..inner
+            },
+            Some(Rect {
+                x: inner.x + left + 2,
+                width: inner.width - left - 2,
+                ..inner
+            }),
+        )
+    } else {
+        (inner, None)
+    };
+    let mut remaining = column.height;
+
+    // The time display takes whatever is left if it can't get a bordered box.
+    // Taller big digits are only given room on a full-size screen.
+    let full_column = match laps_column {
+        Some(_) => FULL_HEIGHT - FULL_LAPS,
+        None => FULL_HEIGHT,
+    };
+    let time_height = if full && time_rows > 1 && remaining >= full_column + time_rows - 1 {
+        time_rows + 2
+    } else {
+        remaining.min(SECTION)
+    };
+    remaining -= time_height;
+
+    let mut keep = |needed: u16| {
+        if remaining >= needed {
+            remaining -= needed;
+            true
+        } else {
+            false
+        }
+    };
+    let laps = laps_column.is_none() && keep(SECTION);
+    let phase = phase_height > 0 && keep(phase_height);
+    let progress = show_progress && keep(SECTION);
+    let stats_height = (stats_rows as u16).saturating_add(2);
+    let stats = keep(stats_height);
+    let timers_height = (timer_count as u16).saturating_add(2);
+    let timers = timer_count > 0 && keep(timers_height);
+    let title = keep(SECTION);
+    let controls = keep(SECTION);
+    let status = keep(1);
+
+    let mut constraints = Vec::new();
+    if title {
+        constraints.push(Constraint::Length(SECTION));
+    }
+    constraints.push(Constraint::Length(time_height));
+    if phase {
+        constraints.push(Constraint::Length(phase_height));
+    }
+    if progress {
+        constraints.push(Constraint::Length(SECTION));
+    }
+    if stats {
+        constraints.push(Constraint::Length(stats_height));
+    }
+    if timers {
+        constraints.push(Constraint::Length(timers_height));
+    }
+    if laps {
+        constraints.push(Constraint::Min(if full { FULL_LAPS } else { SECTION }));
+    }
+    if status {
+        constraints.push(Constraint::Length(1));
+    }
+    if controls {
+        constraints.push(Constraint::Length(SECTION));
+    }
+    if !laps {
+        // Absorb leftover rows so the time box keeps its size
+        constraints.push(Constraint::Min(0));
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(column);
+
+    let mut chunks = chunks.iter().copied();
+    UiLayout {
+        title: title.then(|| chunks.next()).flatten(),
+        time: chunks.next().unwrap_or_default(),
+        phase: phase.then(|| chunks.next()).flatten(),
+        progress: progress.then(|| chunks.next()).flatten(),
+        stats: stats.then(|| chunks.next()).flatten(),
+        timers: timers.then(|| chunks.next()).flatten(),
+        laps: laps_column.or(laps.then(|| chunks.next()).flatten()),
+        status: status.then(|| chunks.next()).flatten(),
+        controls: controls.then(|| chunks.next()).flatten(),
+    }
+}
+
+// Actions listed in the controls bar, labelled with their current keys
+const CONTROL_HINTS: [(Action, Msg); 22] = [
+    (Action::Help, Msg::HintHelp),
+    (Action::Reset, Msg::HintReset),
+    (Action::Lap, Msg::HintLap),
+    (Action::DeleteLap, Msg::HintDeleteLap),
+    (Action::LapNote, Msg::HintLapNote),
+    (Action::Filter, Msg::HintFilter),
+    (Action::Snapshot, Msg::HintSnapshot),
+    (Action::Export, Msg::HintExport),
+    (Action::Copy, Msg::HintCopy),
+    (Action::Heatmap, Msg::HintHeatmap),
+    (Action::WorldClock, Msg::HintClock),
+    (Action::SplitView, Msg::HintSplits),
+    (Action::Chart, Msg::HintChart),
+    (Action::LapStats, Msg::HintLapStats),
+    (Action::History, Msg::HintHistory),
+    (Action::Precision, Msg::HintPrecision),
+    (Action::ResetTo, Msg::HintResetTo),
+    (Action::Undo, Msg::HintUndo),
+    (Action::RedoLap, Msg::HintRedoLap),
+    (Action::Pause, Msg::HintPause),
+    (Action::NewTimer, Msg::HintNewTimer),
+    (Action::Quit, Msg::HintQuit),
+];
+
+// Where the last frame put what the mouse can act on
+#[derive(Default)]
+struct HitAreas {
+    buttons: Vec<(Action, Rect)>,
+    laps: Option<Rect>,
+}
+
+// Clickable buttons at the start of the controls bar
+const BUTTONS: [(Action, Msg); 4] = [
+    (Action::Lap, Msg::HintLap),
+    (Action::Pause, Msg::ButtonPause),
+    (Action::Reset, Msg::HintReset),
+    (Action::Quit, Msg::HintQuit),
+];
+
+fn ui(f: &mut Frame, timers: &App, app: &AppState) -> HitAreas {
+    let chronometer = timers.active();
+    let mut area = f.size();
+
+    // Too small for the boxes to stay readable
+    if area.width < COMPACT_WIDTH || area.height < COMPACT_HEIGHT {
+        render_compact(f, area, timers, app);
+        if let Some(ref input) = app.input {
+            render_input(f, input, app);
+        }
+        if let Some(action) = app.confirming {
+            render_confirm(f, action, timers, app);
+        }
+        return HitAreas::default();
+    }
+
+    // Tab bar once there is more than one timer
+    if timers.timers.len() > 1 && area.height > 1 {
+        render_tabs(f, Rect::new(area.x, area.y, area.width, 1), timers, app);
+        area = Rect::new(area.x, area.y + 1, area.width, area.height - 1);
+    }
+
+    let stats_rows = stats_lines(chronometer, app).len();
+    // Big digits need enough width for the whole time
+    let big_time = app
+        .big_digits
+        .then(|| app.big_text(&chronometer.display()))
+        .flatten()
+        .filter(|rows| rows[0].chars().count() as u16 + 6 <= area.width);
+    let time_rows = big_time.as_ref().map_or(1, |_| BIG_HEIGHT as u16);
+    let layout = compute_layout(
+        area,
+        time_rows,
+        app.phase_height(),
+        app.progress(chronometer).is_some(),
+        stats_rows,
+        app.countdowns.len(),
+    );
+
+    // Title
+    if let Some(area) = layout.title {
+        let mut title = app
+            .lang
+            .fill(Msg::Title, &[("version", &env!("CARGO_PKG_VERSION"))]);
+        if app.locked {
+            title.push_str(&format!(" - {}", glyphs(app.ascii).locked));
+        }
+        let title = Paragraph::new(title)
+            .style(app.fg(app.theme.title).add_modifier(Modifier::BOLD))
+            .alignment(Alignment::Center)
+            .block(if app.beat_flash(chronometer) {
+                app.block()
+                    .border_style(app.fg(app.theme.title).add_modifier(Modifier::REVERSED))
+            } else {
+                app.block()
+            });
+        f.render_widget(title, area);
+    }
+
+    // Time display
+    let (marker, suffix) = time_marker(chronometer, app);
+    let time_color = time_color(chronometer, app);
+
+    // The state marker moves into the title when the digits fill the box,
+    // which in the wide layout is only half the screen across
+    let big_time = big_time.filter(|rows| {
+        let width = rows[0].chars().count() as u16;
+        layout.time.height >= time_rows + 2 && width + 2 <= layout.time.width
+    });
+    // Ahead of --target in green, behind in red, next to the time or, with
+    // big digits, in the title
+    let target = chronometer
+        .target_delta()
+        .map(|delta| (chronometer.format_pace_delta(delta), delta.1));
+    let (mut time_title, time_lines) = match big_time {
+        Some(rows) => {
+            let mut title = format!(
+                "{} {}{}",
+                app.lang.text(Msg::Time),
+                marker.trim_end(),
+                suffix
+            );
+            if let Some((ref delta, _)) = target {
+                let delta = app.lang.fill(Msg::TargetDelta, &[("delta", delta)]);
+                title.push_str(&format!(" - {}", delta));
+            }
+            (title, rows.into_iter().map(Line::from).collect())
+        }
+        None => {
+            let mut spans = vec![Span::raw(format!(
+                "{}{}{}",
+                marker,
+                chronometer.display(),
+                suffix
+            ))];
+            if let Some((delta, ahead)) = target {
+                spans.push(Span::raw("  "));
+                spans.push(Span::styled(delta, pace_style(app, ahead)));
+            }
+            (
+                app.lang.text(Msg::Time).to_string(),
+                vec![Line::from(spans)],
+            )
+        }
+    };
+
+    if chronometer.read_only {
+        time_title.push_str(&format!(" - {}", app.lang.text(Msg::ReadOnly)));
+    }
+    if let Some(project) = chronometer.current_project() {
+        let project = app.lang.fill(Msg::ProjectTag, &[("project", &project)]);
+        time_title.push_str(&format!(" - {}", project));
+    }
+    if let Some(ref alarm) = app.alarm {
+        let alarm = app.lang.fill(Msg::AlarmAt, &[("time", alarm)]);
+        time_title.push_str(&format!(" - {}", alarm));
+    }
+    if let Some(ref command) = app.command {
+        time_title.push_str(&format!(
+            " - {}: {}",
+            command.command_line,
+            command_state(command, app.lang)
+        ));
+    }
+
+    let mut time_style = app.fg(time_color).add_modifier(Modifier::BOLD);
+    if app.time_flash(chronometer) {
+        time_style = time_style.add_modifier(Modifier::REVERSED);
+    }
+    let mut time_paragraph = Paragraph::new(time_lines)
+        .style(time_style)
+        .alignment(Alignment::Center);
+    // Too short for a border: show the bare time line instead. Messages go
+    // in the title when there is no room for the status bar.
+    if layout.time.height >= 3 {
+        let title = match app.current_status().filter(|_| layout.status.is_none()) {
+            Some(status) => format!("{} - {}", time_title, status),
+            None => time_title,
+        };
+        time_paragraph = time_paragraph.block(app.block().title(title));
+    }
+    f.render_widget(time_paragraph, layout.time);
+
+    // Pomodoro phase
+    if let (Some(area), Some(pomodoro)) = (layout.phase, app.pomodoro.as_ref()) {
+        render_pomodoro(f, area, pomodoro, app);
+    }
+    if let (Some(area), Some(interval)) = (layout.phase, app.interval.as_ref()) {
+        render_interval(f, area, interval, app);
+    }
+    if let (Some(area), Some(plan)) = (layout.phase, app.plan.as_ref()) {
+        render_plan(f, area, plan, app);
+    }
+    if let (Some(area), Some(sequence)) = (layout.phase, app.sequence.as_ref()) {
+        render_sequence(f, area, sequence, app);
+    }
+
+    // Countdown, alarm or workout progress
+    if let (Some(area), Some(progress)) = (layout.progress, app.progress(chronometer)) {
+        render_progress(f, area, progress, app);
+    }
+
+    // Stats
+    if let Some(area) = layout.stats {
+        render_stats(f, area, chronometer, app);
+    }
+
+    // Egg timers
+    if let Some(area) = layout.timers {
+        render_countdowns(f, area, app);
+    }
+
+    // Lap times with differences
+    if let Some(area) = layout.laps {
+        #[cfg(feature = "zones")]
+        let area = render_zones(f, area, chronometer, app);
+        let area = render_histogram(f, area, chronometer, app);
+        match app.history {
+            Some(ref view) => render_history(f, area, view, chronometer, app),
+            None => {
+                let area = render_lap_stats(f, area, chronometer, app);
+                render_laps(f, area, chronometer, app);
+            }
+        }
+    }
+
+    if let Some(area) = layout.status {
+        render_status_bar(f, area, app);
+    }
+
+    // Controls
+    let buttons = match layout.controls {
+        Some(area) => render_controls(f, area, app),
+        None => Vec::new(),
+    };
+
+    // Text prompt, preset menu and key help on top of everything else
+    if let Some(selected) = app.preset_menu {
+        render_preset_menu(f, selected, app);
+    }
+    if let Some(offset) = app.help {
+        render_help(f, offset, app);
+    }
+    if let Some(ref input) = app.input {
+        render_input(f, input, app);
+    }
+    if let Some(action) = app.confirming {
+        render_confirm(f, action, timers, app);
+    }
+
+    HitAreas {
+        buttons,
+        laps: layout.laps,
+    }
+}
+
+// Live messages newest first, as many as fit
+fn render_status_bar(f: &mut Frame, area: Rect, app: &AppState) {
+    let mut spans = Vec::new();
+    for message in app.live_messages() {
+        if !spans.is_empty() {
+            spans.push(Span::styled(" | ", app.fg(app.theme.dim)));
+        }
+        let color = match message {
+            message if message.is_fading() => app.theme.dim,
+            message if message.error => app.theme.alert,
+            _ => app.theme.text,
+        };
+        spans.push(Span::styled(message.text.clone(), app.fg(color)));
+    }
+    f.render_widget(
+        Paragraph::new(Line::from(spans)).alignment(Alignment::Center),
+        area,
+    );
+}
+
+// Draws the key hints, after the mouse buttons when the mouse is captured,
+// and returns where the buttons went
+fn render_controls(f: &mut Frame, area: Rect, app: &AppState) -> Vec<(Action, Rect)> {
+    let block = app.block().title(app.lang.text(Msg::Controls));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.height == 0 {
+        return Vec::new();
+    }
+
+    let mut buttons = Vec::new();
+    let mut x = inner.x;
+    if app.mouse {
+        let style = app.fg(app.theme.accent).add_modifier(Modifier::REVERSED);
+        for &(action, label) in BUTTONS.iter() {
+            let label = app.lang.text(label);
+            let width = label.chars().count() as u16 + 2;
+            if x + width > inner.right() {
+                break;
+            }
+            let button = Rect::new(x, inner.y, width, 1);
+            f.render_widget(Paragraph::new(format!(" {} ", label)).style(style), button);
+            buttons.push((action, button));
+            x += width + 1;
+        }
+    }
+
+    let controls: Vec<String> = CONTROL_HINTS
+        .iter()
+        .map(|&(action, hint)| format!("{} - {}", app.keymap.label(action), app.lang.text(hint)))
+        .collect();
+    let controls_text = app
+        .lang
+        .fill(Msg::ControlsList, &[("keys", &controls.join(" | "))]);
+    let controls_paragraph = Paragraph::new(controls_text)
+        .style(app.fg(app.theme.muted))
+        .alignment(Alignment::Center);
+    let hints = Rect {
+        x,
+        width: inner.right() - x,
+        ..inner
+    };
+    f.render_widget(controls_paragraph, hints);
+    buttons
+}
+
+// State glyph in front of the time and any note after it
+fn time_marker(chronometer: &Chronometer, app: &AppState) -> (&'static str, &'static str) {
+    let glyphs = glyphs(app.ascii);
+    let (marker, note) = if chronometer.prestart_until.is_some() {
+        (glyphs.prestart, "")
+    } else if chronometer.auto_paused {
+        (glyphs.paused, app.lang.text(Msg::TimeIdle))
+    } else if chronometer.is_paused() {
+        (glyphs.paused, "")
+    } else {
+        (glyphs.running, "")
+    };
+    // The clock goes on underneath a frozen time
+    match chronometer.frozen {
+        Some(_) => (marker, app.lang.text(Msg::TimeFrozenNote)),
+        None => (marker, note),
+    }
+}
+
+fn time_color(chronometer: &Chronometer, app: &AppState) -> Color {
+    let final_seconds = chronometer
+        .countdown_remaining()
+        .is_some_and(|remaining| remaining <= Duration::from_secs(10));
+    if chronometer.prestart_until.is_some() {
+        app.theme.prestart
+    } else if final_seconds {
+        app.theme.alert
+    } else if chronometer.is_paused() {
+        app.theme.paused
+    } else {
+        app.theme.running
+    }
+}
+
+// The whole screen as one line in the middle: the time, then as many of
+// the tab, lap count and last split, phase and status as fit the width
+fn render_compact(f: &mut Frame, area: Rect, timers: &App, app: &AppState) {
+    let chronometer = timers.active();
+    let (marker, suffix) = time_marker(chronometer, app);
+    let mut text = format!("{}{}{}", marker, chronometer.display(), suffix);
+
+    let mut extras = Vec::new();
+    if app.locked {
+        extras.push(glyphs(app.ascii).locked.to_string());
+    }
+    if timers.timers.len() > 1 {
+        extras.push(app.lang.fill(
+            Msg::TabOf,
+            &[
+                ("index", &(timers.active + 1)),
+                ("count", &timers.timers.len()),
+            ],
+        ));
+    }
+    if let Some(project) = chronometer.current_project() {
+        extras.push(project.to_string());
+    }
+    if let Some(&last) = chronometer.lap_splits().last() {
+        extras.push(app.lang.fill(
+            Msg::LapShort,
+            &[
+                ("number", &chronometer.lap_durations.len()),
+                ("time", &chronometer.format_duration(last)),
+            ],
+        ));
+    }
+    if let Some(ref pomodoro) = app.pomodoro {
+        extras.push(app.lang.text(pomodoro_phase(pomodoro.phase)).to_string());
+    }
+    if let Some(ref interval) = app.interval {
+        extras.push(format!(
+            "{} {}/{}",
+            app.lang.text(interval_phase(interval.phase)),
+            interval.round,
+            interval.rounds
+        ));
+    }
+    if let Some(ref plan) = app.plan {
+        extras.push(match plan.current() {
+            Some((name, _)) => format!("{} {}/{}", name, plan.index + 1, plan.segments.len()),
+            None => app.lang.text(Msg::PlanDone).to_string(),
+        });
+    }
+    if let Some(ref sequence) = app.sequence {
+        if !sequence.is_done() {
+            extras.push(app.lang.fill(
+                Msg::SegmentOf,
+                &[
+                    ("index", &(sequence.index + 1)),
+                    ("count", &sequence.segments.len()),
+                ],
+            ));
+        }
+    }
+    if let Some(ref alarm) = app.alarm {
+        extras.push(app.lang.fill(Msg::AlarmAt, &[("time", alarm)]));
+    }
+    if let Some(ref command) = app.command {
+        extras.push(command_state(command, app.lang));
+    }
+    if let Some(status) = app.current_status() {
+        extras.push(status.to_string());
+    }
+    for extra in extras {
+        let longer = format!("{} | {}", text, extra);
+        if Line::from(longer.as_str()).width() > area.width as usize {
+            break;
+        }
+        text = longer;
+    }
+
+    let row = Rect {
+        y: area.y + area.height / 2,
+        height: area.height.min(1),
+        ..area
+    };
+    let mut style = app
+        .fg(time_color(chronometer, app))
+        .add_modifier(Modifier::BOLD);
+    if app.time_flash(chronometer) {
+        style = style.add_modifier(Modifier::REVERSED);
+    }
+    let paragraph = Paragraph::new(text)
+        .style(style)
+        .alignment(Alignment::Center);
+    f.render_widget(paragraph, row);
+}
+
+fn render_tabs(f: &mut Frame, area: Rect, timers: &App, app: &AppState) {
+    let titles: Vec<Line> = timers
+        .timers
+        .iter()
+        .enumerate()
+        .map(|(i, chronometer)| Line::from(format!("{} {}", i + 1, chronometer.display())))
+        .collect();
+    let tabs = Tabs::new(titles)
+        .select(timers.active)
+        .style(app.fg(app.theme.muted))
+        .highlight_style(app.fg(app.theme.accent).add_modifier(Modifier::REVERSED))
+        .divider(if app.ascii {
+            "|"
+        } else {
+            symbols::line::VERTICAL
+        });
+    f.render_widget(tabs, area.inner(&Margin::new(2, 0)));
+}
+
+fn render_input(f: &mut Frame, input: &TextInput, app: &AppState) {
+    let area = f.size();
+    let width = area.width.min(50);
+    let height = area.height.min(4);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let mut lines = vec![Line::from(format!("> {}_", input.buffer))];
+    if let Some(ref err) = input.error {
+        lines.push(Line::styled(err.clone(), app.fg(app.theme.alert)));
+    }
+    let paragraph = Paragraph::new(lines)
+        .style(app.fg(app.theme.text))
+        .block(app.block().title(input.purpose.prompt(app.lang)));
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
+
+fn render_confirm(f: &mut Frame, action: Action, timers: &App, app: &AppState) {
+    let area = f.size();
+    let width = area.width.min(50);
+    let height = area.height.min(4);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let lang = app.lang;
+    let laps = lang.laps(at_risk(timers, action));
+    let (title, question) = match action {
+        Action::Reset => (Msg::ResetTitle, Msg::ResetQuestion),
+        _ => (Msg::QuitTitle, Msg::QuitQuestion),
+    };
+    let lines = vec![
+        Line::from(lang.fill(question, &[("laps", &laps)])),
+        Line::styled(lang.text(Msg::ConfirmKeys), app.fg(app.theme.muted)),
+    ];
+    let paragraph = Paragraph::new(lines).style(app.fg(app.theme.text)).block(
+        app.block()
+            .title(lang.text(title))
+            .border_style(app.fg(app.theme.alert)),
+    );
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
+
+// A key in the help popup: a remappable action, that action with Shift, or
+// one of the keys that can't be remapped
+enum HelpKey {
+    Action(Action),
+    Shift(Action),
+    Fixed(&'static str),
+}
+
+// Every key, grouped the way the README lists them
+const HELP: [(Msg, &[(HelpKey, Msg)]); 6] = [
+    (
+        Msg::HelpTiming,
+        &[
+            (HelpKey::Action(Action::Pause), Msg::KeyPause),
+            (HelpKey::Action(Action::Freeze), Msg::KeyFreeze),
+            (HelpKey::Action(Action::Reset), Msg::KeyReset),
+            (HelpKey::Action(Action::ResetTo), Msg::KeyResetTo),
+            (HelpKey::Action(Action::Undo), Msg::KeyUndo),
+            (HelpKey::Action(Action::NextSegment), Msg::KeyNextSegment),
+            (HelpKey::Action(Action::Project), Msg::KeyProject),
+            (HelpKey::Action(Action::Lock), Msg::KeyLock),
+            (HelpKey::Fixed("Ctrl+Z"), Msg::KeySuspend),
+            (HelpKey::Fixed("Ctrl+D"), Msg::KeyDetach),
+            (HelpKey::Action(Action::Quit), Msg::KeyQuit),
+        ],
+    ),
+    (
+        Msg::HelpLaps,
+        &[
+            (HelpKey::Action(Action::Lap), Msg::KeyLap),
+            (HelpKey::Action(Action::DeleteLap), Msg::KeyDeleteLap),
+            (HelpKey::Action(Action::RedoLap), Msg::KeyRedoLap),
+            (HelpKey::Fixed("Up / Down"), Msg::KeySelectLap),
+            (HelpKey::Fixed("PgUp / PgDn"), Msg::KeyPageLaps),
+            (HelpKey::Fixed("Home / End"), Msg::KeyFirstLap),
+            (HelpKey::Action(Action::LapNote), Msg::KeyLapNote),
+            (HelpKey::Action(Action::Filter), Msg::KeyFilter),
+        ],
+    ),
+    (
+        Msg::HelpTimers,
+        &[
+            (HelpKey::Action(Action::NewTimer), Msg::KeyNewTab),
+            (HelpKey::Fixed("Tab / Shift+Tab"), Msg::KeySwitchTab),
+            (HelpKey::Action(Action::AddTimer), Msg::KeyAddTimer),
+            (HelpKey::Shift(Action::Precision), Msg::KeyPreset),
+            (HelpKey::Action(Action::PrevTimer), Msg::KeyPrevTimer),
+            (HelpKey::Action(Action::NextTimer), Msg::KeyNextTimer),
+            (HelpKey::Action(Action::RemoveTimer), Msg::KeyRemoveTimer),
+        ],
+    ),
+    (
+        Msg::HelpView,
+        &[
+            (HelpKey::Action(Action::Precision), Msg::KeyPrecision),
+            (HelpKey::Action(Action::BigDigits), Msg::KeyBigDigits),
+            (HelpKey::Action(Action::SplitView), Msg::KeySplitView),
+            (HelpKey::Action(Action::Heatmap), Msg::KeyHeatmap),
+            (HelpKey::Action(Action::LapStats), Msg::KeyLapStats),
+            (HelpKey::Action(Action::Chart), Msg::KeyChart),
+            (HelpKey::Action(Action::WorldClock), Msg::KeyWorldClock),
+            (HelpKey::Action(Action::History), Msg::KeyHistory),
+        ],
+    ),
+    (
+        Msg::HelpExport,
+        &[
+            (HelpKey::Action(Action::Export), Msg::KeyExport),
+            (HelpKey::Action(Action::Snapshot), Msg::KeySnapshot),
+            (HelpKey::Action(Action::Copy), Msg::KeyCopy),
+            (HelpKey::Shift(Action::Copy), Msg::KeyCopyLaps),
+        ],
+    ),
+    (
+        Msg::HelpHelp,
+        &[
+            (HelpKey::Action(Action::Help), Msg::KeyHelp),
+            (HelpKey::Fixed("Up / Down"), Msg::KeyScrollHelp),
+            (HelpKey::Fixed("Esc"), Msg::KeyCloseHelp),
+        ],
+    ),
+];
+
+fn help_lines(app: &AppState) -> Vec<Line<'static>> {
+    let label = |key: &HelpKey| match *key {
+        HelpKey::Action(action) => app.keymap.label(action),
+        HelpKey::Shift(action) => format!("Shift+{}", app.keymap.label(action)),
+        HelpKey::Fixed(label) => label.to_string(),
+    };
+    let mut lines = Vec::new();
+    for (index, (section, keys)) in HELP.iter().enumerate() {
+        if index > 0 {
+            lines.push(Line::from(""));
+        }
+        lines.push(Line::styled(
+            app.lang.text(*section),
+            app.fg(app.theme.accent).add_modifier(Modifier::BOLD),
+        ));
+        for (key, description) in keys.iter() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {:<16}", label(key)), app.fg(app.theme.title)),
+                Span::raw(app.lang.text(*description)),
+            ]));
+        }
+    }
+    lines
+}
+
+fn render_help(f: &mut Frame, offset: u16, app: &AppState) {
+    let lines = help_lines(app);
+    let area = f.size();
+    let width = area.width.min(60);
+    let height = area.height.min(lines.len() as u16 + 2);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    // Scrolling stops with the last line at the bottom
+    let offset = offset.min((lines.len() as u16 + 2).saturating_sub(height));
+    let help = Paragraph::new(lines)
+        .style(app.fg(app.theme.text))
+        .scroll((offset, 0))
+        .block(app.block().title(app.lang.text(Msg::KeysTitle)));
+    f.render_widget(Clear, popup);
+    f.render_widget(help, popup);
+}
+
+fn render_preset_menu(f: &mut Frame, selected: usize, app: &AppState) {
+    let area = f.size();
+    let width = area.width.min(40);
+    let height = area.height.min(app.presets.len() as u16 + 2);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let name_width = app
+        .presets
+        .iter()
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let items: Vec<ListItem> = app
+        .presets
+        .iter()
+        .enumerate()
+        .map(|(index, (name, duration))| {
+            let number = if index < 9 {
+                format!("{} ", index + 1)
+            } else {
+                "  ".to_string()
+            };
+            ListItem::new(format!(
+                "{}{:width$}  {}",
+                number,
+                name,
+                format_seconds(*duration),
+                width = name_width
+            ))
+        })
+        .collect();
+    let list = List::new(items)
+        .style(app.fg(app.theme.text))
+        .highlight_style(app.fg(app.theme.accent).add_modifier(Modifier::REVERSED))
+        .block(app.block().title(app.lang.text(Msg::PresetsTitle)));
+    let mut state = ListState::default().with_selected(Some(selected));
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut state);
+}
+
+fn stats_lines(chronometer: &Chronometer, app: &AppState) -> Vec<Line<'static>> {
+    let unit = app.rate_unit;
+    let format_rate = |rate: Option<f64>| match rate {
+        Some(rate) => format!("{:.2} {}", rate, app.lang.text(rate_unit_name(unit))),
+        None => "--".to_string(),
+    };
+    let lang = app.lang;
+    let mut spans = vec![Span::raw(lang.fill(
+        Msg::RateLine,
+        &[
+            ("rate", &format_rate(chronometer.lap_rate(unit))),
+            ("last", &format_rate(chronometer.last_lap_rate(unit))),
+        ],
+    ))];
+
+    // Cumulative time saved or lost against the target pace
+    if chronometer.target_pace.is_some() {
+        spans.push(Span::raw(format!(" | {}: ", lang.text(Msg::Pace))));
+        spans.push(match chronometer.running_pace_delta() {
+            Some(delta) => Span::styled(
+                chronometer.format_pace_delta(delta),
+                pace_style(app, delta.1).add_modifier(Modifier::BOLD),
+            ),
+            None => Span::raw("--"),
+        });
+    }
+
+    let mut lines = vec![Line::from(spans)];
+
+    // Distance-derived metrics for a known lap length
+    if let Some(stats) = chronometer.distance_stats() {
+        let speed = stats
+            .speed_kmh
+            .map_or("--".to_string(), |speed| format!("{:.2} km/h", speed));
+        let pace = stats
+            .pace_per_km
+            .map_or("--".to_string(), |pace| chronometer.format_duration(pace));
+        lines.push(Line::from(lang.fill(
+            Msg::DistanceLine,
+            &[
+                ("distance", &format_distance(stats.total_meters)),
+                ("speed", &speed),
+                ("pace", &pace),
+            ],
+        )));
+    }
+
+    // Pause allowance, flashing once it's nearly used up
+    if let Some(remaining) = chronometer.pause_remaining() {
+        let text = lang.fill(
+            Msg::PauseLeft,
+            &[("time", &chronometer.format_duration(remaining))],
+        );
+        lines.push(if chronometer.pause_budget_low() {
+            // Running or paused, one of the two clocks is always advancing
+            let session = chronometer.elapsed() + chronometer.paused_total();
+            let flash = (session.as_millis() / 500).is_multiple_of(2);
+            let mut style = app.fg(app.theme.alert).add_modifier(Modifier::BOLD);
+            if flash {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            Line::styled(text, style)
+        } else {
+            Line::from(text)
+        });
+    }
+
+    // Projection towards a target total time
+    if let Some(target) = app.project_target {
+        let projection = chronometer.project(target);
+        let mut text = if projection.past_target {
+            lang.fill(
+                Msg::TargetPassed,
+                &[("target", &chronometer.format_duration(target))],
+            )
+        } else {
+            let laps = projection
+                .laps_remaining
+                .map_or("--".to_string(), |laps| laps.to_string());
+            lang.fill(
+                Msg::ToTarget,
+                &[
+                    (
+                        "remaining",
+                        &chronometer.format_duration(projection.remaining),
+                    ),
+                    ("laps", &laps),
+                ],
+            )
+        };
+        if let Some(pace) = projection.required_pace {
+            text.push_str(&lang.fill(
+                Msg::NeedPace,
+                &[("pace", &chronometer.format_duration(pace))],
+            ));
+        }
+        lines.push(Line::from(text));
+    }
+
+    // Time under each --tag project this session
+    if chronometer.has_projects() {
+        let list = project_list(chronometer, &chronometer.project_totals());
+        lines.push(Line::from(lang.fill(Msg::Projects, &[("list", &list)])));
+    }
+
+    // Everything tracked today, this run's resets included
+    if let Some(today) = app.today {
+        lines.push(Line::from(
+            lang.fill(Msg::Today, &[("time", &format_seconds(today))]),
+        ));
+    }
+
+    lines
+}
+
+fn render_stats(f: &mut Frame, area: Rect, chronometer: &Chronometer, app: &AppState) {
+    let stats_paragraph = Paragraph::new(stats_lines(chronometer, app))
+        .style(app.fg(app.theme.stats))
+        .alignment(Alignment::Center)
+        .block(app.block().title(app.lang.text(Msg::Stats)));
+    f.render_widget(stats_paragraph, area);
+}
+
+fn pomodoro_phase(phase: Phase) -> Msg {
+    match phase {
+        Phase::Work => Msg::Work,
+        Phase::Break => Msg::Break,
+    }
+}
+
+fn precision_name(precision: Precision) -> Msg {
+    match precision {
+        Precision::Seconds => Msg::PrecisionSeconds,
+        Precision::Millis => Msg::PrecisionMillis,
+        Precision::Micro => Msg::PrecisionMicros,
+    }
+}
+
+fn rate_unit_name(unit: RateUnit) -> Msg {
+    match unit {
+        RateUnit::PerMinute => Msg::LapsPerMinute,
+        RateUnit::PerHour => Msg::LapsPerHour,
+    }
+}
+
+// The command's state as the time title and the exit line show it; the log
+// keeps process::exit_label's English
+fn command_state(command: &RunningCommand, lang: Lang) -> String {
+    let Some(status) = command.status else {
+        return lang.text(Msg::CommandRunning).to_string();
+    };
+    if let Some(code) = status.code() {
+        return lang.fill(Msg::CommandExit, &[("code", &code)]);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return lang.fill(Msg::CommandKilled, &[("signal", &signal)]);
+        }
+    }
+    lang.text(Msg::CommandTerminated).to_string()
+}
+
+// Shouted, as the interval box shows it in big letters
+fn interval_phase(phase: IntervalPhase) -> Msg {
+    match phase {
+        IntervalPhase::Work => Msg::WorkCaps,
+        IntervalPhase::Rest => Msg::RestCaps,
+        IntervalPhase::Done => Msg::DoneCaps,
+    }
+}
+
+// "05:00", minutes and seconds as the phase gauges show them
+fn minutes(secs: u64) -> String {
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+// Work phases count from 1; a break belongs to the work phase before it
+fn pomodoro_cycle(pomodoro: &Pomodoro) -> u32 {
+    match pomodoro.phase {
+        Phase::Work => pomodoro.cycles + 1,
+        Phase::Break => pomodoro.cycles,
+    }
+}
+
+fn render_pomodoro(f: &mut Frame, area: Rect, pomodoro: &Pomodoro, app: &AppState) {
+    let color = match pomodoro.phase {
+        Phase::Work => app.theme.alert,
+        Phase::Break => app.theme.good,
+    };
+    // Round up so a phase never shows zero before it changes
+    let secs = pomodoro.remaining().as_millis().div_ceil(1_000) as u64;
+    let lang = app.lang;
+    let label = lang.fill(
+        Msg::PomodoroLabel,
+        &[
+            ("phase", &lang.text(pomodoro_phase(pomodoro.phase))),
+            ("cycle", &pomodoro_cycle(pomodoro)),
+            ("time", &minutes(secs)),
+        ],
+    );
+    let gauge = Gauge::default()
+        .block(app.block().title(lang.text(Msg::PomodoroTitle)))
+        .gauge_style(app.fg(color))
+        .ratio(pomodoro.progress())
+        .label(label)
+        .use_unicode(!app.ascii);
+    f.render_widget(gauge, area);
+}
+
+fn render_interval(f: &mut Frame, area: Rect, interval: &Interval, app: &AppState) {
+    let color = match interval.phase {
+        IntervalPhase::Work => app.theme.alert,
+        IntervalPhase::Rest => app.theme.good,
+        IntervalPhase::Done => app.theme.accent,
+    };
+    // Round up so a phase never shows zero before it changes
+    let secs = interval.remaining().as_millis().div_ceil(1_000) as u64;
+    let lang = app.lang;
+    let title = lang.fill(
+        Msg::IntervalTitle,
+        &[("round", &interval.round), ("rounds", &interval.rounds)],
+    );
+    let block = app.block().title(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.height == 0 {
+        return;
+    }
+
+    // The phase name in big letters when there's room, above the gauge
+    let phase = lang.text(interval_phase(interval.phase));
+    let name_rows: Vec<Line> = match app.big_text(phase) {
+        Some(rows) if inner.height > BIG_HEIGHT as u16 => {
+            rows.into_iter().map(Line::from).collect()
+        }
+        _ => vec![Line::from(phase)],
+    };
+    let name_height = (name_rows.len() as u16).min(inner.height - 1);
+    let name = Paragraph::new(name_rows)
+        .style(app.fg(color).add_modifier(Modifier::BOLD))
+        .alignment(Alignment::Center);
+    f.render_widget(name, Rect::new(inner.x, inner.y, inner.width, name_height));
+
+    let label = match interval.phase {
+        IntervalPhase::Done => {
+            let total = interval.total().as_secs();
+            lang.fill(
+                Msg::RoundsIn,
+                &[("rounds", &interval.rounds), ("time", &minutes(total))],
+            )
+        }
+        _ => lang.fill(Msg::TimeLeft, &[("time", &minutes(secs))]),
+    };
+    let gauge = Gauge::default()
+        .gauge_style(app.fg(color))
+        .ratio(interval.progress())
+        .label(label)
+        .use_unicode(!app.ascii);
+    f.render_widget(
+        gauge,
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+    );
+}
+
+fn render_plan(f: &mut Frame, area: Rect, plan: &Plan, app: &AppState) {
+    let lang = app.lang;
+    let mut title = lang.text(Msg::PlanTitle).to_string();
+    if let Some(ref name) = plan.title {
+        title.push_str(&format!(" - {}", name));
+    }
+    if !plan.is_done() {
+        let segment = lang.fill(
+            Msg::SegmentOf,
+            &[
+                ("index", &(plan.index + 1)),
+                ("count", &plan.segments.len()),
+            ],
+        );
+        title.push_str(&format!(" - {}", segment));
+        if !plan.auto_advance {
+            let key = app.keymap.label(Action::NextSegment);
+            title.push_str(&format!(
+                " {}",
+                lang.fill(Msg::KeyForNext, &[("key", &key)])
+            ));
+        }
+    }
+    let block = app.block().title(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.height == 0 {
+        return;
+    }
+
+    // The segment under way, then what comes after it
+    let mut spans = vec![Span::styled(
+        plan.current()
+            .map_or(lang.text(Msg::DoneCaps).to_string(), |(name, _)| {
+                name.clone()
+            }),
+        app.fg(app.theme.accent).add_modifier(Modifier::BOLD),
+    )];
+    if let Some((name, length)) = plan.next() {
+        spans.push(Span::styled(
+            format!(
+                "  {}",
+                lang.fill(
+                    Msg::NextUp,
+                    &[("name", name), ("length", &format_seconds(*length))],
+                )
+            ),
+            app.fg(app.theme.muted),
+        ));
+    }
+    f.render_widget(
+        Paragraph::new(Line::from(spans)).alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y, inner.width, 1),
+    );
+    if inner.height < 2 {
+        return;
+    }
+
+    // Round up so a segment never shows zero before it ends
+    let secs = plan.remaining().as_millis().div_ceil(1_000) as u64;
+    let over = plan.overtime();
+    let (label, color) = if plan.is_done() {
+        let total = plan.total().as_secs();
+        (
+            lang.fill(
+                Msg::SegmentsPlanned,
+                &[("count", &plan.segments.len()), ("time", &minutes(total))],
+            ),
+            app.theme.accent,
+        )
+    } else if !over.is_zero() {
+        let over = over.as_millis().div_ceil(1_000) as u64;
+        (
+            lang.fill(Msg::TimeOver, &[("time", &minutes(over))]),
+            app.theme.alert,
+        )
+    } else {
+        (
+            lang.fill(Msg::TimeLeft, &[("time", &minutes(secs))]),
+            app.theme.good,
+        )
+    };
+    let gauge = Gauge::default()
+        .gauge_style(app.fg(color))
+        .ratio(plan.progress())
+        .label(label)
+        .use_unicode(!app.ascii);
+    f.render_widget(
+        gauge,
+        Rect::new(inner.x, inner.y + inner.height - 1, inner.width, 1),
+    );
+}
+
+// The segments in a row with the current one picked out, and a gauge for
+// the time it has left; the time line above counts down the whole sequence
+fn render_sequence(f: &mut Frame, area: Rect, sequence: &Sequence, app: &AppState) {
+    let lang = app.lang;
+    let mut title = lang.text(Msg::SequenceTitle).to_string();
+    if !sequence.is_done() {
+        let segment = lang.fill(
+            Msg::SegmentOf,
+            &[
+                ("index", &(sequence.index + 1)),
+                ("count", &sequence.segments.len()),
+            ],
+        );
+        title.push_str(&format!(" - {}", segment));
+    }
+    let block = app.block().title(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+    if inner.height == 0 {
+        return;
+    }
+
+    let mut spans = Vec::new();
+    for (index, &length) in sequence.segments.iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::styled(" > ", app.fg(app.theme.dim)));
+        }
+        let style = if index < sequence.index {
+            app.fg(app.theme.dim)
+        } else if index == sequence.index {
+            app.fg(app.theme.accent).add_modifier(Modifier::BOLD)
+        } else {
+            app.fg(app.theme.muted)
+        };
+        spans.push(Span::styled(format_seconds(length), style));
+    }
+    f.render_widget(
+        Paragraph::new(Line::from(spans)).alignment(Alignment::Center),
+        Rect::new(inner.x, inner.y, inner.width, 1),
+    );
+    if inner.height < 2 {
+        return;
+    }
+
+    // Round up so a segment never shows zero before it ends
+    let secs = sequence.remaining().as_millis().div_ceil(1_000) as u64;
+    let label = if sequence.is_done() {
+        lang.fill(Msg::SegmentsDone, &[("count", &sequence.segments.len())])
+    } else {
+        lang.fill(Msg::LeftInSegment, &[("time", &minutes(secs))])
+    };
+    let gauge = Gauge::default()
+        .gauge_style(app.fg(app.theme.accent))
+        .ratio(sequence.progress())
+        .label(label)
+        .use_unicode(!app.ascii);
+    f.render_widget(gauge, Rect::new(inner.x, inner.y + 1, inner.width, 1));
+}
+
+// Green for the first half, yellow up to 80% and red for the rest
+fn render_progress(f: &mut Frame, area: Rect, (ratio, left): (f64, Duration), app: &AppState) {
+    let color = match ratio {
+        ratio if ratio < 0.5 => app.theme.good,
+        ratio if ratio < 0.8 => app.theme.warning,
+        _ => app.theme.alert,
+    };
+    // Percent rounds down and seconds up, so neither says done too early
+    let secs = left.as_millis().div_ceil(1_000) as u64;
+    let label = app.lang.fill(
+        Msg::ProgressLabel,
+        &[
+            ("percent", &(ratio * 100.0).floor()),
+            ("time", &format_seconds(Duration::from_secs(secs))),
+        ],
+    );
+    let gauge = Gauge::default()
+        .block(app.block().title(app.lang.text(Msg::ProgressTitle)))
+        .gauge_style(app.fg(color))
+        .ratio(ratio)
+        .label(label)
+        .use_unicode(!app.ascii);
+    f.render_widget(gauge, area);
+}
+
+fn render_countdowns(f: &mut Frame, area: Rect, app: &AppState) {
+    const BAR_WIDTH: usize = 20;
+    let (full, empty) = if app.ascii {
+        ("#", "-")
+    } else {
+        ("█", "░")
+    };
+
+    let items: Vec<ListItem> = app
+        .countdowns
+        .iter()
+        .enumerate()
+        .map(|(i, countdown)| {
+            let filled = (countdown.progress() * BAR_WIDTH as f64).round() as usize;
+            let bar = format!(
+                "{}{}",
+                full.repeat(filled),
+                empty.repeat(BAR_WIDTH - filled)
+            );
+            let remaining = if countdown.done {
+                app.lang.text(Msg::CountdownDone).to_string()
+            } else {
+                // Round up so a timer never shows zero before it fires
+                let secs = countdown.remaining().as_millis().div_ceil(1_000) as u64;
+                format!(
+                    "{:02}:{:02}:{:02}",
+                    secs / 3_600,
+                    (secs % 3_600) / 60,
+                    secs % 60
+                )
+            };
+            let marker = if i == app.selected_countdown {
+                ">"
+            } else {
+                " "
+            };
+            let style = if countdown.done {
+                app.fg(app.theme.alert).add_modifier(Modifier::BOLD)
+            } else {
+                app.fg(app.theme.accent)
+            };
+            ListItem::new(format!(
+                "{} {:<16} {:>8} {}",
+                marker, countdown.name, remaining, bar
+            ))
+            .style(style)
+        })
+        .collect();
+
+    let list = List::new(items).block(app.block().title(app.lang.text(Msg::TimersTitle)));
+    f.render_widget(list, area);
+}
+
+fn pace_style(app: &AppState, ahead: bool) -> Style {
+    app.fg(if ahead {
+        app.theme.good
+    } else {
+        app.theme.alert
+    })
+}
+
+// One lap row, shared by the lap list and the text snapshot
+fn lap_spans(
+    chronometer: &Chronometer,
+    app: &AppState,
+    differences: &[String],
+    i: usize,
+) -> Vec<Span<'static>> {
+    let glyphs = glyphs(app.ascii);
+    let cumulative = chronometer.format_duration(chronometer.lap_durations[i]);
+    // The first lap's split is its cumulative time, so it shows just one
+    let (primary, secondary) = match (
+        i.checked_sub(1).and_then(|i| differences.get(i)),
+        app.show_splits,
+    ) {
+        (Some(split), false) => (cumulative, Some(format!("{}: {}", glyphs.delta, split))),
+        (Some(split), true) => (
+            split.clone(),
+            Some(format!("{}: {}", glyphs.total, cumulative)),
+        ),
+        (None, _) => (cumulative, None),
+    };
+    let name = chronometer
+        .segment_name(i)
+        .map_or_else(String::new, |name| format!(" {}", name));
+    let lang = app.lang;
+    let mut lap_text = lang.fill(
+        Msg::LapRow,
+        &[
+            ("number", &(i + 1)),
+            ("id", &chronometer.lap_ids[i]),
+            ("name", &name),
+            ("time", &primary),
+        ],
+    );
+    if let Some(secondary) = secondary {
+        lap_text.push_str(&format!(" ({})", secondary));
+    }
+
+    if chronometer.is_auto_lap(i) {
+        lap_text.push_str(&format!(" {}", lang.text(Msg::LapAuto)));
+    }
+    if chronometer.is_lap_skipped(i) {
+        lap_text.push_str(&format!(" {}", lang.text(Msg::LapExcluded)));
+    }
+
+    let mut spans = vec![Span::raw(lap_text)];
+    if let Some(delta) = chronometer.pace_delta(i) {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!("[{}]", chronometer.format_pace_delta(delta)),
+            pace_style(app, delta.1),
+        ));
+    }
+
+    // Ghost columns against the reference session: its lap, the cumulative
+    // delta, gold when this lap beat the reference's, and the split delta
+    if let Some(comparison) = chronometer.reference_comparison(i) {
+        let dim = app.fg(app.theme.dim);
+        spans.push(Span::styled(
+            format!(
+                " | {} {} ",
+                lang.text(Msg::Reference),
+                chronometer.format_duration(comparison.reference)
+            ),
+            dim,
+        ));
+        let (delta, marker) = if comparison.is_gold() {
+            (app.fg(app.theme.gold), glyphs.gold)
+        } else {
+            (pace_style(app, comparison.delta.1), "")
+        };
+        spans.push(Span::styled(
+            format!(
+                "{}{}",
+                chronometer.format_pace_delta(comparison.delta),
+                marker
+            ),
+            delta,
+        ));
+        spans.push(Span::styled(
+            format!(" | {} ", lang.text(Msg::SplitColumn)),
+            dim,
+        ));
+        spans.push(Span::styled(
+            chronometer.format_pace_delta(comparison.split_delta),
+            pace_style(app, comparison.split_delta.1),
+        ));
+    }
+
+    // The project the lap was taken under
+    if let Some(project) = chronometer.lap_project(chronometer.lap_ids[i]) {
+        spans.push(Span::styled(
+            format!(" | {}", project),
+            app.fg(app.theme.dim),
+        ));
+    }
+    spans
+}
+
+// Green for the fastest split through yellow to red for the slowest; the
+// gradient is the same in every theme, equal splits use the lap color
+fn heat_color(split: Duration, stats: &LapStats, laps: Color) -> Color {
+    let range = stats.worst.saturating_sub(stats.best).as_secs_f64();
+    if range == 0.0 {
+        return laps;
+    }
+    let t = (split.saturating_sub(stats.best).as_secs_f64() / range).clamp(0.0, 1.0);
+    let red = (255.0 * (2.0 * t).min(1.0)) as u8;
+    let green = (255.0 * (2.0 * (1.0 - t)).min(1.0)) as u8;
+    Color::Rgb(red, green, 0)
+}
+
+fn pace_color(split: Duration, stats: &LapStats, app: &AppState) -> Color {
+    match classify_pace(split, stats.average, app.lap_tolerance) {
+        LapPace::Faster => app.theme.good,
+        LapPace::Even => app.theme.warning,
+        LapPace::Slower => app.theme.alert,
+    }
+}
+
+// Draws the world clock beside the laps and returns the space left for them
+#[cfg(feature = "zones")]
+fn render_zones(f: &mut Frame, area: Rect, chronometer: &Chronometer, app: &AppState) -> Rect {
+    if !app.show_clock || app.zones.is_empty() {
+        return area;
+    }
+    let lines: Vec<Line> = zones::zone_lines(&app.zones, chronometer.time_format)
+        .into_iter()
+        .map(Line::from)
+        .collect();
+    let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16 + 2;
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(width)])
+        .split(area);
+    let panel = Paragraph::new(lines)
+        .style(app.fg(app.theme.accent))
+        .block(app.block().title(app.lang.text(Msg::WorldClock)));
+    f.render_widget(panel, chunks[1]);
+    chunks[0]
+}
+
+const ASCII_BARS: bar::Set = bar::Set {
+    full: "#",
+    seven_eighths: "#",
+    three_quarters: "#",
+    five_eighths: "#",
+    half: "=",
+    three_eighths: "=",
+    one_quarter: "_",
+    one_eighth: "_",
+    empty: " ",
+};
+
+// Draws the laps-per-bucket chart under the laps while there's room, and
+// returns the space left for the lap list
+fn render_histogram(f: &mut Frame, area: Rect, chronometer: &Chronometer, app: &AppState) -> Rect {
+    const CHART_HEIGHT: u16 = 8;
+    const BAR_WIDTH: u16 = 3;
+    if !app.show_histogram || area.height < CHART_HEIGHT + 3 {
+        return area;
+    }
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(CHART_HEIGHT)])
+        .split(area);
+
+    // Keep the most recent buckets that fit across the chart
+    let histogram = chronometer.lap_histogram(app.histogram_bucket);
+    let fits = (chunks[1].width.saturating_sub(2) / (BAR_WIDTH + 1)) as usize;
+    let shown = &histogram[histogram.len().saturating_sub(fits)..];
+    let labels: Vec<String> = shown.iter().map(|(index, _)| index.to_string()).collect();
+    let data: Vec<(&str, u64)> = labels
+        .iter()
+        .zip(shown)
+        .map(|(label, &(_, count))| (label.as_str(), count as u64))
+        .collect();
+
+    let title = app.lang.fill(
+        Msg::LapsPer,
+        &[("bucket", &chronometer.format_duration(app.histogram_bucket))],
+    );
+    let mut chart = BarChart::default()
+        .block(app.block().title(title))
+        .data(&data)
+        .bar_width(BAR_WIDTH)
+        .bar_style(app.fg(app.theme.accent));
+    if app.ascii {
+        chart = chart.bar_set(ASCII_BARS);
+    }
+    f.render_widget(chart, chunks[1]);
+    chunks[0]
+}
+
+// Sidebar beside the lap list; skipped warm-up laps aren't counted
+fn render_lap_stats(f: &mut Frame, area: Rect, chronometer: &Chronometer, app: &AppState) -> Rect {
+    const WIDTH: u16 = 24;
+    if !app.show_lap_stats || area.width < 2 * WIDTH {
+        return area;
+    }
+
+    let lang = app.lang;
+    let lines: Vec<Line> = match chronometer.split_stats() {
+        Some(stats) => {
+            let rows = [
+                (Msg::StatLaps, stats.count.to_string()),
+                (Msg::StatBest, chronometer.format_duration(stats.min)),
+                (Msg::StatWorst, chronometer.format_duration(stats.max)),
+                (Msg::StatMean, chronometer.format_duration(stats.mean)),
+                (Msg::StatMedian, chronometer.format_duration(stats.median)),
+                (Msg::StatStdDev, chronometer.format_duration(stats.stddev)),
+            ]
+            .map(|(label, value)| (lang.text(label), value));
+            // The values line up after the longest label in the language
+            let width = rows
+                .iter()
+                .map(|(label, _)| label.chars().count())
+                .max()
+                .unwrap_or(0);
+            rows.into_iter()
+                .map(|(label, value)| Line::from(format!("{:<width$} {}", label, value)))
+                .collect()
+        }
+        None => vec![Line::from(lang.text(Msg::NoLapsYet))],
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Min(0), Constraint::Length(WIDTH)])
+        .split(area);
+    let panel = Paragraph::new(lines)
+        .style(app.fg(app.theme.stats))
+        .block(app.block().title(lang.text(Msg::LapStats)));
+    f.render_widget(panel, chunks[1]);
+    chunks[0]
+}
+
+fn render_laps(f: &mut Frame, area: Rect, chronometer: &Chronometer, app: &AppState) {
+    let mut lap_items: Vec<ListItem> = Vec::new();
+    let differences = chronometer.get_lap_differences();
+
+    let splits = chronometer.lap_splits();
+    let stats = chronometer.lap_stats();
+    let mut matches = 0;
+    let mut selected_index = None;
+
+    for (i, &split) in splits.iter().enumerate() {
+        if let Some(filter) = app.lap_filter {
+            if !filter.matches(split) {
+                continue;
+            }
+            matches += 1;
+        }
+
+        let spans = lap_spans(chronometer, app, &differences, i);
+
+        let lap_id = chronometer.lap_ids[i];
+        let selected = app.selected_lap == Some(lap_id);
+        let mut lines = vec![Line::from(spans)];
+        if selected {
+            if let Some(note) = chronometer.lap_notes.get(&lap_id) {
+                let note = app.lang.fill(Msg::LapNoteLine, &[("note", note)]);
+                lines.push(Line::from(format!("    {}", note)));
+            }
+        }
+
+        // The best and worst counted laps stand out even without the heatmap
+        let counted = stats
+            .as_ref()
+            .filter(|stats| stats.best != stats.worst && !chronometer.is_lap_skipped(i));
+        let style = match counted {
+            Some(stats) if split == stats.best => {
+                app.fg(app.theme.good).add_modifier(Modifier::BOLD)
+            }
+            Some(stats) if split == stats.worst => {
+                app.fg(app.theme.alert).add_modifier(Modifier::BOLD)
+            }
+            Some(stats) if app.heatmap => app.fg(match app.lap_colors {
+                LapColors::Heat => heat_color(split, stats, app.theme.laps),
+                LapColors::Pace => pace_color(split, stats, app),
+            }),
+            _ => app.fg(app.theme.laps),
+        };
+        if selected {
+            selected_index = Some(lap_items.len());
+        }
+        lap_items.push(ListItem::new(lines).style(style));
+    }
+
+    let lang = app.lang;
+    let title = match app.lap_filter {
+        Some(filter) => lang.fill(
+            Msg::LapTimesFiltered,
+            &[
+                ("filter", &filter.label(chronometer)),
+                ("shown", &matches),
+                ("total", &splits.len()),
+            ],
+        ),
+        // The segment the next lap will finish
+        None => match chronometer.segment_name(splits.len()) {
+            Some(next) => lang.fill(Msg::LapTimesNext, &[("next", &next)]),
+            None => lang.text(Msg::LapTimes).to_string(),
+        },
+    };
+    // Without a cursor the view sticks to the newest lap, which the list
+    // scrolls to as an unhighlighted selection; with one, it scrolls just
+    // enough to keep the selected lap visible
+    let highlight = match selected_index {
+        Some(_) => Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD),
+        None => Style::default(),
+    };
+    let selected = selected_index.or(lap_items.len().checked_sub(1));
+    let mut block = app.block().title(title);
+
+    // Once the laps outgrow the panel, the top right says which are in view
+    // and the bottom border keeps the totals that scrolled away
+    let heights: Vec<usize> = lap_items.iter().map(ListItem::height).collect();
+    let visible = visible_range(
+        &heights,
+        selected.unwrap_or(0),
+        block.inner(area).height as usize,
+    );
+    if visible.len() < heights.len() {
+        let page = lang.fill(
+            Msg::LapPage,
+            &[
+                ("from", &(visible.start + 1)),
+                ("to", &visible.end),
+                ("count", &heights.len()),
+            ],
+        );
+        block = block
+            .title(Title::from(page).alignment(Alignment::Right))
+            .title(Title::from(lap_footer(chronometer, app, &splits)).position(Position::Bottom));
+    }
+
+    let mut state = ListState::default().with_selected(selected);
+    let lap_list = List::new(lap_items).block(block).highlight_style(highlight);
+    f.render_stateful_widget(lap_list, area, &mut state);
+}
+
+// The items a fresh ListState scrolled to `selected` shows in `height`
+// rows, picked the way ratatui's List picks them
+fn visible_range(heights: &[usize], selected: usize, height: usize) -> std::ops::Range<usize> {
+    let (mut start, mut end, mut used) = (0, 0, 0);
+    while end < heights.len() && used + heights[end] <= height {
+        used += heights[end];
+        end += 1;
+    }
+    while selected >= end && end < heights.len() {
+        used += heights[end];
+        end += 1;
+        while used > height {
+            used -= heights[start];
+            start += 1;
+        }
+    }
+    start..end
+}
+
+// "233 laps, fastest ..., slowest ..., last Δ ..."; fastest and slowest
+// leave out laps skipped as warm-up, as in the stats
+fn lap_footer(chronometer: &Chronometer, app: &AppState, splits: &[Duration]) -> String {
+    let lang = app.lang;
+    let mut parts = vec![lang.laps(splits.len())];
+    if let Some(stats) = chronometer.lap_stats() {
+        parts.push(lang.fill(
+            Msg::FooterFastest,
+            &[("time", &chronometer.format_duration(stats.best))],
+        ));
+        parts.push(lang.fill(
+            Msg::FooterSlowest,
+            &[("time", &chronometer.format_duration(stats.worst))],
+        ));
+    }
+    if let Some(&last) = splits.last() {
+        parts.push(lang.fill(
+            Msg::FooterLast,
+            &[
+                ("delta", &glyphs(app.ascii).delta),
+                ("time", &chronometer.format_duration(last)),
+            ],
+        ));
+    }
+    parts.join(", ")
+}
+
+fn render_history(
+    f: &mut Frame,
+    area: Rect,
+    view: &HistoryView,
+    chronometer: &Chronometer,
+    app: &AppState,
+) {
+    let (title, lines, cursor) = match view.viewing {
+        None => (
+            app.lang
+                .fill(Msg::HistoryTitle, &[("count", &view.entries.len())]),
+            view.entries
+                .iter()
+                .map(|entry| history_row(entry, chronometer))
+                .collect(),
+            view.selected,
+        ),
+        Some(lap) => {
+            let entry = &view.entries[view.selected];
+            (
+                app.lang.fill(
+                    Msg::SessionTitle,
+                    &[("session", &history_row(entry, chronometer))],
+                ),
+                history_laps(entry, chronometer),
+                lap,
+            )
+        }
+    };
+
+    let empty = lines.is_empty();
+    let items: Vec<ListItem> = if empty {
+        vec![ListItem::new(format!(
+            "  {}",
+            app.lang.text(Msg::NoSessions)
+        ))]
+    } else {
+        lines.into_iter().map(ListItem::new).collect()
+    };
+    let mut state = ListState::default().with_selected((!empty).then_some(cursor));
+    let list = List::new(items)
+        .style(app.fg(app.theme.laps))
+        .block(app.block().title(title))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED | Modifier::BOLD));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chronorust::logging::LogFormat;
+    use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+    // The screen ui() draws, one string per row, and the buffer behind it
+    fn render(timers: &App, app: &AppState, width: u16, height: u16) -> (Vec<String>, Buffer) {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal
+            .draw(|f| {
+                ui(f, timers, app);
+            })
+            .unwrap();
+        let buffer = terminal.backend().buffer().clone();
+        let rows = buffer
+            .content
+            .chunks(width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect())
+            .collect();
+        (rows, buffer)
+    }
+
+    // Where `text` starts on the screen, for ASCII text
+    fn find(buffer: &Buffer, text: &str) -> Option<(u16, u16)> {
+        let area = buffer.area;
+        let len = text.chars().count() as u16;
+        (area.top()..area.bottom()).find_map(|y| {
+            (area.left()..area.right().saturating_sub(len - 1)).find_map(|x| {
+                let found: String = (x..x + len).map(|x| buffer.get(x, y).symbol()).collect();
+                (found == text).then_some((x, y))
+            })
+        })
+    }
+
+    fn row_of(rows: &[String], text: &str) -> usize {
+        rows.iter()
+            .position(|row| row.contains(text))
+            .unwrap_or_else(|| panic!("'{}' not on screen:\n{}", text, rows.join("\n")))
+    }
+
+    // Whole seconds, so the real clock moving on between start and render
+    // doesn't change what's shown
+    fn chronometer_at(secs: u64) -> Chronometer {
+        let mut chronometer = Chronometer::new();
+        chronometer.precision = Precision::Seconds;
+        chronometer.start_since(Duration::from_secs(secs)).unwrap();
+        chronometer
+    }
+
+    #[test]
+    fn fresh_start_shows_zero_in_the_time_box() {
+        let mut chronometer = Chronometer::new();
+        chronometer.precision = Precision::Seconds;
+        let (rows, _) = render(&App::new(chronometer), &AppState::new(), 80, 30);
+        assert_eq!(row_of(&rows, "00:00:00"), row_of(&rows, "Time") + 1);
+        assert!(!rows.iter().any(|row| row.contains("Lap 1")));
+    }
+
+    #[test]
+    fn laps_are_listed_under_their_title() {
+        let mut chronometer = chronometer_at(10);
+        chronometer.lap();
+        chronometer.lap();
+        let (rows, _) = render(&App::new(chronometer), &AppState::new(), 80, 30);
+        let title = row_of(&rows, "Lap Times");
+        assert_eq!(row_of(&rows, "Lap 1 [#1]: 00:00:10"), title + 1);
+        assert_eq!(row_of(&rows, "Lap 2 [#2]"), title + 2);
+        assert!(row_of(&rows, "Time") < title);
+    }
+
+    #[test]
+    fn paused_time_takes_the_paused_color() {
+        // The dark theme draws both states green
+        let mut app = AppState::new();
+        app.theme = Theme::named("light").unwrap();
+        let mut chronometer = chronometer_at(61);
+        let (_, buffer) = render(&App::new(chronometer.new_like()), &app, 80, 30);
+        let (x, y) = find(&buffer, "00:00:00").unwrap();
+        assert_eq!(buffer.get(x, y).fg, app.theme.running);
+
+        chronometer.pause().unwrap();
+        let (_, buffer) = render(&App::new(chronometer), &app, 80, 30);
+        let (x, y) = find(&buffer, "00:01:01").unwrap();
+        assert_eq!(buffer.get(x, y).fg, app.theme.paused);
+    }
+
+    #[test]
+    fn countdown_shows_the_time_left() {
+        let mut chronometer = Chronometer::new();
+        chronometer.precision = Precision::Seconds;
+        chronometer.countdown_from = Some(Duration::from_secs(300));
+        chronometer.start_since(Duration::from_secs(60)).unwrap();
+        chronometer.pause().unwrap();
+        let (rows, _) = render(&App::new(chronometer), &AppState::new(), 80, 30);
+        assert_eq!(row_of(&rows, "00:03:59"), row_of(&rows, "Time") + 1);
+    }
+
+    #[test]
+    fn keys_drive_the_active_timer() {
+        let mut timers = App::new(chronometer_at(0));
+        let mut app = AppState::new();
+        let press =
+            |timers: &mut App, app: &mut AppState, c| handle_key(timers, app, KeyCode::Char(c));
+        assert!(press(&mut timers, &mut app, 'l') == LoopControl::Continue);
+        assert_eq!(timers.active().lap_durations.len(), 1);
+        assert_eq!(app.messages.back().unwrap().text, "Lap 1 recorded");
+
+        press(&mut timers, &mut app, 's');
+        assert!(timers.active().is_paused());
+        press(&mut timers, &mut app, 'S');
+        assert!(timers.active().is_counting());
+
+        // Quitting would lose the lap, so it asks first
+        assert!(press(&mut timers, &mut app, 'q') == LoopControl::Continue);
+        assert!(app.confirming == Some(Action::Quit));
+        assert!(press(&mut timers, &mut app, 'y') == LoopControl::Quit);
+    }
+
+    #[test]
+    fn time_survives_small_terminals() {
+        let mut chronometer = chronometer_at(3_723);
+        chronometer.lap();
+        let timers = App::new(chronometer);
+        let app = AppState::new();
+        for (width, height) in [
+            (80, 24),
+            (80, 16),
+            (80, 10),
+            (60, 7),
+            (40, 6),
+            (39, 6),
+            (40, 5),
+            (30, 3),
+            (20, 1),
+            (150, 8),
+        ] {
+            let (rows, _) = render(&timers, &app, width, height);
+            assert!(
+                rows.iter().any(|row| row.contains("01:02:03")),
+                "no time at {}x{}:\n{}",
+                width,
+                height,
+                rows.join("\n")
+            );
+        }
+    }
+
+    #[test]
+    fn short_terminals_drop_sections_in_priority_order() {
+        let layout = |height| compute_layout(Rect::new(0, 0, 80, height), 1, 0, false, 1, 0);
+
+        let full = layout(30);
+        assert!(full.title.is_some() && full.stats.is_some() && full.controls.is_some());
+        assert!(full.laps.is_some_and(|laps| laps.height >= 5));
+
+        // Title and controls go before the stats, the stats before the laps
+        let short = layout(9);
+        assert_eq!(short.time.height, 3);
+        assert!(short.laps.is_some() && short.stats.is_some());
+        assert!(short.title.is_none() && short.controls.is_none());
+        let tiny = layout(6);
+        assert!(tiny.laps.is_some() && tiny.stats.is_none());
+        let time_only = layout(2);
+        assert_eq!(time_only.time.height, 2);
+        assert!(time_only.laps.is_none());
+    }
+
+    #[test]
+    fn transient_input_errors_are_logged_and_skipped() {
+        let path =
+            std::env::temp_dir().join(format!("chronorust-input-{}.log", std::process::id()));
+        let mut chronometer = chronometer_at(0);
+        chronometer
+            .enable_logging(Some(&path), LogFormat::Text)
+            .unwrap();
+        let mut timers = App::new(chronometer);
+        let mut errors = 0;
+
+        for kind in [io::ErrorKind::Interrupted, io::ErrorKind::TimedOut] {
+            assert!(skip_input_error(&mut timers, kind.into(), &mut errors).is_ok());
+        }
+        assert_eq!(errors, 2);
+        let fatal = skip_input_error(&mut timers, io::ErrorKind::BrokenPipe.into(), &mut errors);
+        assert_eq!(fatal.unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+
+        // A long enough run of them means the terminal isn't coming back
+        errors = MAX_INPUT_ERRORS;
+        assert!(
+            skip_input_error(&mut timers, io::ErrorKind::Interrupted.into(), &mut errors).is_err()
+        );
+
+        drop(timers);
+        let log = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(log.matches("Input error at:").count(), 2);
+    }
+
+    fn press_all(timers: &mut App, app: &mut AppState, keys: &[KeyCode]) -> Vec<bool> {
+        keys.iter()
+            .map(|&key| handle_key(timers, app, key) == LoopControl::Quit)
+            .collect()
+    }
+
+    #[test]
+    fn key_sequence_laps_pauses_and_quits() {
+        let mut timers = App::new(chronometer_at(0));
+        let mut app = AppState::new();
+        app.confirm = false;
+        let keys = [
+            KeyCode::Char('l'),
+            KeyCode::Char('L'),
+            KeyCode::Char('s'),
+            KeyCode::Char('s'),
+            KeyCode::Char('q'),
+        ];
+        let quits = press_all(&mut timers, &mut app, &keys);
+        assert_eq!(quits, [false, false, false, false, true]);
+        let chronometer = timers.active();
+        assert_eq!(chronometer.lap_ids, [1, 2]);
+        assert!(chronometer.is_counting());
+        assert_eq!(chronometer.report().pauses.len(), 1);
+    }
+
+    #[test]
+    fn prompts_take_the_keys_until_answered() {
+        let mut timers = App::new(chronometer_at(0));
+        let mut app = AppState::new();
+        let mut keys = vec![KeyCode::Char('o')];
+        keys.extend("1:30".chars().map(KeyCode::Char));
+        keys.push(KeyCode::Enter);
+        press_all(&mut timers, &mut app, &keys);
+        assert!(app.input.is_none());
+        assert!(timers.active().elapsed() >= Duration::from_secs(90));
+        assert!(timers.active().laps().is_empty());
+
+        // A bad offset keeps the prompt open with the error
+        press_all(
+            &mut timers,
+            &mut app,
+            &[KeyCode::Char('o'), KeyCode::Char('x'), KeyCode::Enter],
+        );
+        assert!(app
+            .input
+            .as_ref()
+            .is_some_and(|input| input.error.is_some()));
+        press_all(&mut timers, &mut app, &[KeyCode::Esc]);
+        assert!(app.input.is_none());
+    }
+
+    #[test]
+    fn a_declined_reset_keeps_the_laps() {
+        let mut timers = App::new(chronometer_at(0));
+        let mut app = AppState::new();
+        let keys = [
+            KeyCode::Char('l'),
+            KeyCode::Char('r'),
+            KeyCode::Char('x'),
+            KeyCode::Char('n'),
+        ];
+        press_all(&mut timers, &mut app, &keys);
+        assert!(app.confirming.is_none());
+        assert_eq!(timers.active().lap_ids, [1]);
+
+        press_all(&mut timers, &mut app, &[KeyCode::Char('r'), KeyCode::Enter]);
+        assert!(timers.active().laps().is_empty());
+    }
+
+    #[test]
+    fn locked_controls_ignore_keys_until_unlocked() {
+        let mut timers = App::new(chronometer_at(0));
+        let mut app = AppState::new();
+        press_all(
+            &mut timers,
+            &mut app,
+            &[KeyCode::Char('k'), KeyCode::Char('l'), KeyCode::Char('q')],
+        );
+        assert!(app.locked);
+        assert!(timers.active().laps().is_empty());
+
+        press_all(
+            &mut timers,
+            &mut app,
+            &[KeyCode::Char('k'), KeyCode::Enter, KeyCode::Char('l')],
+        );
+        assert!(!app.locked);
+        assert_eq!(timers.active().lap_ids, [1]);
+    }
+
+    #[test]
+    fn remapped_keys_follow_the_keymap() {
+        let mut timers = App::new(chronometer_at(0));
+        let mut app = AppState::new();
+        app.keymap = KeyMap::from_entries(&[("lap", "space"), ("chart", "l")]).unwrap();
+        press_all(
+            &mut timers,
+            &mut app,
+            &[KeyCode::Char(' '), KeyCode::Char('l')],
+        );
+        assert_eq!(timers.active().lap_ids, [1]);
+        assert!(app.show_histogram);
+    }
+
+    #[test]
+    fn generated_files_go_under_the_output_dir() {
+        let base = std::env::temp_dir().join(format!("chronorust-output-{}", std::process::id()));
+        let dir = prepare_output_dir(base.join("runs/today")).unwrap();
+        assert!(dir.is_dir());
+
+        let mut chronometer = chronometer_at(5);
+        chronometer.output_dir = Some(dir.clone());
+        chronometer.lap();
+        let log = chronometer.enable_logging(None, LogFormat::Text).unwrap();
+        let export = chronometer.export_laps(ExportFormat::Csv).unwrap();
+        let snapshot = write_snapshot(&chronometer, &AppState::new()).unwrap();
+        for file in [&log, &export, &snapshot] {
+            assert_eq!(file.parent(), Some(dir.as_path()));
+            assert!(file.is_file(), "{}", file.display());
+        }
+
+        // A file in the way means the current directory is used
+        assert!(prepare_output_dir(export.join("below")).is_none());
+        drop(chronometer);
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn pace_colors_follow_the_classification() {
+        let app = AppState::new();
+        let stats = LapStats {
+            best: Duration::from_secs(50),
+            worst: Duration::from_secs(70),
+            average: Duration::from_secs(60),
+        };
+        let color = |secs| pace_color(Duration::from_secs(secs), &stats, &app);
+        assert_eq!(color(50), app.theme.good);
+        assert_eq!(color(61), app.theme.warning);
+        assert_eq!(color(70), app.theme.alert);
+    }
+
+    #[test]
+    fn turkish_reaches_units_precision_and_filter_errors() {
+        let mut timers = App::new(chronometer_at(60));
+        timers.timers[0].lap();
+        let mut app = AppState::new();
+        app.lang = Lang::Tr;
+        let stats: String = stats_lines(&timers.timers[0], &app)
+            .iter()
+            .flat_map(|line| line.spans.iter().map(|span| span.content.to_string()))
+            .collect();
+        assert!(stats.contains(" tur/dk"), "{}", stats);
+
+        press_all(&mut timers, &mut app, &[KeyCode::Char('p')]);
+        assert_eq!(app.current_status(), Some("Hassasiyet: milisaniye"));
+        assert_eq!(
+            LapFilter::parse("01:30", Lang::Tr).err().as_deref(),
+            Some("süzgece '>' veya '<' ile başlayın")
+        );
+    }
 }
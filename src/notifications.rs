@@ -0,0 +1,68 @@
+use std::{
+    process::{Command, Stdio},
+    thread,
+};
+
+// Desktop notifications through each platform's own tool, so no D-Bus or
+// notification library is linked in. Delivery is best effort: without the
+// tool or a notification daemon nothing is shown.
+pub fn notify(summary: &str, body: &str) {
+    let Some(mut command) = platform_command(summary, body) else {
+        return;
+    };
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Reaped on its own thread so the draw loop never waits for it
+    thread::spawn(move || {
+        let _ = command.status();
+    });
+}
+
+// libnotify's notify-send, shipped by most Linux and BSD desktops
+#[cfg(all(unix, not(target_os = "macos")))]
+fn platform_command(summary: &str, body: &str) -> Option<Command> {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name=ChronoRust", summary, body]);
+    Some(command)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_command(summary: &str, body: &str) -> Option<Command> {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let script = format!(
+        "display notification {} with title {}",
+        quote(body),
+        quote(summary)
+    );
+    let mut command = Command::new("osascript");
+    command.args(["-e", script.as_str()]);
+    Some(command)
+}
+
+// A tray balloon through Windows Forms, available on every Windows install.
+// PowerShell has to stay alive while the balloon shows.
+#[cfg(windows)]
+fn platform_command(summary: &str, body: &str) -> Option<Command> {
+    let quote = |text: &str| format!("'{}'", text.replace('\'', "''"));
+    let script = format!(
+        "Add-Type -AssemblyName System.Windows.Forms; \
+         $n = New-Object System.Windows.Forms.NotifyIcon; \
+         $n.Icon = [System.Drawing.SystemIcons]::Information; \
+         $n.Visible = $true; \
+         $n.ShowBalloonTip(5000, {}, {}, 'Info'); \
+         Start-Sleep -Seconds 6; \
+         $n.Dispose()",
+        quote(summary),
+        quote(body)
+    );
+    let mut command = Command::new("powershell");
+    command.args(["-NoProfile", "-NonInteractive", "-Command", script.as_str()]);
+    Some(command)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn platform_command(_summary: &str, _body: &str) -> Option<Command> {
+    None
+}
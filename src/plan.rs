@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use crate::duration::parse_duration;
+
+// A sequence of named segments, like a meeting agenda, laid over the
+// stopwatch's elapsed time. Segments either follow each other on their own
+// or wait for a key press, running over their time until it comes. The plan
+// is done when the last one ends.
+pub struct Plan {
+    pub title: Option<String>,
+    pub segments: Vec<(String, Duration)>,
+    pub auto_advance: bool,
+    // segments.len() once done
+    pub index: usize,
+    segment_start: Duration,
+    elapsed: Duration,
+}
+
+impl Plan {
+    // The session file's `title`, `advance` ("auto" or "key") and
+    // `segments`, each a name and a duration like "Demo 10m"
+    pub fn new(
+        title: Option<&str>,
+        advance: Option<&str>,
+        segments: &[&str],
+    ) -> Result<Self, String> {
+        let auto_advance = match advance {
+            None | Some("auto") => true,
+            Some("key") => false,
+            Some(other) => {
+                return Err(format!(
+                    "advance must be \"auto\" or \"key\", not \"{}\"",
+                    other
+                ))
+            }
+        };
+        let segments = segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                parse_segment(segment).map_err(|err| format!("segment {}: {}", index + 1, err))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if segments.is_empty() {
+            return Err("no segments".to_string());
+        }
+        Ok(Self {
+            title: title.map(str::to_string),
+            segments,
+            auto_advance,
+            index: 0,
+            segment_start: Duration::ZERO,
+            elapsed: Duration::ZERO,
+        })
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.index >= self.segments.len()
+    }
+
+    pub fn current(&self) -> Option<&(String, Duration)> {
+        self.segments.get(self.index)
+    }
+
+    pub fn next(&self) -> Option<&(String, Duration)> {
+        self.segments.get(self.index + 1)
+    }
+
+    pub fn total(&self) -> Duration {
+        self.segments.iter().map(|&(_, length)| length).sum()
+    }
+
+    // Moves to `elapsed`, returning true when a new segment began or the
+    // plan finished. A clock that went back before the segment started was
+    // reset, so the plan starts over.
+    pub fn tick(&mut self, elapsed: Duration) -> bool {
+        let before = self.index;
+        if elapsed < self.segment_start {
+            self.index = 0;
+            self.segment_start = Duration::ZERO;
+        }
+        self.elapsed = elapsed;
+        if self.auto_advance {
+            while let Some(&(_, length)) = self.current() {
+                if elapsed < self.segment_start + length {
+                    break;
+                }
+                self.segment_start += length;
+                self.index += 1;
+            }
+        }
+        self.index != before
+    }
+
+    // The key press that ends the current segment now, however early or
+    // late that is. Returns false once the plan is done.
+    pub fn advance(&mut self, elapsed: Duration) -> bool {
+        if self.is_done() {
+            return false;
+        }
+        self.index += 1;
+        self.segment_start = elapsed;
+        self.elapsed = elapsed;
+        true
+    }
+
+    fn in_segment(&self) -> Duration {
+        self.elapsed.saturating_sub(self.segment_start)
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.current().map_or(Duration::ZERO, |&(_, length)| {
+            length.saturating_sub(self.in_segment())
+        })
+    }
+
+    // How long a segment waiting for its key press has run over
+    pub fn overtime(&self) -> Duration {
+        self.current().map_or(Duration::ZERO, |&(_, length)| {
+            self.in_segment().saturating_sub(length)
+        })
+    }
+
+    pub fn progress(&self) -> f64 {
+        match self.current() {
+            Some(&(_, length)) => (self.in_segment().as_secs_f64() / length.as_secs_f64()).min(1.0),
+            None => 1.0,
+        }
+    }
+
+    // The planned time covered so far: every finished segment in full and
+    // the current one up to its length, so overruns don't count twice
+    pub fn planned_elapsed(&self) -> Duration {
+        let finished: Duration = self.segments[..self.index.min(self.segments.len())]
+            .iter()
+            .map(|&(_, length)| length)
+            .sum();
+        let current = self
+            .current()
+            .map_or(Duration::ZERO, |&(_, length)| self.in_segment().min(length));
+        finished + current
+    }
+}
+
+// "Q&A 10m": the last word is the duration, the rest the name
+fn parse_segment(segment: &str) -> Result<(String, Duration), String> {
+    let (name, duration) = segment
+        .trim()
+        .rsplit_once(char::is_whitespace)
+        .ok_or_else(|| format!("expected a name and a duration, got \"{}\"", segment))?;
+    let duration = parse_duration(duration)?;
+    if duration.is_zero() {
+        return Err(format!("'{}' must be longer than zero", name.trim()));
+    }
+    Ok((name.trim().to_string(), duration))
+}
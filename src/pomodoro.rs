@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Phase {
+    Work,
+    Break,
+}
+
+impl Phase {
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::Work => "Work",
+            Phase::Break => "Break",
+        }
+    }
+}
+
+// Alternating work and break phases laid over the stopwatch's elapsed time,
+// so pausing the stopwatch pauses the cycle and a reset starts it over
+pub struct Pomodoro {
+    pub work: Duration,
+    pub rest: Duration,
+    pub phase: Phase,
+    // Work phases finished so far
+    pub cycles: u32,
+    phase_start: Duration,
+    elapsed: Duration,
+}
+
+impl Pomodoro {
+    pub fn new(work: Duration, rest: Duration) -> Self {
+        Self {
+            work,
+            rest,
+            phase: Phase::Work,
+            cycles: 0,
+            phase_start: Duration::ZERO,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn phase_length(&self) -> Duration {
+        match self.phase {
+            Phase::Work => self.work,
+            Phase::Break => self.rest,
+        }
+    }
+
+    // Advances to `elapsed`, returning true when a new phase began. Time
+    // going backwards (reset, restart from lap) starts over from work.
+    pub fn tick(&mut self, elapsed: Duration) -> bool {
+        if elapsed < self.elapsed {
+            *self = Self::new(self.work, self.rest);
+        }
+        self.elapsed = elapsed;
+
+        let mut changed = false;
+        while elapsed >= self.phase_start + self.phase_length() {
+            self.phase_start += self.phase_length();
+            self.phase = match self.phase {
+                Phase::Work => {
+                    self.cycles += 1;
+                    Phase::Break
+                }
+                Phase::Break => Phase::Work,
+            };
+            changed = true;
+        }
+        changed
+    }
+
+    pub fn remaining(&self) -> Duration {
+        (self.phase_start + self.phase_length()).saturating_sub(self.elapsed)
+    }
+
+    pub fn progress(&self) -> f64 {
+        let done = self.elapsed.saturating_sub(self.phase_start).as_secs_f64();
+        (done / self.phase_length().as_secs_f64()).min(1.0)
+    }
+}
@@ -0,0 +1,65 @@
+use std::{
+    fs::File,
+    io,
+    path::Path,
+    process::{Child, Command, ExitStatus, Stdio},
+};
+
+// A command timed by `chronorust run`. Its output goes to a file because
+// the TUI owns the terminal while it runs.
+pub struct RunningCommand {
+    pub command_line: String,
+    child: Child,
+    pub status: Option<ExitStatus>,
+}
+
+impl RunningCommand {
+    pub fn spawn(command: &[String], output: &Path) -> io::Result<Self> {
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no command given"))?;
+        let log = File::create(output)?;
+        let child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()?;
+        Ok(Self {
+            command_line: command.join(" "),
+            child,
+            status: None,
+        })
+    }
+
+    // Returns the exit status once, on the first poll after the command ends
+    pub fn poll(&mut self) -> Option<ExitStatus> {
+        if self.status.is_some() {
+            return None;
+        }
+        self.status = self.child.try_wait().ok().flatten();
+        self.status
+    }
+
+    // Used when chronorust quits first, so the command doesn't outlive it
+    pub fn kill(&mut self) {
+        if self.status.is_none() {
+            let _ = self.child.kill();
+            self.status = self.child.wait().ok();
+        }
+    }
+}
+
+pub fn exit_label(status: ExitStatus) -> String {
+    if let Some(code) = status.code() {
+        return format!("exit {}", code);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return format!("killed by signal {}", signal);
+        }
+    }
+    "terminated".to_string()
+}
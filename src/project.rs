@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+// Time per project tag, as a time tracker keeps it. The tag in force
+// collects the elapsed time from when it was set; switching banks that and
+// starts the next. Untagged time isn't counted anywhere.
+#[derive(Clone, Default)]
+pub struct ProjectTimes {
+    pub current: Option<String>,
+    // The elapsed time the current tag was set at
+    since: Duration,
+    // Banked time, in the order the tags were first used
+    banked: Vec<(String, Duration)>,
+}
+
+impl ProjectTimes {
+    pub fn new(current: Option<String>) -> Self {
+        Self {
+            current,
+            ..Self::default()
+        }
+    }
+
+    pub fn switch(&mut self, project: Option<String>, elapsed: Duration) {
+        self.bank(elapsed);
+        self.current = project;
+    }
+
+    // Every tag's time up to `elapsed`, the current one included
+    pub fn totals(&self, elapsed: Duration) -> Vec<(String, Duration)> {
+        let mut totals = self.banked.clone();
+        if let Some(ref project) = self.current {
+            add(&mut totals, project, elapsed.saturating_sub(self.since));
+        }
+        totals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.current.is_none() && self.banked.is_empty()
+    }
+
+    // Starts counting over from `elapsed` under the same tag, as a reset
+    // does, handing back the time counted so far
+    pub fn restart(&mut self, elapsed: Duration, from: Duration) -> Vec<(String, Duration)> {
+        let totals = self.totals(elapsed);
+        self.banked.clear();
+        self.since = from;
+        totals
+    }
+
+    // Puts earlier totals back underneath the ones up to `elapsed`, then
+    // counts on from `from`, as undoing a reset or resuming a session does
+    pub fn restore(&mut self, earlier: &[(String, Duration)], elapsed: Duration, from: Duration) {
+        let mut totals = earlier.to_vec();
+        for (project, time) in self.totals(elapsed) {
+            add(&mut totals, &project, time);
+        }
+        // The current tag's share is in the totals now
+        self.banked = totals;
+        self.since = from;
+    }
+
+    fn bank(&mut self, elapsed: Duration) {
+        self.banked = self.totals(elapsed);
+        self.since = elapsed;
+    }
+}
+
+// Adds `time` to the project's entry, creating it at the end
+pub fn add(totals: &mut Vec<(String, Duration)>, project: &str, time: Duration) {
+    match totals.iter_mut().find(|(name, _)| name == project) {
+        Some((_, total)) => *total += time,
+        None => totals.push((project.to_string(), time)),
+    }
+}
+
+// Tags are written into logs, exports and session files as they are, so
+// the characters those would have to escape are kept out
+pub fn parse_project(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("empty project name".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|&c| c.is_control() || matches!(c, '"' | '\\' | ',' | '[' | ']'))
+    {
+        return Err(format!("project names can't contain {:?}", c));
+    }
+    Ok(name.to_string())
+}
@@ -0,0 +1,69 @@
+use std::time::Duration;
+
+// Countdowns run back to back, as `chronorust seq 5m 30s 10m` starts them.
+// The whole sequence counts down as one countdown from the sum of its
+// segments; this works out from the elapsed time which segment is on and
+// how long it has left, so a reset starts over from the first.
+pub struct Sequence {
+    pub segments: Vec<Duration>,
+    // segments.len() once done
+    pub index: usize,
+    segment_start: Duration,
+    elapsed: Duration,
+}
+
+impl Sequence {
+    pub fn new(segments: Vec<Duration>) -> Self {
+        Self {
+            segments,
+            index: 0,
+            segment_start: Duration::ZERO,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.index >= self.segments.len()
+    }
+
+    pub fn total(&self) -> Duration {
+        self.segments.iter().sum()
+    }
+
+    // Moves to `elapsed`, returning true when a new segment began or the
+    // sequence finished
+    pub fn tick(&mut self, elapsed: Duration) -> bool {
+        let before = self.index;
+        self.elapsed = elapsed;
+        self.index = 0;
+        self.segment_start = Duration::ZERO;
+        while let Some(&length) = self.segments.get(self.index) {
+            if elapsed < self.segment_start + length {
+                break;
+            }
+            self.segment_start += length;
+            self.index += 1;
+        }
+        self.index != before
+    }
+
+    pub fn current(&self) -> Option<Duration> {
+        self.segments.get(self.index).copied()
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.current().map_or(Duration::ZERO, |length| {
+            (self.segment_start + length).saturating_sub(self.elapsed)
+        })
+    }
+
+    pub fn progress(&self) -> f64 {
+        match self.current() {
+            Some(length) => {
+                let done = self.elapsed.saturating_sub(self.segment_start);
+                (done.as_secs_f64() / length.as_secs_f64()).min(1.0)
+            }
+            None => 1.0,
+        }
+    }
+}
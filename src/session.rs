@@ -0,0 +1,342 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{self, Read, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Compact binary snapshot of a session for small devices:
+//   magic "CHRB", version byte, flags byte (bit 0 = paused),
+//   elapsed ms, start timestamp ms since the epoch, lap count (u32),
+//   then each lap's elapsed ms. All integers are little-endian.
+const MAGIC: &[u8; 4] = b"CHRB";
+const VERSION: u8 = 1;
+const FLAG_PAUSED: u8 = 0b0000_0001;
+
+pub struct SessionState {
+    pub elapsed_ms: u64,
+    pub laps_ms: Vec<u64>,
+    pub paused: bool,
+    pub start_timestamp_ms: u64,
+    // Time per --tag project; the binary format doesn't carry it
+    pub projects_ms: Vec<(String, u64)>,
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u8),
+    Truncated,
+    Malformed(String),
+}
+
+impl fmt::Display for SessionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SessionError::Io(err) => write!(f, "{}", err),
+            SessionError::BadMagic => write!(f, "not a ChronoRust binary session"),
+            SessionError::UnsupportedVersion(version) => {
+                write!(
+                    f,
+                    "unsupported session version {} (expected {})",
+                    version, VERSION
+                )
+            }
+            SessionError::Truncated => write!(f, "session file is truncated"),
+            SessionError::Malformed(reason) => write!(f, "malformed session file: {}", reason),
+        }
+    }
+}
+
+impl From<io::Error> for SessionError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => SessionError::Truncated,
+            _ => SessionError::Io(err),
+        }
+    }
+}
+
+pub fn save_state_binary(path: &Path, state: &SessionState) -> Result<(), SessionError> {
+    let mut bytes = Vec::with_capacity(26 + state.laps_ms.len() * 8);
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.push(if state.paused { FLAG_PAUSED } else { 0 });
+    bytes.extend_from_slice(&state.elapsed_ms.to_le_bytes());
+    bytes.extend_from_slice(&state.start_timestamp_ms.to_le_bytes());
+    bytes.extend_from_slice(&(state.laps_ms.len() as u32).to_le_bytes());
+    for lap in &state.laps_ms {
+        bytes.extend_from_slice(&lap.to_le_bytes());
+    }
+
+    File::create(path)?.write_all(&bytes)?;
+    Ok(())
+}
+
+pub fn load_state_binary(path: &Path) -> Result<SessionState, SessionError> {
+    let mut file = File::open(path)?;
+
+    let mut magic = [0; 4];
+    file.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(SessionError::BadMagic);
+    }
+    let [version, flags] = read_array(&mut file)?;
+    if version != VERSION {
+        return Err(SessionError::UnsupportedVersion(version));
+    }
+
+    let elapsed_ms = read_u64(&mut file)?;
+    let start_timestamp_ms = read_u64(&mut file)?;
+    let lap_count = u32::from_le_bytes(read_array(&mut file)?);
+    // Read laps one at a time so a bogus count can't trigger a huge allocation
+    let mut laps_ms = Vec::new();
+    for _ in 0..lap_count {
+        laps_ms.push(read_u64(&mut file)?);
+    }
+
+    Ok(SessionState {
+        elapsed_ms,
+        laps_ms,
+        paused: flags & FLAG_PAUSED != 0,
+        start_timestamp_ms,
+        projects_ms: Vec::new(),
+    })
+}
+
+fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    read_array(reader).map(u64::from_le_bytes)
+}
+
+// The same state as JSON for --resume, plus the wall-clock time it was
+// saved so a session left running keeps counting while chronorust is closed
+pub fn save_state_json(path: &Path, state: &SessionState) -> Result<(), SessionError> {
+    File::create(path)?.write_all(state_json(state).as_bytes())?;
+    Ok(())
+}
+
+// The file's one line of JSON, which the daemon also answers `state` with
+pub fn state_json(state: &SessionState) -> String {
+    let laps: Vec<String> = state.laps_ms.iter().map(u64::to_string).collect();
+    // Tag names keep out the characters that would need escaping here
+    let (names, times): (Vec<String>, Vec<String>) = state
+        .projects_ms
+        .iter()
+        .map(|(project, ms)| (format!("\"{}\"", project), ms.to_string()))
+        .unzip();
+    format!(
+        "{{\"version\":{},\"elapsed_ms\":{},\"laps_ms\":[{}],\"paused\":{},\"start_timestamp_ms\":{},\"projects\":[{}],\"projects_ms\":[{}],\"saved_at_ms\":{}}}\n",
+        VERSION,
+        state.elapsed_ms,
+        laps.join(","),
+        state.paused,
+        state.start_timestamp_ms,
+        names.join(","),
+        times.join(","),
+        now_ms()
+    )
+}
+
+pub fn load_state_json(path: &Path) -> Result<SessionState, SessionError> {
+    parse_state_json(&std::fs::read_to_string(path)?)
+}
+
+// A running session comes back with the time since it was saved added on
+pub fn parse_state_json(text: &str) -> Result<SessionState, SessionError> {
+    let version = json_u64(text, "version")?;
+    if version != u64::from(VERSION) {
+        return Err(SessionError::UnsupportedVersion(version as u8));
+    }
+
+    let paused = match json_field(text, "paused")? {
+        "true" => true,
+        "false" => false,
+        other => return Err(malformed("paused", other)),
+    };
+    let laps_ms = json_array(text, "laps_ms")?
+        .into_iter()
+        .map(|lap| lap.parse().map_err(|_| malformed("laps_ms", lap)))
+        .collect::<Result<Vec<u64>, _>>()?;
+
+    // Files from before project tags have neither array
+    let projects_ms = if text.contains("\"projects\":") {
+        let names = json_array(text, "projects")?;
+        let times = json_array(text, "projects_ms")?;
+        if names.len() != times.len() {
+            return Err(SessionError::Malformed(
+                "projects and projects_ms differ in length".to_string(),
+            ));
+        }
+        names
+            .into_iter()
+            .zip(times)
+            .map(|(name, ms)| {
+                let ms = ms.parse().map_err(|_| malformed("projects_ms", ms))?;
+                Ok((name.trim_matches('"').to_string(), ms))
+            })
+            .collect::<Result<Vec<_>, SessionError>>()?
+    } else {
+        Vec::new()
+    };
+
+    let mut elapsed_ms = json_u64(text, "elapsed_ms")?;
+    if !paused {
+        elapsed_ms += now_ms().saturating_sub(json_u64(text, "saved_at_ms")?);
+    }
+
+    Ok(SessionState {
+        elapsed_ms,
+        laps_ms,
+        paused,
+        start_timestamp_ms: json_u64(text, "start_timestamp_ms")?,
+        projects_ms,
+    })
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+fn malformed(name: &str, value: &str) -> SessionError {
+    SessionError::Malformed(format!("bad value '{}' for {}", value, name))
+}
+
+// Raw value of a top-level field in the flat object save_state_json writes
+fn json_field<'a>(text: &'a str, name: &str) -> Result<&'a str, SessionError> {
+    let key = format!("\"{}\":", name);
+    let start = text
+        .find(&key)
+        .map(|index| index + key.len())
+        .ok_or_else(|| SessionError::Malformed(format!("missing {}", name)))?;
+    let rest = &text[start..];
+    let end = if rest.starts_with('[') {
+        rest.find(']').map(|index| index + 1)
+    } else {
+        rest.find([',', '}'])
+    };
+    Ok(rest[..end.unwrap_or(rest.len())].trim())
+}
+
+// The items of a flat array field, unparsed
+fn json_array<'a>(text: &'a str, name: &str) -> Result<Vec<&'a str>, SessionError> {
+    let array = json_field(text, name)?;
+    Ok(array
+        .strip_prefix('[')
+        .and_then(|items| items.strip_suffix(']'))
+        .ok_or_else(|| malformed(name, array))?
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect())
+}
+
+fn json_u64(text: &str, name: &str) -> Result<u64, SessionError> {
+    let value = json_field(text, name)?;
+    value.parse().map_err(|_| malformed(name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::temp_file;
+
+    fn sample(paused: bool) -> SessionState {
+        SessionState {
+            elapsed_ms: 3_723_456,
+            laps_ms: vec![61_000, 122_500, 3_700_001],
+            paused,
+            start_timestamp_ms: 1_790_000_000_000,
+            projects_ms: vec![("review".to_string(), 60_000)],
+        }
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let path = temp_file("round-trip.bin");
+        save_state_binary(&path, &sample(true)).unwrap();
+        let loaded = load_state_binary(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(loaded.elapsed_ms, 3_723_456);
+        assert_eq!(loaded.laps_ms, [61_000, 122_500, 3_700_001]);
+        assert!(loaded.paused);
+        assert_eq!(loaded.start_timestamp_ms, 1_790_000_000_000);
+        assert!(loaded.projects_ms.is_empty());
+    }
+
+    #[test]
+    fn truncated_binary_is_rejected() {
+        let path = temp_file("truncated.bin");
+        save_state_binary(&path, &sample(false)).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        for len in [0, 3, 5, 14, bytes.len() - 1] {
+            std::fs::write(&path, &bytes[..len]).unwrap();
+            let result = load_state_binary(&path);
+            assert!(matches!(result, Err(SessionError::Truncated)), "at {}", len);
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn foreign_and_newer_files_are_rejected() {
+        let path = temp_file("foreign.bin");
+        std::fs::write(&path, b"PK\x03\x04 not a session").unwrap();
+        assert!(matches!(
+            load_state_binary(&path),
+            Err(SessionError::BadMagic)
+        ));
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&[VERSION + 1, 0]);
+        std::fs::write(&path, &bytes).unwrap();
+        let result = load_state_binary(&path);
+        let _ = std::fs::remove_file(&path);
+        assert!(matches!(result, Err(SessionError::UnsupportedVersion(2))));
+    }
+
+    #[test]
+    fn json_round_trip_keeps_projects() {
+        let loaded = parse_state_json(&state_json(&sample(true))).unwrap();
+        assert_eq!(loaded.elapsed_ms, 3_723_456);
+        assert_eq!(loaded.laps_ms, [61_000, 122_500, 3_700_001]);
+        assert!(loaded.paused);
+        assert_eq!(loaded.start_timestamp_ms, 1_790_000_000_000);
+        assert_eq!(loaded.projects_ms, [("review".to_string(), 60_000)]);
+    }
+
+    #[test]
+    fn running_json_session_counts_the_time_since_saving() {
+        let text = state_json(&sample(false));
+        let saved = json_u64(&text, "saved_at_ms").unwrap();
+        let text = text.replace(
+            &format!("\"saved_at_ms\":{}", saved),
+            &format!("\"saved_at_ms\":{}", saved - 60_000),
+        );
+        let loaded = parse_state_json(&text).unwrap();
+        assert!(loaded.elapsed_ms >= 3_723_456 + 60_000);
+    }
+
+    #[test]
+    fn bad_json_is_malformed() {
+        let text = state_json(&sample(true)).replace("\"paused\":true", "\"paused\":maybe");
+        assert!(matches!(
+            parse_state_json(&text),
+            Err(SessionError::Malformed(_))
+        ));
+        assert!(matches!(
+            parse_state_json("{\"version\":1}"),
+            Err(SessionError::Malformed(_))
+        ));
+    }
+}
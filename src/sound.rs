@@ -0,0 +1,103 @@
+use std::{
+    io::{stdout, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+};
+
+pub fn ring_bell() {
+    let mut stdout = stdout();
+    let _ = write!(stdout, "\x07");
+    let _ = stdout.flush();
+}
+
+// Audio feedback. Alarms ring the terminal bell unless a sound file is set;
+// laps are only audible with a file, so a busy session isn't a wall of
+// bells. The file is handed to an installed player, and when none can be
+// started alarms fall back to the bell.
+#[derive(Default)]
+pub struct Sound {
+    pub file: Option<PathBuf>,
+}
+
+impl Sound {
+    pub fn alarm(&self) {
+        if !self.file.as_deref().is_some_and(play_file) {
+            ring_bell();
+        }
+    }
+
+    pub fn lap(&self) {
+        if let Some(ref file) = self.file {
+            play_file(file);
+        }
+    }
+}
+
+// Starts the first player found and returns whether one started. The
+// player is reaped on its own thread so the draw loop never waits for it.
+fn play_file(path: &Path) -> bool {
+    for mut command in players(path) {
+        let spawned = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        if let Ok(mut child) = spawned {
+            thread::spawn(move || {
+                let _ = child.wait();
+            });
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(unix)]
+fn player(program: &str, args: &[&str], path: &Path) -> Command {
+    let mut command = Command::new(program);
+    command.args(args).arg(path);
+    command
+}
+
+// PipeWire, PulseAudio and libsndfile players take WAV and OGG; aplay only
+// WAV. ffplay comes last as it is the heaviest to start.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn players(path: &Path) -> Vec<Command> {
+    vec![
+        player("pw-play", &[], path),
+        player("paplay", &[], path),
+        player("aplay", &["-q"], path),
+        player(
+            "ffplay",
+            &["-nodisp", "-autoexit", "-loglevel", "quiet"],
+            path,
+        ),
+    ]
+}
+
+#[cfg(target_os = "macos")]
+fn players(path: &Path) -> Vec<Command> {
+    vec![player("afplay", &[], path)]
+}
+
+// SoundPlayer only decodes WAV. The path goes through the environment so
+// it needs no quoting inside the script.
+#[cfg(windows)]
+fn players(path: &Path) -> Vec<Command> {
+    let mut command = Command::new("powershell");
+    command
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "(New-Object Media.SoundPlayer $env:CHRONORUST_SOUND).PlaySync()",
+        ])
+        .env("CHRONORUST_SOUND", path);
+    vec![command]
+}
+
+#[cfg(not(any(unix, windows)))]
+fn players(_path: &Path) -> Vec<Command> {
+    Vec::new()
+}
@@ -0,0 +1,65 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use crate::clock;
+
+// Where the stopwatch is in its life. Active time is banked in
+// `accumulated` whenever counting stops, so a paused clock holds its exact
+// elapsed time and a running one adds the current stretch on top.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChronoState {
+    // Not started yet, or waiting out a prestart countdown
+    Idle,
+    Running {
+        since: Instant,
+        accumulated: Duration,
+    },
+    // `since` is when the pause began, for the pause budget
+    Paused {
+        accumulated: Duration,
+        since: Instant,
+    },
+}
+
+impl ChronoState {
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed_at(clock::now())
+    }
+
+    // The elapsed time as of `now`, for a reading taken together with the
+    // wall clock
+    pub fn elapsed_at(&self, now: Instant) -> Duration {
+        match *self {
+            ChronoState::Idle => Duration::ZERO,
+            ChronoState::Running { since, accumulated } => {
+                accumulated + now.saturating_duration_since(since)
+            }
+            ChronoState::Paused { accumulated, .. } => accumulated,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransitionError {
+    AlreadyStarted,
+    NotStarted,
+    AlreadyPaused,
+    NotPaused,
+    PauseBudgetUsed,
+    CountdownTooLong,
+}
+
+impl fmt::Display for TransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TransitionError::AlreadyStarted => write!(f, "already started"),
+            TransitionError::NotStarted => write!(f, "not started"),
+            TransitionError::AlreadyPaused => write!(f, "already paused"),
+            TransitionError::NotPaused => write!(f, "not paused"),
+            TransitionError::PauseBudgetUsed => write!(f, "pause budget used up"),
+            TransitionError::CountdownTooLong => write!(f, "countdown too long"),
+        }
+    }
+}
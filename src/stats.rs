@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+// Spread of a set of lap splits
+pub struct SplitStats {
+    pub count: usize,
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    pub median: Duration,
+    pub stddev: Duration,
+}
+
+// Population standard deviation; the median of an even count is the mean
+// of the two middle splits. None for no splits.
+pub fn split_stats(splits: &[Duration]) -> Option<SplitStats> {
+    let mut sorted = splits.to_vec();
+    sorted.sort();
+    let count = sorted.len();
+    let min = *sorted.first()?;
+    let max = *sorted.last()?;
+
+    let total: Duration = sorted.iter().sum();
+    let mean = total / count as u32;
+    let median = if count.is_multiple_of(2) {
+        (sorted[count / 2 - 1] + sorted[count / 2]) / 2
+    } else {
+        sorted[count / 2]
+    };
+
+    let mean_secs = mean.as_secs_f64();
+    let variance = sorted
+        .iter()
+        .map(|split| (split.as_secs_f64() - mean_secs).powi(2))
+        .sum::<f64>()
+        / count as f64;
+
+    Some(SplitStats {
+        count,
+        min,
+        max,
+        mean,
+        median,
+        stddev: Duration::from_secs_f64(variance.sqrt()),
+    })
+}
+
+// A split against the running average of the counted laps
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LapPace {
+    Faster,
+    // Within the tolerance either way
+    Even,
+    Slower,
+}
+
+// `tolerance` is a fraction of the average, 0.05 for 5%
+pub fn classify_pace(split: Duration, average: Duration, tolerance: f64) -> LapPace {
+    let margin = average.as_secs_f64() * tolerance;
+    let difference = split.as_secs_f64() - average.as_secs_f64();
+    if difference < -margin {
+        LapPace::Faster
+    } else if difference > margin {
+        LapPace::Slower
+    } else {
+        LapPace::Even
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::ms;
+
+    #[test]
+    fn pace_against_the_average() {
+        // 5% of a 60 s average is 3 s either way
+        let average = ms(60_000);
+        let cases = [
+            (ms(50_000), LapPace::Faster),
+            (ms(56_999), LapPace::Faster),
+            (ms(57_000), LapPace::Even),
+            (ms(60_000), LapPace::Even),
+            (ms(63_000), LapPace::Even),
+            (ms(63_001), LapPace::Slower),
+        ];
+        for (split, pace) in cases {
+            assert_eq!(classify_pace(split, average, 0.05), pace, "{:?}", split);
+        }
+    }
+
+    #[test]
+    fn no_tolerance_only_matches_the_average() {
+        let average = ms(1_000);
+        assert_eq!(classify_pace(ms(999), average, 0.0), LapPace::Faster);
+        assert_eq!(classify_pace(ms(1_000), average, 0.0), LapPace::Even);
+        assert_eq!(classify_pace(ms(1_001), average, 0.0), LapPace::Slower);
+        assert_eq!(classify_pace(ms(0), ms(0), 0.05), LapPace::Even);
+    }
+
+    #[test]
+    fn split_stats_of_an_even_count() {
+        let stats = split_stats(&[ms(4_000), ms(1_000), ms(3_000), ms(2_000)]).unwrap();
+        assert_eq!(stats.count, 4);
+        assert_eq!((stats.min, stats.max), (ms(1_000), ms(4_000)));
+        assert_eq!((stats.mean, stats.median), (ms(2_500), ms(2_500)));
+        assert_eq!(stats.stddev.as_millis(), 1_118);
+        assert!(split_stats(&[]).is_none());
+    }
+}
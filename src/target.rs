@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use crate::duration::parse_duration;
+
+// A goal for the session: a total time, and the cumulative time each lap
+// should be done by, like the splits of a speedrun
+#[derive(Clone, Default)]
+pub struct Target {
+    pub total: Option<Duration>,
+    pub laps: Vec<Duration>,
+}
+
+impl Target {
+    // The [target] config entries: `total` and a `laps` array
+    pub fn parse(total: Option<&str>, laps: &[&str]) -> Result<Self, String> {
+        let total = total
+            .map(|total| parse_duration(total).map_err(|err| format!("total: {}", err)))
+            .transpose()?;
+        let laps = laps
+            .iter()
+            .enumerate()
+            .map(|(index, lap)| {
+                parse_duration(lap).map_err(|err| format!("lap {}: {}", index + 1, err))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if laps.windows(2).any(|pair| pair[1] < pair[0]) {
+            return Err("lap targets must not go backwards".to_string());
+        }
+        Ok(Self { total, laps })
+    }
+
+    // How far ahead (true) or behind the goal the session is, with `laps`
+    // the cumulative lap times. Against lap targets that's the last lap's
+    // delta, unless the lap in progress is already past its own target.
+    // Without a lap target to go, the elapsed time is held against the
+    // total, or stays at the last lap's delta when there is none.
+    pub fn delta(&self, elapsed: Duration, laps: &[Duration]) -> Option<(Duration, bool)> {
+        let last = laps
+            .len()
+            .checked_sub(1)
+            .and_then(|index| Some(compare(laps[index], *self.laps.get(index)?)));
+        match self.laps.get(laps.len()) {
+            Some(&next) if elapsed > next => Some(compare(elapsed, next)),
+            Some(&next) => last.or(Some(compare(elapsed, next))),
+            None => match self.total {
+                Some(total) => Some(compare(elapsed, total)),
+                None => last,
+            },
+        }
+    }
+}
+
+fn compare(actual: Duration, target: Duration) -> (Duration, bool) {
+    if actual <= target {
+        (target - actual, true)
+    } else {
+        (actual - target, false)
+    }
+}
@@ -0,0 +1,88 @@
+use crossterm::{
+    cursor,
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use std::{
+    io::{self, stdout, Stdout},
+    panic,
+};
+
+// The full-screen terminal: raw mode, the alternate screen and, unless
+// --no-mouse, mouse capture. However the interface ends, by leave(), a
+// dropped guard on an error path or a panic, the terminal is put back the
+// way the shell had it. Ctrl+Z does the same before stopping the process.
+pub struct Tui {
+    pub terminal: Terminal<CrosstermBackend<Stdout>>,
+    mouse: bool,
+    restored: bool,
+}
+
+impl Tui {
+    pub fn enter(mouse: bool) -> io::Result<Self> {
+        // Restored before the message prints, or it'd go to the alternate
+        // screen and vanish with it
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = restore(mouse);
+            previous(info);
+        }));
+
+        setup(mouse)?;
+        let terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+        Ok(Self {
+            terminal,
+            mouse,
+            restored: false,
+        })
+    }
+
+    // Hands the terminal back and stops like Ctrl+Z outside raw mode would;
+    // returns once `fg` has continued the process
+    #[cfg(unix)]
+    pub fn suspend(&mut self) -> io::Result<()> {
+        restore(self.mouse)?;
+        signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+        self.resume()
+    }
+
+    // Also after a SIGCONT from outside, since the shell may have reset the
+    // terminal while the process was stopped
+    pub fn resume(&mut self) -> io::Result<()> {
+        setup(self.mouse)?;
+        self.terminal.clear()
+    }
+
+    // Restores the terminal, reporting what went wrong
+    pub fn leave(mut self) -> io::Result<()> {
+        self.restored = true;
+        restore(self.mouse)
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        if !self.restored {
+            let _ = restore(self.mouse);
+        }
+    }
+}
+
+fn setup(mouse: bool) -> io::Result<()> {
+    terminal::enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen)?;
+    if mouse {
+        execute!(stdout(), EnableMouseCapture)?;
+    }
+    Ok(())
+}
+
+fn restore(mouse: bool) -> io::Result<()> {
+    if mouse {
+        execute!(stdout(), DisableMouseCapture)?;
+    }
+    execute!(stdout(), LeaveAlternateScreen, cursor::Show)?;
+    terminal::disable_raw_mode()
+}
@@ -0,0 +1,180 @@
+use ratatui::style::Color;
+use std::str::FromStr;
+
+// What every widget is drawn with, by role rather than by color, so one
+// table switches the whole screen
+#[derive(Clone, Copy)]
+pub struct Theme {
+    // Title bar and the metronome flash
+    pub title: Color,
+    pub running: Color,
+    pub paused: Color,
+    // The "get ready" countdown before the start
+    pub prestart: Color,
+    // Final seconds, errors, the worst lap, falling behind, work phases
+    pub alert: Color,
+    // Between good and alert, e.g. a progress gauge past halfway
+    pub warning: Color,
+    // The best lap, running ahead, break phases
+    pub good: Color,
+    // A lap faster than the same lap of the --compare session
+    pub gold: Color,
+    // Stats and lap statistics panels
+    pub stats: Color,
+    // Charts, egg timers, world clock and the selected tab
+    pub accent: Color,
+    pub laps: Color,
+    // Text typed into prompts
+    pub text: Color,
+    // Controls bar and tab titles
+    pub muted: Color,
+    // Reference laps and other secondary figures
+    pub dim: Color,
+    // Monochrome drops every color, the heatmap included
+    pub colored: bool,
+}
+
+pub const THEME_NAMES: [&str; 5] = ["dark", "light", "solarized", "high-contrast", "monochrome"];
+
+const DARK: Theme = Theme {
+    title: Color::Cyan,
+    running: Color::Green,
+    paused: Color::Green,
+    prestart: Color::Yellow,
+    alert: Color::Red,
+    warning: Color::Yellow,
+    good: Color::Green,
+    gold: Color::LightYellow,
+    stats: Color::Magenta,
+    accent: Color::Cyan,
+    laps: Color::Yellow,
+    text: Color::White,
+    muted: Color::Gray,
+    dim: Color::DarkGray,
+    colored: true,
+};
+
+// Darker shades from the 256-color palette that keep their contrast on a
+// white background
+const LIGHT: Theme = Theme {
+    title: Color::Indexed(25),
+    running: Color::Indexed(28),
+    paused: Color::Indexed(130),
+    prestart: Color::Indexed(130),
+    alert: Color::Indexed(160),
+    warning: Color::Indexed(130),
+    good: Color::Indexed(28),
+    gold: Color::Indexed(136),
+    stats: Color::Indexed(90),
+    accent: Color::Indexed(25),
+    laps: Color::Indexed(94),
+    text: Color::Black,
+    muted: Color::Indexed(240),
+    dim: Color::Indexed(245),
+    colored: true,
+};
+
+// Solarized accent colors, readable on both the dark and light variants
+const SOLARIZED: Theme = Theme {
+    title: Color::Rgb(0x26, 0x8b, 0xd2),
+    running: Color::Rgb(0x85, 0x99, 0x00),
+    paused: Color::Rgb(0xb5, 0x89, 0x00),
+    prestart: Color::Rgb(0xcb, 0x4b, 0x16),
+    alert: Color::Rgb(0xdc, 0x32, 0x2f),
+    warning: Color::Rgb(0xb5, 0x89, 0x00),
+    good: Color::Rgb(0x85, 0x99, 0x00),
+    gold: Color::Rgb(0xcb, 0x4b, 0x16),
+    stats: Color::Rgb(0x6c, 0x71, 0xc4),
+    accent: Color::Rgb(0x2a, 0xa1, 0x98),
+    laps: Color::Rgb(0xb5, 0x89, 0x00),
+    text: Color::Rgb(0x93, 0xa1, 0xa1),
+    muted: Color::Rgb(0x83, 0x94, 0x96),
+    dim: Color::Rgb(0x58, 0x6e, 0x75),
+    colored: true,
+};
+
+const HIGH_CONTRAST: Theme = Theme {
+    title: Color::White,
+    running: Color::LightGreen,
+    paused: Color::LightYellow,
+    prestart: Color::LightYellow,
+    alert: Color::LightRed,
+    warning: Color::LightYellow,
+    good: Color::LightGreen,
+    gold: Color::LightYellow,
+    stats: Color::White,
+    accent: Color::LightCyan,
+    laps: Color::White,
+    text: Color::White,
+    muted: Color::White,
+    dim: Color::Gray,
+    colored: true,
+};
+
+const MONOCHROME: Theme = Theme {
+    colored: false,
+    ..DARK
+};
+
+impl Default for Theme {
+    fn default() -> Self {
+        DARK
+    }
+}
+
+impl Theme {
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(DARK),
+            "light" => Some(LIGHT),
+            "solarized" => Some(SOLARIZED),
+            "high-contrast" => Some(HIGH_CONTRAST),
+            "monochrome" => Some(MONOCHROME),
+            _ => None,
+        }
+    }
+
+    // A user theme from the [theme] config table: `base` picks the
+    // built-in to start from, every other key overrides one role with a
+    // color name, a "#rrggbb" value or a 256-color index
+    pub fn from_entries(entries: &[(&str, &str)]) -> Result<Self, String> {
+        let mut theme = match entries.iter().find(|&&(key, _)| key == "base") {
+            Some(&(_, base)) => Self::named(base).ok_or_else(|| unknown_theme(base))?,
+            None => DARK,
+        };
+        for &(key, value) in entries {
+            if key == "base" {
+                continue;
+            }
+            let color = Color::from_str(value)
+                .map_err(|_| format!("'{}' for '{}' is not a color", value, key))?;
+            let role = match key {
+                "title" => &mut theme.title,
+                "running" => &mut theme.running,
+                "paused" => &mut theme.paused,
+                "prestart" => &mut theme.prestart,
+                "alert" => &mut theme.alert,
+                "warning" => &mut theme.warning,
+                "good" => &mut theme.good,
+                "gold" => &mut theme.gold,
+                "stats" => &mut theme.stats,
+                "accent" => &mut theme.accent,
+                "laps" => &mut theme.laps,
+                "text" => &mut theme.text,
+                "muted" => &mut theme.muted,
+                "dim" => &mut theme.dim,
+                _ => return Err(format!("unknown theme color '{}'", key)),
+            };
+            *role = color;
+        }
+        Ok(theme)
+    }
+}
+
+pub fn unknown_theme(name: &str) -> String {
+    format!(
+        "unknown theme '{}', expected one of: {}",
+        name,
+        THEME_NAMES.join(", ")
+    )
+}
@@ -0,0 +1,106 @@
+use std::{env, fmt::Display};
+
+use chrono::{DateTime, TimeZone};
+
+// How wall-clock moments read in the log, the history list and the world
+// clock. --datetime-format, when given, still wins for the log and history.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum TimeFormat {
+    // 2026-10-14 17:05:09, as ChronoRust has always written
+    #[default]
+    Iso,
+    // The date order and clock LC_TIME's region uses, read when parsed
+    Locale {
+        date: &'static str,
+        twelve_hour: bool,
+    },
+    // 2026-10-14 05:05:09 PM
+    TwelveHour,
+}
+
+impl TimeFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "iso" => Some(TimeFormat::Iso),
+            "locale" => Some(Self::from_env()),
+            "12h" => Some(TimeFormat::TwelveHour),
+            _ => None,
+        }
+    }
+
+    // From LC_ALL, LC_TIME or LANG, in that order; C, POSIX and regions
+    // not listed keep the ISO layout
+    pub fn from_env() -> Self {
+        let locale = ["LC_ALL", "LC_TIME", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        let (date, twelve_hour) = conventions(&locale);
+        TimeFormat::Locale { date, twelve_hour }
+    }
+
+    // Date and time of day, as log lines and the history list show them
+    pub fn datetime<Tz: TimeZone>(self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        format!("{} {}", time.format(self.date()), self.time_of_day(time))
+    }
+
+    // The same to the millisecond, for lap lines
+    pub fn datetime_millis<Tz: TimeZone>(self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        let clock = if self.twelve_hour() {
+            "%I:%M:%S%.3f %p"
+        } else {
+            "%H:%M:%S%.3f"
+        };
+        format!("{} {}", time.format(self.date()), time.format(clock))
+    }
+
+    pub fn time_of_day<Tz: TimeZone>(self, time: &DateTime<Tz>) -> String
+    where
+        Tz::Offset: Display,
+    {
+        let clock = if self.twelve_hour() {
+            "%I:%M:%S %p"
+        } else {
+            "%H:%M:%S"
+        };
+        time.format(clock).to_string()
+    }
+
+    fn date(self) -> &'static str {
+        match self {
+            TimeFormat::Locale { date, .. } => date,
+            TimeFormat::Iso | TimeFormat::TwelveHour => "%Y-%m-%d",
+        }
+    }
+
+    fn twelve_hour(self) -> bool {
+        match self {
+            TimeFormat::Iso => false,
+            TimeFormat::Locale { twelve_hour, .. } => twelve_hour,
+            TimeFormat::TwelveHour => true,
+        }
+    }
+}
+
+// The date layout and clock of a locale name such as "en_US.UTF-8"
+fn conventions(locale: &str) -> (&'static str, bool) {
+    let name = locale.split(['.', '@']).next().unwrap_or_default();
+    let (language, region) = name.split_once('_').unwrap_or((name, ""));
+    match (language, region) {
+        (_, "US") => ("%m/%d/%Y", true),
+        ("en", "AU" | "IN" | "NZ" | "PH") => ("%d/%m/%Y", true),
+        ("en" | "fr" | "es" | "it" | "pt" | "el" | "ga", _) => ("%d/%m/%Y", false),
+        ("de" | "tr" | "ru" | "uk" | "pl" | "cs" | "sk" | "fi" | "nb" | "da" | "ro", _) => {
+            ("%d.%m.%Y", false)
+        }
+        ("nl", _) => ("%d-%m-%Y", false),
+        _ => ("%Y-%m-%d", false),
+    }
+}
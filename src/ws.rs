@@ -0,0 +1,101 @@
+use std::{
+    io::{self, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        mpsc::{self, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use chronorust::Chronometer;
+use tungstenite::{HandshakeError, Message, WebSocket};
+
+use crate::App;
+
+const TICK: Duration = Duration::from_millis(100);
+
+// Overlays are a handful of browser tabs; more than this are turned away
+const MAX_CLIENTS: usize = 32;
+
+// --ws-port: JSON text frames to every connected WebSocket client, for a
+// browser overlay to mirror the session timer (the first tab). A tick with
+// the state goes out every 100 ms, and each event the log records (laps,
+// pauses, resets, timers, ...) as it happens, sent down the returned
+// channel. tungstenite speaks the protocol on two threads: one accepts
+// connections and does the handshake, and one sends to everyone, dropping
+// clients that have gone. The draw loop only ever waits on the timer lock.
+pub fn serve(port: u16, timers: Arc<Mutex<App>>) -> io::Result<Sender<String>> {
+    let listener = TcpListener::bind(("127.0.0.1", port))?;
+    let clients: Arc<Mutex<Vec<WebSocket<TcpStream>>>> = Arc::new(Mutex::new(Vec::new()));
+
+    let accepted = Arc::clone(&clients);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if accepted.lock().unwrap().len() >= MAX_CLIENTS {
+                continue;
+            }
+            if let Some(client) = handshake(stream) {
+                accepted.lock().unwrap().push(client);
+            }
+        }
+    });
+
+    let (sender, receiver) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        let mut next_tick = Instant::now();
+        loop {
+            let wait = next_tick.saturating_duration_since(Instant::now());
+            let message = match receiver.recv_timeout(wait) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => {
+                    next_tick += TICK;
+                    tick_json(&timers.lock().unwrap().timers[0])
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            let message = Message::text(message);
+            clients
+                .lock()
+                .unwrap()
+                .retain_mut(|client| client.send(message.clone()).is_ok());
+        }
+    });
+    Ok(sender)
+}
+
+fn tick_json(chronometer: &Chronometer) -> String {
+    let elapsed = chronometer.elapsed();
+    format!(
+        "{{\"event\":\"tick\",\"state\":\"{}\",\"elapsed\":\"{}\",\"elapsed_ms\":{},\"laps\":{}}}",
+        chronometer.state_label(),
+        chronometer.display(),
+        elapsed.as_millis(),
+        chronometer.lap_durations.len()
+    )
+}
+
+// Answers the upgrade request; anything that isn't a WebSocket request gets
+// a 400. A client gets a few seconds to send it, so a stalled one holds up
+// the next connection no longer than that.
+fn handshake(stream: TcpStream) -> Option<WebSocket<TcpStream>> {
+    stream.set_read_timeout(Some(Duration::from_secs(2))).ok()?;
+    let mut reply = stream.try_clone().ok()?;
+    let client = match tungstenite::accept(stream) {
+        Ok(client) => client,
+        Err(HandshakeError::Failure(tungstenite::Error::Protocol(_))) => {
+            let _ = reply.write_all(
+                b"HTTP/1.1 400 Bad Request\r\nContent-Length: 24\r\n\r\nExpected a WebSocket key",
+            );
+            return None;
+        }
+        Err(_) => return None,
+    };
+    // A client that stops reading is dropped rather than stalling the rest
+    client
+        .get_ref()
+        .set_write_timeout(Some(Duration::from_secs(1)))
+        .ok()?;
+    Some(client)
+}
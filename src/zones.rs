@@ -0,0 +1,42 @@
+use chrono::Utc;
+use chrono_tz::Tz;
+use chronorust::timefmt::TimeFormat;
+
+// Resolves names like "UTC" and "America/New_York"; names chrono-tz doesn't
+// know are handed back so the caller can warn about them
+pub fn parse_zones(names: Vec<&str>) -> (Vec<Tz>, Vec<String>) {
+    let mut zones = Vec::new();
+    let mut unknown = Vec::new();
+    for name in names
+        .into_iter()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        match name.parse::<Tz>() {
+            Ok(zone) => zones.push(zone),
+            Err(_) => unknown.push(name.to_string()),
+        }
+    }
+    (zones, unknown)
+}
+
+// Current wall-clock time in each zone, names padded into a column
+pub fn zone_lines(zones: &[Tz], format: TimeFormat) -> Vec<String> {
+    let now = Utc::now();
+    let width = zones
+        .iter()
+        .map(|zone| zone.name().len())
+        .max()
+        .unwrap_or(0);
+    zones
+        .iter()
+        .map(|zone| {
+            format!(
+                "{:width$}  {}",
+                zone.name(),
+                format.time_of_day(&now.with_timezone(zone)),
+                width = width
+            )
+        })
+        .collect()
+}